@@ -0,0 +1,370 @@
+use crate::{
+    common::*, error::*, extra::*, input::*, number::PrimInt, pattern::*, predicate::ANY, primitive::TakeExact, private,
+};
+use core::{fmt, marker::PhantomData};
+
+/// Decodes a value from exactly `N` bytes, see the `le_*`, `be_*` and `ne_*` functions.
+pub struct FromBytes<T, const N: usize> {
+    take: TakeExact<u8, ANY, N>,
+    decode: fn([u8; N]) -> T,
+}
+
+impl<T, const N: usize> FromBytes<T, N> {
+    #[inline]
+    const fn new(decode: fn([u8; N]) -> T) -> Self {
+        Self {
+            take: TakeExact {
+                pred: ANY,
+                phantom: PhantomData,
+            },
+            decode,
+        }
+    }
+}
+
+impl<'src, I, Ext, T, const N: usize> Pattern<'src, I, Ext> for FromBytes<T, N>
+where
+    I: InputSlice<'src, Token = u8> + InputOwnableToken<'src>,
+    Ext: Extra<'src, I>,
+{
+    type View<'tmp>
+        = T
+    where
+        'src: 'tmp;
+
+    #[inline]
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        Pattern::<I, Ext>::__parse(&self.take, input, start, state, ctx, private::Token)
+            .raise_or_map(|(bytes, end)| ((self.decode)(bytes), end))
+    }
+
+    #[inline]
+    fn __check<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        Pattern::<I, Ext>::__check(&self.take, input, start, state, ctx, private::Token)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+macro_rules! gen_binary_patterns {
+    ( $(
+      $(#[$attr:meta])*
+        $f:ident, $ty:ty, $len:literal;
+    )* ) => { paste::paste! { $(
+      $(#[$attr])*
+        #[inline]
+        pub const fn [<$f _ $ty>]() -> FromBytes<$ty, $len> {
+            FromBytes::new($ty::[<from_ $f _bytes>])
+        }
+    )* } };
+}
+
+gen_binary_patterns! {
+    /** little-endian       signed  1 bytes integer */ le,   i8,  1;
+    /** little-endian       signed  2 bytes integer */ le,  i16,  2;
+    /** little-endian       signed  4 bytes integer */ le,  i32,  4;
+    /** little-endian       signed  8 bytes integer */ le,  i64,  8;
+    /** little-endian       signed 16 bytes integer */ le, i128, 16;
+    /** little-endian     unsigned  1 bytes integer */ le,   u8,  1;
+    /** little-endian     unsigned  2 bytes integer */ le,  u16,  2;
+    /** little-endian     unsigned  4 bytes integer */ le,  u32,  4;
+    /** little-endian     unsigned  8 bytes integer */ le,  u64,  8;
+    /** little-endian     unsigned 16 bytes integer */ le, u128, 16;
+    /** little-endian 4 bytes floating point number */ le,  f32,  4;
+    /** little-endian 8 bytes floating point number */ le,  f64,  8;
+
+    /**    big-endian       signed  1 bytes integer */ be,   i8,  1;
+    /**    big-endian       signed  2 bytes integer */ be,  i16,  2;
+    /**    big-endian       signed  4 bytes integer */ be,  i32,  4;
+    /**    big-endian       signed  8 bytes integer */ be,  i64,  8;
+    /**    big-endian       signed 16 bytes integer */ be, i128, 16;
+    /**    big-endian     unsigned  1 bytes integer */ be,   u8,  1;
+    /**    big-endian     unsigned  2 bytes integer */ be,  u16,  2;
+    /**    big-endian     unsigned  4 bytes integer */ be,  u32,  4;
+    /**    big-endian     unsigned  8 bytes integer */ be,  u64,  8;
+    /**    big-endian     unsigned 16 bytes integer */ be, u128, 16;
+    /**    big-endian 4 bytes floating point number */ be,  f32,  4;
+    /**    big-endian 8 bytes floating point number */ be,  f64,  8;
+
+    /** native-endian       signed  1 bytes integer */ ne,   i8,  1;
+    /** native-endian       signed  2 bytes integer */ ne,  i16,  2;
+    /** native-endian       signed  4 bytes integer */ ne,  i32,  4;
+    /** native-endian       signed  8 bytes integer */ ne,  i64,  8;
+    /** native-endian       signed 16 bytes integer */ ne, i128, 16;
+    /** native-endian     unsigned  1 bytes integer */ ne,   u8,  1;
+    /** native-endian     unsigned  2 bytes integer */ ne,  u16,  2;
+    /** native-endian     unsigned  4 bytes integer */ ne,  u32,  4;
+    /** native-endian     unsigned  8 bytes integer */ ne,  u64,  8;
+    /** native-endian     unsigned 16 bytes integer */ ne, u128, 16;
+    /** native-endian 4 bytes floating point number */ ne,  f32,  4;
+    /** native-endian 8 bytes floating point number */ ne,  f64,  8;
+}
+
+//------------------------------------------------------------------------------
+
+/// Unsigned LEB128, as used by WebAssembly and DWARF.
+///
+/// At most `ceil(T::BITS / 7)` bytes are accepted, and the bits beyond `T::BITS` must be zero.
+pub const fn uleb128<T: PrimInt>() -> VarInt<T> {
+    VarInt::new(Encoding::Unsigned, T::BITS)
+}
+
+/// Signed LEB128, as used by WebAssembly and DWARF.
+///
+/// At most `ceil(T::BITS / 7)` bytes are accepted, and the bits beyond `T::BITS` must be the sign extension.
+pub const fn sleb128<T: PrimInt>() -> VarInt<T> {
+    VarInt::new(Encoding::Signed, T::BITS)
+}
+
+/// Protocol Buffers varint, which is decoded as an `u64`, then truncated to `T` as `int32` fields do.
+pub const fn varint<T: PrimInt>() -> VarInt<T> {
+    assert!(T::BITS <= 64, "varints have at most 64 bits");
+    VarInt::new(Encoding::Unsigned, 64)
+}
+
+/// Protocol Buffers ZigZag varint, as used by `sint32` and `sint64` fields.
+pub const fn varint_zigzag<T: PrimInt>() -> VarInt<T> {
+    assert!(
+        T::SIGNED && T::BITS <= 64,
+        "ZigZag varints are signed and have at most 64 bits"
+    );
+    VarInt::new(Encoding::ZigZag, T::BITS)
+}
+
+/// QUIC variable-length integer (RFC 9000, Section 16): the two most significant bits
+/// of the first byte give the length of 1, 2, 4 or 8 bytes, followed by a big-endian 62 bits integer.
+pub const fn quic_varint() -> VarInt<u64> {
+    VarInt::new(Encoding::Quic, 62)
+}
+
+//------------------------------------------------------------------------------
+
+pub struct VarInt<T> {
+    encoding: Encoding,
+    width: u32,
+    canonical: bool,
+    phantom: PhantomData<T>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Unsigned,
+    Signed,
+    ZigZag,
+    Quic,
+}
+
+enum Decoded<T> {
+    Incomplete,
+    Done(T, usize),
+    Invalid(ErrorKind<'static>, usize),
+}
+
+impl<T: PrimInt> VarInt<T> {
+    const fn new(encoding: Encoding, width: u32) -> Self {
+        Self {
+            encoding,
+            width,
+            canonical: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Also rejects encodings longer than necessary, as [`ErrorKind::Overlong`].
+    pub const fn canonical(mut self) -> Self {
+        self.canonical = true;
+        self
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Decoded<T> {
+        match self.encoding {
+            Encoding::Quic => self.decode_quic(bytes),
+            _ => self.decode_leb128(bytes),
+        }
+    }
+
+    fn decode_leb128(&self, bytes: &[u8]) -> Decoded<T> {
+        let max_len = self.width.div_ceil(7) as usize;
+        let mut acc = 0u128;
+        let mut shift = 0;
+        let mut prev = 0;
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            if i == max_len {
+                return Decoded::Invalid(ErrorKind::Overlong, i + 1);
+            }
+
+            let low = byte & 0x7f;
+            if shift + 7 > self.width {
+                let used = self.width - shift;
+                let extension = match self.encoding == Encoding::Signed && (low >> (used - 1)) & 1 == 1 {
+                    true => 0x7f >> used,
+                    false => 0,
+                };
+                if low >> used != extension {
+                    return Decoded::Invalid(ErrorKind::Overflow, i + 1);
+                }
+            }
+
+            acc |= (low as u128) << shift;
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                let redundant = match self.encoding {
+                    Encoding::Signed => low == 0 && prev & 0x40 == 0 || low == 0x7f && prev & 0x40 != 0,
+                    _ => low == 0,
+                };
+                if self.canonical && i > 0 && redundant {
+                    return Decoded::Invalid(ErrorKind::Overlong, i + 1);
+                }
+
+                let bits = match self.encoding {
+                    Encoding::Signed if shift < 128 && low & 0x40 != 0 => acc | (!0 << shift),
+                    Encoding::ZigZag => ((acc >> 1) as i128 ^ -((acc & 1) as i128)) as u128,
+                    _ => acc,
+                };
+                return Decoded::Done(T::from_bits(bits), i + 1);
+            }
+
+            prev = low;
+        }
+
+        Decoded::Incomplete
+    }
+
+    fn decode_quic(&self, bytes: &[u8]) -> Decoded<T> {
+        let Some(first) = bytes.first() else {
+            return Decoded::Incomplete;
+        };
+
+        let len = 1 << (first >> 6);
+        let Some(bytes) = bytes.get(..len) else {
+            return Decoded::Incomplete;
+        };
+
+        let val = bytes[1..]
+            .iter()
+            .fold((first & 0x3f) as u128, |acc, &b| acc << 8 | b as u128);
+
+        // The largest value the half length is able to hold.
+        let half = (1u128 << (len * 4 - 2)) - 1;
+        if self.canonical && len > 1 && val <= half {
+            return Decoded::Invalid(ErrorKind::Overlong, len);
+        }
+
+        Decoded::Done(T::from_bits(val), len)
+    }
+}
+
+impl<T> Describe for VarInt<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.encoding {
+            Encoding::Unsigned => write!(f, "an unsigned LEB128 integer"),
+            Encoding::Signed => write!(f, "a signed LEB128 integer"),
+            Encoding::ZigZag => write!(f, "a ZigZag varint"),
+            Encoding::Quic => write!(f, "a QUIC varint"),
+        }
+    }
+}
+
+impl<'src, I, Ext, T> Pattern<'src, I, Ext> for VarInt<T>
+where
+    I: InputByteSlice<'src, Token = u8>,
+    Ext: Extra<'src, I>,
+    T: PrimInt,
+{
+    type View<'tmp>
+        = T
+    where
+        'src: 'tmp;
+
+    #[inline]
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        __decode(self, input, start.clone(), |bytes| self.decode(bytes)).raise_or_map(|(val, end)| {
+            input.release_slice(start..end.clone());
+            (val, end)
+        })
+    }
+
+    #[inline]
+    fn __check<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        __decode(self, input, start, |bytes| self.decode(bytes)).raise_or_map(|(_, end)| end)
+    }
+}
+
+#[inline]
+fn __decode<'src, I, E, T, D>(
+    desc: &dyn Describe,
+    input: &mut I,
+    start: I::Cursor,
+    decode: D,
+) -> PResult<(T, I::Cursor), E>
+where
+    I: InputByteSlice<'src>,
+    E: Error,
+    D: Fn(&[u8]) -> Decoded<T>,
+{
+    let mut more = false;
+    loop {
+        let (bytes, eof) = match more {
+            false => trip!(input.fetch_byte_slice(start.clone())),
+            true => trip!(input.fetch_more_byte_slice(start.clone())),
+        };
+
+        return match decode(bytes) {
+            Decoded::Incomplete if !eof => {
+                more = true;
+                continue;
+            }
+            Decoded::Incomplete => {
+                let end = I::bump_cursor(start.clone(), bytes.len());
+                PResult::raise(E::new(I::span(start..end), ErrorKind::Expected(desc)))
+            }
+            Decoded::Done(val, len) => PResult::emit((val, I::bump_cursor(start, len))),
+            Decoded::Invalid(kind, len) => {
+                let end = I::bump_cursor(start.clone(), len);
+                PResult::raise(E::new(I::span(start..end), kind))
+            }
+        };
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{common::*, error::*, extra::*, input::*, parser::*, pattern::*, private};
+use core::{fmt, marker::PhantomData};
+
+/// Tries each parser of the tuple in order, and yields the output of the first succeeded one.
+///
+/// If all of them fail, their errors are merged.
+pub const fn alt<A>(alt: A) -> Alt<A> {
+    Alt { alt }
+}
+
+/// Parses a length with `len`, then parses `body` on the input truncated to that many tokens after it.
+///
+/// The body sees the end of input at the declared length, and shall consume all of it.
+///
+/// ```
+/// use kaparser::{binary::*, combinator::*, error::EmptyErr, parser::*, pattern::*};
+///
+/// let record = length_prefixed(be_u16().lift(usize::from), le_u32().captured());
+/// let res = Parser::<&[u8], u32, EmptyErr>::fullmatch(&record, &[0, 4, 0xef, 0xbe, 0xad, 0xde]);
+/// assert_eq!(res.into_output(), Some(0xdead_beef));
+/// ```
+pub fn length_prefixed<'src, I, Ext, L, B, O>(len: L, body: B) -> impl Parser<'src, I, O, Ext>
+where
+    I: InputTruncatable<'src>,
+    Ext: Extra<'src, I>,
+    L: Parser<'src, I, usize, Ext>,
+    B: Parser<'src, I, O, Ext>,
+{
+    LengthPrefixed {
+        len,
+        body,
+        phantom: PhantomData,
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub struct Alt<A> {
+    alt: A,
+}
+
+macro_rules! impl_alt_for_tuple {
+    ( $Len:literal, $($OrdN:literal ~ ($GenN:ident) ~ $_gen:ident ~ $_con:ident ~ $IdxN:tt)+ ) => {
+        impl<'src, I, Ext, O, $($GenN),+> Parser<'src, I, O, Ext> for Alt<($($GenN,)+)>
+        where
+            I: Input<'src>,
+            Ext: Extra<'src, I>,
+          $($GenN: Parser<'src, I, O, Ext>,)+
+        {
+            fn __parse(
+                &self,
+                input: &mut I,
+                start: I::Cursor,
+                mut state: MaybeMut<Ext::State>,
+                ctx: MaybeRef<Ext::Context>,
+                _: private::Token,
+            ) -> PResult<(O, I::Cursor), Ext::Error> {
+                let mut error = None::<Ext::Error>;
+              $(
+                let res = self.alt.$IdxN.__parse(input, start.clone(), (&mut *state).into(), (&*ctx).into(), private::Token);
+                match res.has_output() {
+                    true => return res,
+                    false => error = __merge(error, res.into_error()),
+                }
+              )+
+                PResult { value: None, error }
+            }
+
+            fn __check(
+                &self,
+                input: &mut I,
+                start: I::Cursor,
+                mut state: MaybeMut<Ext::State>,
+                ctx: MaybeRef<Ext::Context>,
+                _: private::Token,
+            ) -> PResult<I::Cursor, Ext::Error> {
+                let mut error = None::<Ext::Error>;
+              $(
+                let res = self.alt.$IdxN.__check(input, start.clone(), (&mut *state).into(), (&*ctx).into(), private::Token);
+                match res.has_output() {
+                    true => return res,
+                    false => error = __merge(error, res.into_error()),
+                }
+              )+
+                PResult { value: None, error }
+            }
+        }
+    };
+}
+
+__generate_codes! { impl_alt_for_tuple ( P ) }
+
+#[inline]
+fn __merge<E: Error>(prev: Option<E>, next: Option<E>) -> Option<E> {
+    match (prev, next) {
+        (Some(e1), Some(e2)) => Some(e1.merge(e2)),
+        (e1, e2) => e1.or(e2),
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub struct ThenWith<P, F, O> {
+    pub(crate) parser: P,
+    pub(crate) f: F,
+    pub(crate) phantom: PhantomData<O>,
+}
+
+impl<'src, I, Ext, P, F, O, P2, O2> Parser<'src, I, O2, Ext> for ThenWith<P, F, O>
+where
+    I: Input<'src>,
+    Ext: Extra<'src, I>,
+    P: Parser<'src, I, O, Ext>,
+    F: Fn(O) -> P2,
+    P2: Parser<'src, I, O2, Ext>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(O2, I::Cursor), Ext::Error> {
+        let res = self
+            .parser
+            .__parse(input, start, (&mut *state).into(), (&*ctx).into(), private::Token);
+        __and_then(res, |(out, cur)| {
+            (self.f)(out).__parse(input, cur, state, ctx, private::Token)
+        })
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        let res = self
+            .parser
+            .__parse(input, start, (&mut *state).into(), (&*ctx).into(), private::Token);
+        __and_then(res, |(out, cur)| {
+            (self.f)(out).__check(input, cur, state, ctx, private::Token)
+        })
+    }
+}
+
+/// Continues with the output, and keeps the recoverable error along with the ones raised later.
+#[inline]
+fn __and_then<T, U, E: Error>(res: PResult<T, E>, f: impl FnOnce(T) -> PResult<U, E>) -> PResult<U, E> {
+    let PResult { value, error } = res;
+    match value {
+        Some(val) => {
+            let next = f(val);
+            PResult {
+                value: next.value,
+                error: __merge(error, next.error),
+            }
+        }
+        None => PResult { value: None, error },
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub struct LengthPrefixed<L, B, O> {
+    len: L,
+    body: B,
+    phantom: PhantomData<O>,
+}
+
+struct Tokens(usize);
+
+impl Describe for Tokens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} more tokens", self.0)
+    }
+}
+
+impl<L, B, O> LengthPrefixed<L, B, O> {
+    #[inline]
+    fn __truncate<'src, I, Ext>(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+    ) -> PResult<(I, I::Cursor, I::Cursor), Ext::Error>
+    where
+        I: InputTruncatable<'src>,
+        Ext: Extra<'src, I>,
+        L: Parser<'src, I, usize, Ext>,
+    {
+        let res = self.len.__parse(input, start, state, ctx, private::Token);
+        __and_then(res, |(len, cur)| {
+            let end = I::bump_cursor(cur.clone(), len);
+            match input.truncate(end.clone()) {
+                Some(sub) => PResult::emit((sub, cur, end)),
+                None => PResult::raise(Ext::Error::new(I::span(cur..end), ErrorKind::Expected(&Tokens(len)))),
+            }
+        })
+    }
+}
+
+impl<'src, I, Ext, L, B, O> Parser<'src, I, O, Ext> for LengthPrefixed<L, B, O>
+where
+    I: InputTruncatable<'src>,
+    Ext: Extra<'src, I>,
+    L: Parser<'src, I, usize, Ext>,
+    B: Parser<'src, I, O, Ext>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(O, I::Cursor), Ext::Error> {
+        let res = self.__truncate(input, start, (&mut *state).into(), (&*ctx).into());
+        __and_then(res, |(mut sub, cur, end)| {
+            self.body
+                .__parse(&mut sub, cur, state, ctx, private::Token)
+                .verify_map(|(out, cur)| ((out, end), sub.shall_reached_end(cur)))
+        })
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        let res = self.__truncate(input, start, (&mut *state).into(), (&*ctx).into());
+        __and_then(res, |(mut sub, cur, end)| {
+            self.body
+                .__check(&mut sub, cur, state, ctx, private::Token)
+                .verify_map(|cur| (end, sub.shall_reached_end(cur)))
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub struct AndParseInner<P, Q, O> {
+    pub(crate) pattern: P,
+    pub(crate) inner: Q,
+    pub(crate) phantom: PhantomData<O>,
+}
+
+impl<P, Q, O> AndParseInner<P, Q, O> {
+    #[inline]
+    fn __truncate<'src, I, Ext>(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+    ) -> PResult<(I, I::Cursor), Ext::Error>
+    where
+        I: InputTruncatable<'src>,
+        Ext: Extra<'src, I>,
+        P: Pattern<'src, I, Ext>,
+    {
+        let res = self.pattern.__check(input, start, state, ctx, private::Token);
+        __and_then(res, |end| match input.truncate(end.clone()) {
+            Some(sub) => PResult::emit((sub, end)),
+            None => PResult::raise(Ext::Error::new(I::offset_span(end), ErrorKind::InvalidInput)),
+        })
+    }
+}
+
+impl<'src, I, Ext, P, Q, O> Parser<'src, I, O, Ext> for AndParseInner<P, Q, O>
+where
+    I: InputTruncatable<'src>,
+    Ext: Extra<'src, I>,
+    P: Pattern<'src, I, Ext>,
+    Q: Parser<'src, I, O, Ext>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(O, I::Cursor), Ext::Error> {
+        let res = self.__truncate(input, start.clone(), (&mut *state).into(), (&*ctx).into());
+        __and_then(res, |(mut sub, end)| {
+            self.inner
+                .__parse(&mut sub, start, state, ctx, private::Token)
+                .verify_map(|(out, cur)| ((out, end), sub.shall_reached_end(cur)))
+        })
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        let res = self.__truncate(input, start.clone(), (&mut *state).into(), (&*ctx).into());
+        __and_then(res, |(mut sub, end)| {
+            self.inner
+                .__check(&mut sub, start, state, ctx, private::Token)
+                .verify_map(|cur| (end, sub.shall_reached_end(cur)))
+        })
+    }
+}
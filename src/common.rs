@@ -187,6 +187,7 @@ impl<T, E> PResult<T, E> {
     }
 
     #[inline]
+    #[allow(dead_code)]
     pub(crate) fn map_err<F, E2>(self, f: F) -> PResult<T, E2>
     where
         F: FnOnce(E) -> E2,
@@ -214,16 +215,6 @@ impl<T, E> PResult<T, E> {
     }
 }
 
-impl<T, E> PResult<Option<T>, E> {
-    #[inline]
-    pub(crate) fn flatten(self) -> PResult<T, E> {
-        PResult {
-            value: self.value.flatten(),
-            error: self.error,
-        }
-    }
-}
-
 impl<T, E> From<Result<T, E>> for PResult<T, E> {
     #[inline]
     fn from(res: Result<T, E>) -> Self {
@@ -392,6 +383,7 @@ macro_rules! trip {
 
 //------------------------------------------------------------------------------
 
+#[allow(unused_macros)]
 macro_rules! coerce_dyn {
     (   $expr:expr => $trait:path $(=> $traits:path)* ) => {
         coerce_dyn! { @  $expr as &dyn $trait $(=> $traits)* }
@@ -20,6 +20,8 @@ pub trait Error: Sized + Debug {
 pub enum ErrorKind<'a> {
     Expected(&'a dyn Describe),
     ExpectedEnd,
+    Overflow,
+    Overlong,
     Other(&'a dyn core::error::Error),
 
     // non-fatal kinds.
@@ -31,6 +33,8 @@ impl<'a> Describe for ErrorKind<'a> {
         match self {
             ErrorKind::Expected(pat) => write!(f, "expected {}", pat),
             ErrorKind::ExpectedEnd => write!(f, "expected end of input"),
+            ErrorKind::Overflow => write!(f, "number too large to fit in target type"),
+            ErrorKind::Overlong => write!(f, "overlong encoding"),
             ErrorKind::Other(err) => write!(f, "error: {}", err),
 
             ErrorKind::InvalidInput => write!(f, "invalid input"),
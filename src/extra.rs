@@ -2,9 +2,13 @@ use crate::{
     common::{MaybeMut, MaybeRef},
     error::{EmptyErr, Error},
     input::*,
+    source::{SourceId, SourceSpan},
 };
 use core::{marker::PhantomData, ops::Range};
 
+#[cfg(feature = "alloc")]
+use crate::location::{ColumnUnit, LineIndex, Span};
+
 pub type State<S> = Full<EmptyErr, S, ()>;
 
 pub type Context<C> = Full<EmptyErr, (), C>;
@@ -68,6 +72,22 @@ where
     pub fn offset(&self) -> usize {
         I::offset(self.range.start.clone())
     }
+    /// Locates the span with the [`LineIndex`] in context,
+    /// returns `None` if the input is not the indexed text.
+    #[cfg(feature = "alloc")]
+    pub fn located_span(&self, unit: ColumnUnit) -> Option<Span>
+    where
+        Ext::Context: AsRef<LineIndex<'src>>,
+    {
+        self.context.as_ref().span(self.span(), unit)
+    }
+    /// Tags the span with the id of the source in context, see [`SourceMap`](crate::source::SourceMap).
+    pub fn source_span(&self) -> SourceSpan
+    where
+        Ext::Context: AsRef<SourceId>,
+    {
+        SourceSpan::new(*self.context.as_ref(), self.span())
+    }
 
     pub fn slice(&self) -> &'tmp I::Slice
     where
@@ -0,0 +1,386 @@
+//! Ready-made grammars of common formats.
+//!
+//! Each is a pattern or parser like any other, so it can be combined with the rest of the crate.
+//! Short tokens are recognized by a scanner over the fetched bytes, and larger structures are parsed
+//! by recursive descent over the crate's own patterns, while aiming to be precise on errors.
+
+use crate::{common::*, error::*, extra::*, input::*, pattern::*, private};
+use core::{fmt, ops::Range};
+
+/// Implements [`Pattern`](crate::pattern::Pattern) for short tokens recognized by the `Scanner` of the module,
+/// which shall have `Scanner::new(bytes)` and the fields `pos` and `hit_end`.
+///
+/// The scanner is rerun on more bytes while it hits the end of what has been fetched,
+/// so such patterns also work over streaming inputs.
+macro_rules! impl_pattern_for_scanner {
+    ($($ty:ty => $out:ty, |$this:ident, $scanner:ident| $scan:expr;)*) => {$(
+        impl<'src, I, Ext> $crate::pattern::Pattern<'src, I, Ext> for $ty
+        where
+            I: $crate::input::InputByteSlice<'src>,
+            Ext: $crate::extra::Extra<'src, I>,
+        {
+            type View<'tmp>
+                = $out
+            where
+                'src: 'tmp;
+
+            #[inline]
+            fn __parse<'tmp>(
+                &self,
+                input: &'tmp mut I,
+                start: I::Cursor,
+                _state: $crate::common::MaybeMut<Ext::State>,
+                _ctx: $crate::common::MaybeRef<Ext::Context>,
+                _: $crate::private::Token,
+            ) -> $crate::common::PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+            where
+                'src: 'tmp,
+            {
+                let $this = self;
+                $crate::grammars::scan_prefix(input, start.clone(), |bytes| {
+                    let mut $scanner = Scanner::new(bytes);
+                    let res = $scan;
+                    (res, $scanner.pos, $scanner.hit_end)
+                })
+                .raise_or_map(|(val, end)| {
+                    input.release_slice(start..end.clone());
+                    (val, end)
+                })
+            }
+
+            #[inline]
+            fn __check<'tmp>(
+                &self,
+                input: &'tmp mut I,
+                start: I::Cursor,
+                _state: $crate::common::MaybeMut<Ext::State>,
+                _ctx: $crate::common::MaybeRef<Ext::Context>,
+                _: $crate::private::Token,
+            ) -> $crate::common::PResult<I::Cursor, Ext::Error>
+            where
+                'src: 'tmp,
+            {
+                let $this = self;
+                $crate::grammars::scan_prefix(input, start, |bytes| {
+                    let mut $scanner = Scanner::new(bytes);
+                    let res = $scan;
+                    (res, $scanner.pos, $scanner.hit_end)
+                })
+                .raise_or_map(|(_, end)| end)
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "alloc")]
+pub mod config;
+#[cfg(feature = "alloc")]
+pub mod csv;
+pub mod datetime;
+#[cfg(feature = "alloc")]
+pub mod http1;
+#[cfg(feature = "alloc")]
+pub mod json;
+pub mod net;
+#[cfg(feature = "alloc")]
+pub mod resp;
+pub mod semver;
+#[cfg(feature = "alloc")]
+pub mod toml;
+pub mod uri;
+
+//------------------------------------------------------------------------------
+
+/// Fixed description of what is expected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expect(pub &'static str);
+
+impl Describe for Expect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Failure of a scanner, at the offsets relative to where it started.
+#[derive(Debug, Clone)]
+pub(crate) struct Failure {
+    pub(crate) range: Range<usize>,
+    pub(crate) kind: FailureKind,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum FailureKind {
+    Expected(Expect),
+    Invalid,
+    Overflow,
+    Other(&'static (dyn core::error::Error + Sync)),
+}
+
+impl Failure {
+    pub(crate) fn expected(at: usize, what: &'static str) -> Self {
+        Self {
+            range: at..at,
+            kind: FailureKind::Expected(Expect(what)),
+        }
+    }
+
+    pub(crate) fn invalid(range: Range<usize>) -> Self {
+        Self {
+            range,
+            kind: FailureKind::Invalid,
+        }
+    }
+
+    pub(crate) fn raise<'src, I: InputSlice<'src>, E: Error>(self, start: I::Cursor) -> E {
+        let range = I::bump_cursor(start.clone(), self.range.start)..I::bump_cursor(start, self.range.end);
+        let kind = match &self.kind {
+            FailureKind::Expected(what) => ErrorKind::Expected(what),
+            FailureKind::Invalid => ErrorKind::InvalidInput,
+            FailureKind::Overflow => ErrorKind::Overflow,
+            FailureKind::Other(err) => ErrorKind::Other(*err),
+        };
+        E::new(I::span(range), kind)
+    }
+}
+
+/// Drives patterns over the input, for grammars parsed by recursive descent.
+pub(crate) struct Driver<'a, 'src, I, Ext>
+where
+    I: Input<'src>,
+    Ext: Extra<'src, I>,
+{
+    pub(crate) input: &'a mut I,
+    pub(crate) state: &'a mut Ext::State,
+    pub(crate) ctx: &'a Ext::Context,
+}
+
+pub(crate) type Res<'src, I, Ext, T> = Result<(T, <I as Input<'src>>::Cursor), <Ext as Extra<'src, I>>::Error>;
+
+impl<'src, I, Ext> Driver<'_, 'src, I, Ext>
+where
+    I: Input<'src>,
+    Ext: Extra<'src, I>,
+{
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn check<P: Pattern<'src, I, Ext>>(
+        &mut self,
+        pattern: &P,
+        cur: I::Cursor,
+    ) -> Result<I::Cursor, Ext::Error> {
+        pattern
+            .__check(
+                self.input,
+                cur,
+                (&mut *self.state).into(),
+                self.ctx.into(),
+                private::Token,
+            )
+            .into_result()
+    }
+
+    /// Matches one of the literals, and yields its value.
+    #[inline]
+    pub(crate) fn dispatch<V: Clone, const N: usize>(
+        &mut self,
+        table: &crate::literal::OneOfLiterals<'static, V, N>,
+        cur: I::Cursor,
+    ) -> Res<'src, I, Ext, V>
+    where
+        I: InputByteSlice<'src>,
+    {
+        Pattern::<I, Ext>::__parse(
+            table,
+            self.input,
+            cur,
+            (&mut *self.state).into(),
+            self.ctx.into(),
+            private::Token,
+        )
+        .into_result()
+    }
+
+    #[inline]
+    pub(crate) fn capture<P: Pattern<'src, I, Ext>>(
+        &mut self,
+        pattern: P,
+        cur: I::Cursor,
+    ) -> Res<'src, I, Ext, P::View<'src>>
+    where
+        I: StaticInput,
+    {
+        use crate::parser::Parser;
+
+        pattern
+            .captured()
+            .__parse(
+                self.input,
+                cur,
+                (&mut *self.state).into(),
+                self.ctx.into(),
+                private::Token,
+            )
+            .into_result()
+    }
+}
+
+/// Unlike [`Predicate::take`](crate::predicate::Predicate::take), the view is known to be a slice,
+/// which can be captured.
+pub(crate) const fn take<Token, Pred, R>(pred: Pred, range: R) -> crate::primitive::Take<Token, Pred, R> {
+    crate::primitive::Take {
+        pred,
+        range,
+        phantom: core::marker::PhantomData,
+    }
+}
+
+pub(crate) fn expected<'src, I: Input<'src>, E: Error>(cur: I::Cursor, what: &'static str) -> E {
+    E::new(I::offset_span(cur), ErrorKind::Expected(&Expect(what)))
+}
+
+pub(crate) fn invalid<'src, I: Input<'src>, E: Error>(range: Range<I::Cursor>) -> E {
+    E::new(I::span(range), ErrorKind::InvalidInput)
+}
+
+/// Validates the bytes captured from `start`, and raises at the first invalid sequence.
+pub(crate) fn utf8<'src, I: InputSlice<'src>, E: Error>(bytes: &'src [u8], start: I::Cursor) -> Result<&'src str, E> {
+    core::str::from_utf8(bytes).map_err(|e| {
+        let at = I::bump_cursor(start, e.valid_up_to());
+        let end = I::bump_cursor(at.clone(), e.error_len().unwrap_or(bytes.len() - e.valid_up_to()));
+        invalid::<I, _>(at..end)
+    })
+}
+
+/// Matches up to the cursor, which has been reached by other patterns, and views the slice in between.
+pub(crate) struct SliceTo<C>(pub(crate) C);
+
+impl<'src, I, Ext> Pattern<'src, I, Ext> for SliceTo<I::Cursor>
+where
+    I: InputSlice<'src>,
+    Ext: Extra<'src, I>,
+{
+    type View<'tmp>
+        = &'tmp I::Slice
+    where
+        'src: 'tmp;
+
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        PResult::emit((input.release_slice(start..self.0.clone()), self.0.clone()))
+    }
+
+    fn __check<'tmp>(
+        &self,
+        _input: &'tmp mut I,
+        _start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        PResult::emit(self.0.clone())
+    }
+}
+
+/// Captures all the rest of input as bytes.
+pub(crate) fn rest_bytes<'src, I, E>(input: &mut I, start: I::Cursor) -> Result<&'src [u8], E>
+where
+    I: InputByteSlice<'src> + StaticInput,
+    E: Error,
+{
+    let mut driver = Driver::<I, E> {
+        input,
+        state: &mut (),
+        ctx: &(),
+    };
+    driver.capture(RestBytes, start).map(|(bytes, _)| bytes)
+}
+
+struct RestBytes;
+
+impl<'src, I, Ext> Pattern<'src, I, Ext> for RestBytes
+where
+    I: InputByteSlice<'src>,
+    Ext: Extra<'src, I>,
+{
+    type View<'tmp>
+        = &'tmp [u8]
+    where
+        'src: 'tmp;
+
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        let (_, mut eof) = trip!(input.fetch_byte_slice::<Ext::Error>(start.clone()));
+        while !eof {
+            (_, eof) = trip!(input.fetch_more_byte_slice::<Ext::Error>(start.clone()));
+        }
+        let (bytes, _) = trip!(input.fetch_byte_slice(start.clone()));
+        let end = I::bump_cursor(start, bytes.len());
+        PResult::emit((bytes, end))
+    }
+
+    fn __check<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        Pattern::<I, Ext>::__parse(self, input, start, state, ctx, private::Token).raise_or_map(|(_, end)| end)
+    }
+}
+
+/// See [`impl_pattern_for_scanner`], where `scan` returns the result, the position and whether it hit the end.
+#[inline]
+pub(crate) fn scan_prefix<'src, I, E, T>(
+    input: &mut I,
+    start: I::Cursor,
+    scan: impl Fn(&[u8]) -> (Result<T, Failure>, usize, bool),
+) -> PResult<(T, I::Cursor), E>
+where
+    I: InputByteSlice<'src>,
+    E: Error,
+{
+    let mut more = false;
+    loop {
+        let (bytes, eof) = match more {
+            false => trip!(input.fetch_byte_slice(start.clone())),
+            true => trip!(input.fetch_more_byte_slice(start.clone())),
+        };
+        let (res, pos, hit_end) = scan(bytes);
+
+        if hit_end && !eof {
+            more = true;
+            continue;
+        }
+
+        return match res {
+            Ok(val) => PResult::emit((val, I::bump_cursor(start, pos))),
+            Err(failure) => PResult::raise(failure.raise::<I, _>(start)),
+        };
+    }
+}
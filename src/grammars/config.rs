@@ -0,0 +1,644 @@
+//! Line-oriented configuration files: INI, Java `.properties` and dotenv.
+//!
+//! ```
+//! use kaparser::{error::EmptyErr, grammars::config::*, parser::*};
+//!
+//! let res = Parser::<&str, Document, EmptyErr>::fullmatch(&ini(), "[server]\nhost = example.com\nurl = http://${host}/\n");
+//! let doc = res.into_output().unwrap();
+//! assert_eq!(doc.get(Some("server"), "host"), Some("example.com"));
+//! assert_eq!(doc.entry(Some("server"), "url").unwrap().interpolations[0].name, "host");
+//! ```
+//!
+//! Line breaks are either `\n` or `\r\n`. Keys and values are borrowed unless they are spliced
+//! from several lines or contain escapes, and `${VAR}` references are reported with their spans
+//! but left for the caller to expand.
+
+use crate::{common::*, extra::*, grammars::*, literal::*, parser::*, predicate::*, private};
+use alloc::{borrow::Cow, vec::Vec};
+use core::ops::Range;
+
+/// Sections in `[brackets]`, `=` or `:` separators, full line comments with `;` or `#`,
+/// and inline comments after whitespace in unquoted values.
+///
+/// Values are either unquoted, where a trailing `\` continues on the next line,
+/// `"double quoted"` with backslash escapes, or `'single quoted'` taken literally.
+pub const fn ini() -> Config {
+    Config { dialect: Dialect::Ini }
+}
+
+/// Java `.properties`, where `=`, `:` or whitespace separates the key and the value,
+/// comments start with `#` or `!`, an odd number of trailing `\` continues on the next line,
+/// and both keys and values may contain escapes including `\uXXXX`.
+pub const fn properties() -> Config {
+    Config {
+        dialect: Dialect::Properties,
+    }
+}
+
+/// `KEY=value` lines with an optional `export` prefix and `#` comments.
+///
+/// Values are either unquoted, `"double quoted"` with backslash escapes, or `'single quoted'` taken literally,
+/// and quoted values may span several lines.
+pub const fn dotenv() -> Config {
+    Config {
+        dialect: Dialect::Dotenv,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    dialect: Dialect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Ini,
+    Properties,
+    Dotenv,
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Document<'src> {
+    pub entries: Vec<Entry<'src>>,
+}
+
+impl<'src> Document<'src> {
+    /// Looks up the last entry with the key in the section, as later entries override earlier ones.
+    /// Entries before any section header, and all entries of dialects without sections, are in `None`.
+    pub fn entry(&self, section: Option<&str>, key: &str) -> Option<&Entry<'src>> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.section == section && entry.key == key)
+    }
+
+    pub fn get(&self, section: Option<&str>, key: &str) -> Option<&str> {
+        self.entry(section, key).map(|entry| &*entry.value)
+    }
+
+    /// Distinct section names in order of first appearance.
+    pub fn sections(&self) -> impl Iterator<Item = &'src str> + '_ {
+        let mut seen = Vec::new();
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.section)
+            .filter(move |section| {
+                let first = !seen.contains(section);
+                if first {
+                    seen.push(*section);
+                }
+                first
+            })
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Entry<'src>> {
+        self.entries.iter()
+    }
+}
+
+impl<'a, 'src> IntoIterator for &'a Document<'src> {
+    type Item = &'a Entry<'src>;
+    type IntoIter = core::slice::Iter<'a, Entry<'src>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// Key and value, with spans in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry<'src> {
+    pub section: Option<&'src str>,
+    pub key: Cow<'src, str>,
+    pub value: Cow<'src, str>,
+    pub key_span: Range<usize>,
+    /// Including quotes, and all lines of a continued value.
+    pub value_span: Range<usize>,
+    /// In order of appearance, excluding those in literal values and escaped as `\${`.
+    pub interpolations: Vec<Interpolation<'src>>,
+}
+
+/// `${name}` within a value, which is neither validated nor expanded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpolation<'src> {
+    /// Everything between the braces, such as `HOME` or `HOME:-/root` for shell-like defaults.
+    pub name: &'src str,
+    /// Including `${` and `}`.
+    pub span: Range<usize>,
+}
+
+//------------------------------------------------------------------------------
+
+impl<'src, I, Ext> Parser<'src, I, Document<'src>, Ext> for Config
+where
+    I: InputSlice<'src, Slice = str> + StaticInput,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Document<'src>, I::Cursor), Ext::Error> {
+        let mut descent = Descent {
+            driver: Driver::<I, Ext> {
+                input,
+                state: &mut state,
+                ctx: &ctx,
+            },
+            dialect: self.dialect,
+            section: None,
+            entries: Vec::new(),
+        };
+        let end = trip!(descent.document(start));
+        PResult::emit((
+            Document {
+                entries: descent.entries,
+            },
+            end,
+        ))
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        Parser::<I, Document, Ext>::__parse(self, input, start, state, ctx, private::Token).raise_or_map(|(_, cur)| cur)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+const BLANK: ByteSet = ByteSet::new(b" \t\x0c");
+const LF: ByteSet = ByteSet::new(b"\n");
+const HEX: ByteSet = ByteSet::range(b'0', b'9')
+    .union(ByteSet::range(b'a', b'f'))
+    .union(ByteSet::range(b'A', b'F'));
+const NAME: ByteSet = ByteSet::range(b'0', b'9')
+    .union(ByteSet::range(b'a', b'z'))
+    .union(ByteSet::range(b'A', b'Z'))
+    .union(ByteSet::new(b"_.-"));
+
+const NEWLINE: OneOfLiterals<'static, (), 2> = one_of_literals([("\n", ()), ("\r\n", ())]);
+const SECTION: OneOfLiterals<'static, (), 1> = one_of_literals([("[", ())]);
+const SECTION_END: OneOfLiterals<'static, (), 1> = one_of_literals([("]", ())]);
+const SEPARATOR: OneOfLiterals<'static, (), 2> = one_of_literals([("=", ()), (":", ())]);
+const EQUALS: OneOfLiterals<'static, (), 1> = one_of_literals([("=", ())]);
+const EXPORT: OneOfLiterals<'static, (), 1> = one_of_literals([("export", ())]);
+const QUOTE: OneOfLiterals<'static, char, 2> = one_of_literals([("\"", '"'), ("'", '\'')]);
+const BACKSLASH: OneOfLiterals<'static, (), 1> = one_of_literals([("\\", ())]);
+
+/// Ends a run of characters taken as they are in quoted values.
+#[rustfmt::skip]
+const QUOTED: OneOfLiterals<'static, char, 5> = one_of_literals([
+    ("\"", '"'), ("'", '\''), ("\\", '\\'), ("\n", '\n'), ("\r", '\r'),
+]);
+
+/// Escapes in double quoted values, where `None` is an escaped line break.
+#[rustfmt::skip]
+const ESCAPE: OneOfLiterals<'static, Option<char>, 13> = one_of_literals([
+    ("n", Some('\n')), ("r", Some('\r')), ("t", Some('\t')), ("0", Some('\0')),
+    ("\"", Some('"')), ("'", Some('\'')), ("\\", Some('\\')), ("$", Some('$')),
+    ("#", Some('#')), (";", Some(';')), ("=", Some('=')), (":", Some(':')), ("\n", None),
+]);
+
+#[derive(Clone, Copy)]
+enum PropEscape {
+    Char(char),
+    Unicode,
+    /// A line break, after which the logical line continues.
+    Continue,
+    /// A lone `\r`, which ends the logical line.
+    Stop,
+}
+
+#[rustfmt::skip]
+const PROP_ESCAPE: OneOfLiterals<'static, PropEscape, 8> = one_of_literals([
+    ("t", PropEscape::Char('\t')), ("n", PropEscape::Char('\n')), ("r", PropEscape::Char('\r')),
+    ("f", PropEscape::Char('\x0c')), ("u", PropEscape::Unicode),
+    ("\n", PropEscape::Continue), ("\r\n", PropEscape::Continue), ("\r", PropEscape::Stop),
+]);
+
+impl Dialect {
+    /// Characters starting full line comments, and inline comments after a blank.
+    const fn comments(self) -> ByteSet {
+        match self {
+            Dialect::Ini => ByteSet::new(b";#"),
+            Dialect::Properties => ByteSet::new(b"#!"),
+            Dialect::Dotenv => ByteSet::new(b"#"),
+        }
+    }
+}
+
+/// Value assembled from pieces of the source, which stays borrowed while there is a single piece.
+struct Text<'src>(Cow<'src, str>);
+
+impl<'src> Text<'src> {
+    fn push_src(&mut self, piece: &'src str) {
+        match self.0.is_empty() {
+            true => self.0 = Cow::Borrowed(piece),
+            false if !piece.is_empty() => self.0.to_mut().push_str(piece),
+            false => (),
+        }
+    }
+
+    fn push_char(&mut self, c: char) {
+        self.0.to_mut().push(c);
+    }
+}
+
+/// Value with its span, and whether it may contain interpolations.
+struct Value<'src, C> {
+    text: Cow<'src, str>,
+    span: Range<C>,
+    interpolate: bool,
+}
+
+/// Recursive descent over the patterns above, line by line.
+struct Descent<'a, 'src, I, Ext>
+where
+    I: Input<'src>,
+    Ext: Extra<'src, I>,
+{
+    driver: Driver<'a, 'src, I, Ext>,
+    dialect: Dialect,
+    section: Option<&'src str>,
+    entries: Vec<Entry<'src>>,
+}
+
+impl<'src, I, Ext> Descent<'_, 'src, I, Ext>
+where
+    I: InputSlice<'src, Slice = str> + StaticInput,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    fn blanks(&mut self, cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        self.driver.check(&take(BLANK, ..), cur)
+    }
+
+    fn at_end(&mut self, cur: I::Cursor) -> bool {
+        self.driver.input.shall_reached_end::<Ext::Error>(cur).is_none()
+    }
+
+    /// Rest of the line, excluding `\r\n` or `\n`.
+    fn line(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, &'src str> {
+        let (line, _) = self.driver.capture(take(except(LF), ..), cur.clone())?;
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        Ok((line, I::bump_cursor(cur, line.len())))
+    }
+
+    /// Moves past the line break, or stays at the end of input.
+    fn newline(&mut self, cur: I::Cursor) -> Option<I::Cursor> {
+        match self.driver.dispatch(&NEWLINE, cur.clone()) {
+            Ok(((), next)) => Some(next),
+            Err(_) => self.at_end(cur.clone()).then_some(cur),
+        }
+    }
+
+    fn skip_line(&mut self, cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let (_, end) = self.line(cur)?;
+        Ok(self.newline(end.clone()).unwrap_or(end))
+    }
+
+    /// Only blanks or a comment shall follow, until the line break.
+    fn end_of_line(&mut self, cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let mut cur = self.blanks(cur)?;
+        if self
+            .driver
+            .check(&take(self.dialect.comments(), 1), cur.clone())
+            .is_ok()
+        {
+            cur = self.line(cur)?.1;
+        }
+        self.newline(cur.clone())
+            .ok_or_else(|| expected::<I, _>(cur, "a line break"))
+    }
+
+    fn document(&mut self, start: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let mut cur = start;
+        loop {
+            cur = self.blanks(cur)?;
+            if self.at_end(cur.clone()) {
+                return Ok(cur);
+            }
+            if self.driver.check(&take(ByteSet::new(b"\n\r"), 1), cur.clone()).is_ok() {
+                cur = self
+                    .newline(cur.clone())
+                    .ok_or_else(|| expected::<I, _>(cur, "a line break"))?;
+                continue;
+            }
+            if self
+                .driver
+                .check(&take(self.dialect.comments(), 1), cur.clone())
+                .is_ok()
+            {
+                cur = self.skip_line(cur)?;
+                continue;
+            }
+            cur = match self.dialect {
+                Dialect::Ini => match self.driver.dispatch(&SECTION, cur.clone()) {
+                    Ok(((), next)) => self.section(next)?,
+                    Err(_) => self.ini_entry(cur)?,
+                },
+                Dialect::Properties => self.properties_entry(cur)?,
+                Dialect::Dotenv => self.dotenv_entry(cur)?,
+            };
+        }
+    }
+
+    fn push(&mut self, key: (Cow<'src, str>, Range<I::Cursor>), value: (Cow<'src, str>, Range<I::Cursor>)) {
+        self.entries.push(Entry {
+            section: self.section,
+            key: key.0,
+            value: value.0,
+            key_span: I::span(key.1),
+            value_span: I::span(value.1),
+            interpolations: Vec::new(),
+        });
+    }
+
+    /// Collects `${...}` in the source of the last value, skipping those escaped by a backslash.
+    fn interpolate(&mut self, range: Range<I::Cursor>) -> Result<(), Ext::Error> {
+        let (text, _) = self.driver.capture(SliceTo(range.end.clone()), range.start.clone())?;
+        let bytes = text.as_bytes();
+        let span = |start: usize, end: usize| {
+            I::span(I::bump_cursor(range.start.clone(), start)..I::bump_cursor(range.start.clone(), end))
+        };
+        let mut pos = 0;
+        let mut found = Vec::new();
+        while pos + 1 < bytes.len() {
+            match bytes[pos] {
+                b'\\' => pos += 2,
+                b'$' if bytes[pos + 1] == b'{' => {
+                    let Some(close) = text[pos + 2..].find('}') else {
+                        return Err(expected::<I, _>(range.end, "`}` of the interpolation"));
+                    };
+                    let end = pos + 2 + close + 1;
+                    found.push(Interpolation {
+                        name: &text[pos + 2..end - 1],
+                        span: span(pos, end),
+                    });
+                    pos = end;
+                }
+                _ => pos += 1,
+            }
+        }
+        self.entries.last_mut().unwrap().interpolations = found;
+        Ok(())
+    }
+
+    /// Either a quoted value followed by the end of line, or an unquoted one.
+    fn value(
+        &mut self,
+        start: I::Cursor,
+        continuation: bool,
+        multiline: bool,
+    ) -> Res<'src, I, Ext, Value<'src, I::Cursor>> {
+        match self.driver.dispatch(&QUOTE, start.clone()) {
+            Ok((quote, next)) => {
+                let (value, end) = self.quoted(start.clone(), next, quote, multiline)?;
+                let next = self.end_of_line(end.clone())?;
+                let value = Value {
+                    text: value,
+                    span: start..end,
+                    interpolate: quote == '"',
+                };
+                Ok((value, next))
+            }
+            Err(_) => {
+                let ((value, end), next) = self.unquoted(start.clone(), continuation)?;
+                let value = Value {
+                    text: value,
+                    span: start..end,
+                    interpolate: true,
+                };
+                Ok((value, next))
+            }
+        }
+    }
+
+    /// Up to the line break or an inline comment after a blank, where a trailing `\` continues on the next line.
+    /// Yields the end of the value, excluding blanks or a comment after it, and where the next line starts.
+    fn unquoted(&mut self, mut cur: I::Cursor, continuation: bool) -> Res<'src, I, Ext, (Cow<'src, str>, I::Cursor)> {
+        let comments = self.dialect.comments();
+        let mut text = Text(Cow::Borrowed(""));
+        loop {
+            let (line, end) = self.line(cur.clone())?;
+            let len = value_len(line, comments);
+            let commented = line[len..].bytes().any(|b| comments.contains(b));
+            let value_end = I::bump_cursor(cur, len);
+
+            match continuation && !commented && line[..len].ends_with('\\') {
+                true => {
+                    text.push_src(&line[..len - 1]);
+                    match self.newline(end.clone()) {
+                        Some(next) if !self.at_end(next.clone()) => cur = self.blanks(next)?,
+                        next => return Ok(((text.0, value_end), next.unwrap_or(end))),
+                    }
+                }
+                false => {
+                    text.push_src(&line[..len]);
+                    let next = self.newline(end.clone()).unwrap_or(end);
+                    return Ok(((text.0, value_end), next));
+                }
+            }
+        }
+    }
+
+    /// From after the opening quote at `open` to after the closing one, with escapes in double quotes.
+    fn quoted(
+        &mut self,
+        open: I::Cursor,
+        mut cur: I::Cursor,
+        quote: char,
+        multiline: bool,
+    ) -> Res<'src, I, Ext, Cow<'src, str>> {
+        let mut stop = ByteSet::new(&[quote as u8]);
+        if quote == '"' {
+            stop = stop.with(b'\\');
+        }
+        if !multiline {
+            stop = stop.with(b'\n').with(b'\r');
+        }
+
+        let mut text = Text(Cow::Borrowed(""));
+        loop {
+            let (run, at) = self.driver.capture(take(except(stop), ..), cur)?;
+            text.push_src(run);
+            let (c, next) = self
+                .driver
+                .dispatch(&QUOTED, at.clone())
+                .map_err(|_| expected::<I, _>(at.clone(), "closing quote"))?;
+            cur = match c {
+                '\\' => {
+                    let (c, end) = self.escape(at, next, multiline)?;
+                    if let Some(c) = c {
+                        text.push_char(c);
+                    }
+                    end
+                }
+                '\n' | '\r' => {
+                    let quote_span = open.clone()..I::bump_cursor(open, 1);
+                    return Err(Ext::Error::new(
+                        I::span(quote_span),
+                        ErrorKind::Expected(&Expect("closing quote on the same line")),
+                    ));
+                }
+                _ => return Ok((text.0, next)),
+            };
+        }
+    }
+
+    /// After the backslash at `at` in double quotes, `None` for an escaped line break.
+    fn escape(&mut self, at: I::Cursor, cur: I::Cursor, multiline: bool) -> Res<'src, I, Ext, Option<char>> {
+        match self.driver.dispatch(&ESCAPE, cur.clone()) {
+            Ok((None, next)) if !multiline => Err(invalid::<I, _>(at..next)),
+            Ok(res) => Ok(res),
+            Err(_) => Err(match self.driver.check(&take(ANY, 1), cur.clone()) {
+                Ok(end) => invalid::<I, _>(at..end),
+                Err(_) => expected::<I, _>(cur, "an escape sequence"),
+            }),
+        }
+    }
+
+    //------------------------------------------------------------------------------
+
+    /// After the opening bracket.
+    fn section(&mut self, cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let (name, close) = self
+            .driver
+            .capture(take(except(ByteSet::new(b"]\n")), ..), cur.clone())?;
+        let Ok(((), next)) = self.driver.dispatch(&SECTION_END, close) else {
+            let (_, end) = self.line(cur)?;
+            return Err(expected::<I, _>(end, "`]`"));
+        };
+        self.section = Some(name.trim_matches([' ', '\t']));
+        self.end_of_line(next)
+    }
+
+    fn ini_entry(&mut self, start: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let (key, sep) = self
+            .driver
+            .capture(take(except(ByteSet::new(b"=:\n")), ..), start.clone())?;
+        let Ok(((), next)) = self.driver.dispatch(&SEPARATOR, sep) else {
+            let (_, end) = self.line(start)?;
+            return Err(expected::<I, _>(end, "`=` or `:`"));
+        };
+        let key = key.trim_end_matches([' ', '\t']);
+        let key_span = start.clone()..I::bump_cursor(start, key.len());
+
+        let value_start = self.blanks(next)?;
+        let (value, next) = self.value(value_start, true, false)?;
+        self.push((key.into(), key_span), (value.text, value.span.clone()));
+        if value.interpolate {
+            self.interpolate(value.span)?;
+        }
+        Ok(next)
+    }
+
+    //------------------------------------------------------------------------------
+
+    fn properties_entry(&mut self, start: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let (key, key_end) = self.logical(start.clone(), true)?;
+        let mut cur = self.blanks(key_end.clone())?;
+        if let Ok(((), next)) = self.driver.dispatch(&SEPARATOR, cur.clone()) {
+            cur = self.blanks(next)?;
+        }
+        let (value, value_end) = self.logical(cur.clone(), false)?;
+        self.push((key, start..key_end), (value, cur.clone()..value_end.clone()));
+        self.interpolate(cur..value_end.clone())?;
+        Ok(self.newline(value_end.clone()).unwrap_or(value_end))
+    }
+
+    /// Up to the end of the logical line, or an unescaped separator in keys.
+    fn logical(&mut self, mut cur: I::Cursor, key: bool) -> Res<'src, I, Ext, Cow<'src, str>> {
+        let stop = match key {
+            true => ByteSet::new(b"\n\r\\=: \t\x0c"),
+            false => ByteSet::new(b"\n\r\\"),
+        };
+        let mut text = Text(Cow::Borrowed(""));
+        loop {
+            let (run, at) = self.driver.capture(take(except(stop), ..), cur)?;
+            text.push_src(run);
+            let Ok(((), next)) = self.driver.dispatch(&BACKSLASH, at.clone()) else {
+                return Ok((text.0, at));
+            };
+            cur = match self.driver.dispatch(&PROP_ESCAPE, next.clone()) {
+                Ok((PropEscape::Char(c), end)) => {
+                    text.push_char(c);
+                    end
+                }
+                Ok((PropEscape::Unicode, digits)) => {
+                    let (hex, end) = self.driver.capture(take(HEX, 4), digits)?;
+                    let c = u32::from_str_radix(hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| invalid::<I, _>(at..end.clone()))?;
+                    text.push_char(c);
+                    end
+                }
+                Ok((PropEscape::Continue, end)) => self.blanks(end)?,
+                Ok((PropEscape::Stop, _)) => next,
+                // Any other character stands for itself.
+                Err(_) => match self.driver.capture(take(ANY, 1), next.clone()) {
+                    Ok((c, end)) => {
+                        text.push_src(c);
+                        end
+                    }
+                    Err(_) => next,
+                },
+            };
+        }
+    }
+
+    //------------------------------------------------------------------------------
+
+    fn dotenv_entry(&mut self, start: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let mut cur = start;
+        if let Ok(((), next)) = self.driver.dispatch(&EXPORT, cur.clone())
+            && let Ok(next) = self.driver.check(&take(ByteSet::new(b" \t"), 1), next)
+        {
+            cur = self.blanks(next)?;
+        }
+
+        let (key, key_end) = self.driver.capture(take(NAME, ..), cur.clone())?;
+        if key.is_empty() {
+            return Err(expected::<I, _>(cur, "a variable name"));
+        }
+        let sep = self.blanks(key_end.clone())?;
+        let Ok(((), next)) = self.driver.dispatch(&EQUALS, sep.clone()) else {
+            return Err(expected::<I, _>(sep, "`=`"));
+        };
+
+        let value_start = self.blanks(next)?;
+        let (value, next) = self.value(value_start, false, true)?;
+        self.push((Cow::Borrowed(key), cur..key_end), (value.text, value.span.clone()));
+        if value.interpolate {
+            self.interpolate(value.span)?;
+        }
+        Ok(next)
+    }
+}
+
+/// Length of the line up to a comment, which starts at the beginning or after a blank, with trailing blanks trimmed.
+fn value_len(line: &str, comments: ByteSet) -> usize {
+    let mut prev_blank = true;
+    let cut = line
+        .char_indices()
+        .find(|&(_, c)| {
+            let found = prev_blank && c.is_ascii() && comments.contains(c as u8);
+            prev_blank = matches!(c, ' ' | '\t');
+            found
+        })
+        .map_or(line.len(), |(off, _)| off);
+    line[..cut].trim_end_matches([' ', '\t']).len()
+}
@@ -0,0 +1,542 @@
+//! [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180) CSV, and its variants such as TSV.
+//!
+//! ```
+//! use kaparser::{error::EmptyErr, grammars::csv::*};
+//!
+//! let text = "name,born\nAda Lovelace,1815\n\"Hopper, Grace\",1906\n";
+//! let mut records = csv().has_headers(true).records::<_, EmptyErr>(text);
+//! let born = records.headers().unwrap().unwrap().position("born").unwrap();
+//! for record in &mut records {
+//!     let record = record.unwrap();
+//!     println!("line {}: {} was born in {}", record.line(), &record[0], &record[born]);
+//! }
+//! ```
+//!
+//! Blank lines are skipped. Line breaks are either `\n` or `\r\n`.
+
+use crate::{common::*, error::*, grammars::*};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{fmt, marker::PhantomData, ops::Range, str};
+use memchr::{memchr_iter, memchr2};
+
+/// Comma-separated, with fields optionally quoted by `"`, where a doubled `""` stands for a quote.
+pub const fn csv() -> Csv {
+    Csv {
+        delimiter: b',',
+        quote: Some(b'"'),
+        escape: None,
+        has_headers: false,
+    }
+}
+
+/// Tab-separated, otherwise the same as [`csv()`].
+pub const fn tsv() -> Csv {
+    csv().delimiter(b'\t')
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Csv {
+    delimiter: u8,
+    quote: Option<u8>,
+    escape: Option<u8>,
+    has_headers: bool,
+}
+
+impl Csv {
+    pub const fn delimiter(self, delimiter: u8) -> Self {
+        Self { delimiter, ..self }
+    }
+
+    /// `None` disables quoting.
+    pub const fn quote(self, quote: Option<u8>) -> Self {
+        Self { quote, ..self }
+    }
+
+    /// Escapes the next byte in quoted fields, in addition to doubled quotes.
+    pub const fn escape(self, escape: Option<u8>) -> Self {
+        Self { escape, ..self }
+    }
+
+    /// Takes the first record as headers, see [`Records::headers`].
+    pub const fn has_headers(self, has_headers: bool) -> Self {
+        Self { has_headers, ..self }
+    }
+
+    /// Yields records whose unquoted and unescaped fields are borrowed from the input.
+    pub fn records<'src, I, E>(self, input: I) -> Records<'src, I, E, Borrowed>
+    where
+        I: InputByteSlice<'src> + StaticInput,
+        E: Error,
+    {
+        Records::new(self, input)
+    }
+
+    /// Yields records whose fields are all owned, which works with streaming inputs.
+    pub fn records_owned<'src, I, E>(self, input: I) -> Records<'src, I, E, Owned>
+    where
+        I: InputByteSlice<'src>,
+        E: Error,
+    {
+        Records::new(self, input)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'src> {
+    fields: Vec<Cow<'src, str>>,
+    line: usize,
+    span: Range<usize>,
+}
+
+impl<'src> Record<'src> {
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.fields.get(idx).map(|field| &**field)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|field| &**field)
+    }
+
+    pub fn fields(&self) -> &[Cow<'src, str>] {
+        &self.fields
+    }
+
+    pub fn into_fields(self) -> Vec<Cow<'src, str>> {
+        self.fields
+    }
+
+    /// Index of the field with the name, for header records.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.iter().position(|field| field == name)
+    }
+
+    /// One-based line number where the record begins.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Span of the record, excluding the line break.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl core::ops::Index<usize> for Record<'_> {
+    type Output = str;
+
+    fn index(&self, idx: usize) -> &str {
+        &self.fields[idx]
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Fields are borrowed from the input where possible.
+pub enum Borrowed {}
+
+/// Fields are always owned.
+pub enum Owned {}
+
+pub struct Records<'src, I, E, M>
+where
+    I: Input<'src>,
+{
+    input: I,
+    reader: Reader<'src, I>,
+    headers: Option<Record<'src>>,
+    phantom: PhantomData<(E, M)>,
+}
+
+/// State of scanning, kept apart from the input whose bytes are being scanned.
+struct Reader<'src, I>
+where
+    I: Input<'src>,
+{
+    config: Csv,
+    cursor: I::Cursor,
+    line: usize,
+    done: bool,
+    raw: Vec<RawField>,
+}
+
+#[derive(Debug, Clone)]
+struct RawField {
+    range: Range<usize>,
+    escaped: bool,
+}
+
+enum Scan {
+    /// Length of the record, and the consumed length including the line break.
+    Record(usize, usize),
+    Blank(usize),
+    More,
+}
+
+enum Step<'a, E> {
+    Record(Result<Record<'a>, E>),
+    More,
+    End,
+}
+
+struct UnterminatedQuote {
+    quote: u8,
+    line: usize,
+}
+
+impl Describe for UnterminatedQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "closing `{}` of the field quoted at line {}",
+            self.quote as char, self.line
+        )
+    }
+}
+
+impl<'src, I> Reader<'src, I>
+where
+    I: InputByteSlice<'src>,
+{
+    fn fail<E: Error>(&mut self, range: Range<usize>, kind: ErrorKind) -> E {
+        self.done = true;
+        let range = I::bump_cursor(self.cursor.clone(), range.start)..I::bump_cursor(self.cursor.clone(), range.end);
+        E::new(I::span(range), kind)
+    }
+
+    /// Scans a record at the beginning of bytes, collecting raw fields.
+    fn scan<E: Error>(&mut self, bytes: &[u8], eof: bool) -> Result<Scan, E> {
+        let Csv {
+            delimiter,
+            quote,
+            escape,
+            ..
+        } = self.config;
+
+        match bytes {
+            [] if eof => return Ok(Scan::Blank(0)),
+            [b'\n', ..] => return Ok(Scan::Blank(1)),
+            [b'\r', b'\n', ..] => return Ok(Scan::Blank(2)),
+            [] | [b'\r'] if !eof => return Ok(Scan::More),
+            _ => (),
+        }
+
+        self.raw.clear();
+        let mut line = self.line;
+        let mut pos = 0;
+        loop {
+            // Quoted field.
+            if let Some(quote) = quote
+                && bytes.get(pos) == Some(&quote)
+            {
+                let opened_at = line;
+                let mut i = pos + 1;
+                let mut escaped = false;
+                let end = loop {
+                    let Some(&b) = bytes.get(i) else {
+                        return match eof {
+                            true => Err(self.fail(
+                                pos..pos + 1,
+                                ErrorKind::Expected(&UnterminatedQuote { quote, line: opened_at }),
+                            )),
+                            false => Ok(Scan::More),
+                        };
+                    };
+                    if Some(b) == escape && b != quote {
+                        escaped = true;
+                        i += 2;
+                    } else if b == quote {
+                        match bytes.get(i + 1) {
+                            Some(&next) if next == quote => {
+                                escaped = true;
+                                i += 2;
+                            }
+                            None if !eof => return Ok(Scan::More),
+                            _ => break i,
+                        }
+                    } else {
+                        line += (b == b'\n') as usize;
+                        i += 1;
+                    }
+                };
+
+                self.raw.push(RawField {
+                    range: pos + 1..end,
+                    escaped,
+                });
+                pos = end + 1;
+                match bytes[pos..] {
+                    [] if eof => return Ok(Scan::Record(pos, pos)),
+                    [b, ..] if b == delimiter => pos += 1,
+                    [b'\n', ..] => return Ok(Scan::Record(pos, pos + 1)),
+                    [b'\r', b'\n', ..] => return Ok(Scan::Record(pos, pos + 2)),
+                    [] | [b'\r'] if !eof => return Ok(Scan::More),
+                    _ => {
+                        return Err(self.fail(
+                            pos..pos,
+                            ErrorKind::Expected(&Expect("a delimiter or a line break after the quoted field")),
+                        ));
+                    }
+                }
+                continue;
+            }
+
+            // Unquoted field.
+            match memchr2(delimiter, b'\n', &bytes[pos..]) {
+                Some(off) if bytes[pos + off] == delimiter => {
+                    self.raw.push(RawField {
+                        range: pos..pos + off,
+                        escaped: false,
+                    });
+                    pos += off + 1;
+                }
+                Some(off) => {
+                    let end = pos + off;
+                    let trimmed = match end > pos && bytes[end - 1] == b'\r' {
+                        true => end - 1,
+                        false => end,
+                    };
+                    self.raw.push(RawField {
+                        range: pos..trimmed,
+                        escaped: false,
+                    });
+                    return Ok(Scan::Record(trimmed, end + 1));
+                }
+                None if eof => {
+                    self.raw.push(RawField {
+                        range: pos..bytes.len(),
+                        escaped: false,
+                    });
+                    return Ok(Scan::Record(bytes.len(), bytes.len()));
+                }
+                None => return Ok(Scan::More),
+            }
+        }
+    }
+
+    fn field<'a, E: Error>(&mut self, bytes: &'a [u8], raw: &RawField) -> Result<Cow<'a, str>, E> {
+        let slice = &bytes[raw.range.clone()];
+        if !raw.escaped {
+            return match str::from_utf8(slice) {
+                Ok(s) => Ok(Cow::Borrowed(s)),
+                Err(e) => Err(self.utf8_error(raw.range.start, e)),
+            };
+        }
+
+        let quote = self.config.quote.unwrap();
+        let mut buf = Vec::with_capacity(slice.len());
+        let mut iter = slice.iter();
+        while let Some(&b) = iter.next() {
+            match b == quote || Some(b) == self.config.escape {
+                true => buf.extend(iter.next()),
+                false => buf.push(b),
+            }
+        }
+        String::from_utf8(buf)
+            .map(Cow::Owned)
+            .map_err(|_| match str::from_utf8(slice) {
+                Err(e) => self.utf8_error(raw.range.start, e),
+                Ok(_) => self.fail(raw.range.clone(), ErrorKind::InvalidInput),
+            })
+    }
+
+    fn utf8_error<E: Error>(&mut self, base: usize, e: str::Utf8Error) -> E {
+        let at = base + e.valid_up_to();
+        self.fail(at..at + e.error_len().unwrap_or(1), ErrorKind::InvalidInput)
+    }
+
+    /// Scans the next record in bytes at the cursor, skipping blank lines.
+    fn step<'a, E: Error>(&mut self, mut bytes: &'a [u8], eof: bool) -> Step<'a, E> {
+        loop {
+            let scanned = match self.scan(bytes, eof) {
+                Ok(scanned) => scanned,
+                Err(e) => return Step::Record(Err(e)),
+            };
+            match scanned {
+                Scan::More => return Step::More,
+                Scan::Blank(0) => {
+                    self.done = true;
+                    return Step::End;
+                }
+                Scan::Blank(consumed) => {
+                    self.line += 1;
+                    self.cursor = I::bump_cursor(self.cursor.clone(), consumed);
+                    bytes = &bytes[consumed..];
+                }
+                Scan::Record(len, consumed) => {
+                    let res = self.record(bytes, len);
+                    self.line += 1 + memchr_iter(b'\n', &bytes[..len]).count();
+                    self.cursor = I::bump_cursor(self.cursor.clone(), consumed);
+                    return Step::Record(res);
+                }
+            }
+        }
+    }
+
+    fn record<'a, E: Error>(&mut self, bytes: &'a [u8], len: usize) -> Result<Record<'a>, E> {
+        let raw = core::mem::take(&mut self.raw);
+        let fields = raw
+            .iter()
+            .map(|raw| self.field(bytes, raw))
+            .collect::<Result<Vec<_>, E>>();
+        self.raw = raw;
+
+        let start = I::offset(self.cursor.clone());
+        Ok(Record {
+            fields: fields?,
+            line: self.line,
+            span: start..I::offset(I::bump_cursor(self.cursor.clone(), len)),
+        })
+    }
+}
+
+impl<'src, I, E, M> Records<'src, I, E, M>
+where
+    I: InputByteSlice<'src>,
+    E: Error,
+{
+    fn new(config: Csv, input: I) -> Self {
+        let cursor = input.begin();
+        Self {
+            input,
+            reader: Reader {
+                config,
+                cursor,
+                line: 1,
+                done: false,
+                raw: Vec::new(),
+            },
+            headers: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// One-based line number where the next record begins.
+    pub fn line(&self) -> usize {
+        self.reader.line
+    }
+}
+
+impl<'src, I, E> Records<'src, I, E, Borrowed>
+where
+    I: InputByteSlice<'src> + StaticInput,
+    E: Error,
+{
+    /// Reads the header record if not yet, returns `None` if headers are not enabled or the input is empty.
+    pub fn headers(&mut self) -> Result<Option<&Record<'src>>, E> {
+        if self.reader.config.has_headers && self.headers.is_none() {
+            self.headers = self.next_borrowed().transpose()?;
+        }
+        Ok(self.headers.as_ref())
+    }
+
+    fn next_borrowed(&mut self) -> Option<Result<Record<'src>, E>> {
+        if self.reader.done {
+            return None;
+        }
+
+        let bytes = match rest_bytes::<I, E>(&mut self.input, self.reader.cursor.clone()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.reader.done = true;
+                return Some(Err(e));
+            }
+        };
+        match self.reader.step(bytes, true) {
+            Step::Record(res) => Some(res),
+            Step::More | Step::End => None,
+        }
+    }
+}
+
+impl<'src, I, E> Iterator for Records<'src, I, E, Borrowed>
+where
+    I: InputByteSlice<'src> + StaticInput,
+    E: Error,
+{
+    type Item = Result<Record<'src>, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.headers() {
+            return Some(Err(e));
+        }
+        self.next_borrowed()
+    }
+}
+
+impl<'src, I, E> Records<'src, I, E, Owned>
+where
+    I: InputByteSlice<'src>,
+    E: Error,
+{
+    /// Reads the header record if not yet, returns `None` if headers are not enabled or the input is empty.
+    pub fn headers(&mut self) -> Result<Option<&Record<'src>>, E> {
+        if self.reader.config.has_headers && self.headers.is_none() {
+            self.headers = self.next_owned().transpose()?;
+        }
+        Ok(self.headers.as_ref())
+    }
+
+    fn next_owned(&mut self) -> Option<Result<Record<'src>, E>> {
+        if self.reader.done {
+            return None;
+        }
+
+        let mut more = false;
+        loop {
+            let cursor = self.reader.cursor.clone();
+            let fetched = match more {
+                false => self.input.fetch_byte_slice::<E>(cursor),
+                true => self.input.fetch_more_byte_slice::<E>(cursor),
+            };
+            let (bytes, eof) = match fetched {
+                Ok(res) => res,
+                Err(e) => {
+                    self.reader.done = true;
+                    return Some(Err(e));
+                }
+            };
+            match self.reader.step(bytes, eof) {
+                Step::Record(res) => return Some(res.map(owned)),
+                Step::More => more = true,
+                Step::End => return None,
+            }
+        }
+    }
+}
+
+impl<'src, I, E> Iterator for Records<'src, I, E, Owned>
+where
+    I: InputByteSlice<'src>,
+    E: Error,
+{
+    type Item = Result<Record<'src>, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.headers() {
+            return Some(Err(e));
+        }
+        self.next_owned()
+    }
+}
+
+fn owned<'src>(record: Record<'_>) -> Record<'src> {
+    Record {
+        fields: record
+            .fields
+            .into_iter()
+            .map(|field| Cow::Owned(field.into_owned()))
+            .collect(),
+        line: record.line,
+        span: record.span,
+    }
+}
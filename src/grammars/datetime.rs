@@ -0,0 +1,806 @@
+//! Dates, times and durations of ISO 8601, and date-times of [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339),
+//! as patterns producing plain values.
+//!
+//! ```
+//! use kaparser::{error::EmptyErr, grammars::datetime::*, parser::*, pattern::*};
+//!
+//! let res = Parser::<&str, DateTime, EmptyErr>::fullmatch(&rfc3339().captured(), "1985-04-12T23:20:50.52Z");
+//! let dt = res.into_output().unwrap();
+//! assert_eq!(dt.time.nanosecond, 520_000_000);
+//! assert_eq!(dt.unix_timestamp(), Some(482196050));
+//! ```
+//!
+//! Only the extended formats with separators are recognized, such as `2024-03-01`, `2024-W09-5` and `2024-061`,
+//! and years are of four digits. Every field is checked against its range, including the days of February
+//! in leap years, and errors are raised at the offending field.
+//!
+//! A second of `60` is accepted for leap seconds. When the offset is known, it shall fall on the last minute
+//! of a UTC day, otherwise it cannot be checked.
+//!
+//! With the `chrono` feature, the values can be converted into those of `chrono`.
+
+use crate::grammars::*;
+use core::{fmt, ops::Range};
+
+/// A calendar date, such as `2024-03-01`.
+pub const fn date() -> CalendarDateFormat {
+    CalendarDateFormat
+}
+
+/// A week date, such as `2024-W09-5`.
+pub const fn week_date() -> WeekDateFormat {
+    WeekDateFormat
+}
+
+/// An ordinal date, such as `2024-061`.
+pub const fn ordinal_date() -> OrdinalDateFormat {
+    OrdinalDateFormat
+}
+
+/// Any of the calendar, week or ordinal date.
+pub const fn iso_date() -> IsoDateFormat {
+    IsoDateFormat
+}
+
+/// A time of day, such as `23:20`, `23:20:50` or `23:20:50.52`, where the fraction may also follow a comma.
+pub const fn time() -> TimeFormat {
+    TimeFormat
+}
+
+/// A time-zone offset, either `Z` or such as `+08:00`, `+0800` and `+08`.
+pub const fn offset() -> OffsetFormat {
+    OffsetFormat
+}
+
+/// A duration, such as `P1Y2M10DT2H30M`, `PT0.5S` or `P2W`.
+pub const fn duration() -> DurationFormat {
+    DurationFormat
+}
+
+/// A date-time of RFC 3339, such as `1985-04-12T23:20:50.52Z`, where `t` or a space may also separate
+/// the date and the time.
+pub const fn rfc3339() -> Rfc3339 {
+    Rfc3339 { local: false }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    /// 1 to 12.
+    pub month: u8,
+    /// Starting from 1.
+    pub day: u8,
+}
+
+/// Date in the ISO week-numbering year, which may differ from the calendar year around new year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WeekDate {
+    pub year: i32,
+    /// 1 to 52 or 53.
+    pub week: u8,
+    /// 1 for Monday to 7 for Sunday.
+    pub weekday: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OrdinalDate {
+    pub year: i32,
+    /// 1 to 365 or 366.
+    pub day: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IsoDate {
+    Calendar(Date),
+    Week(WeekDate),
+    Ordinal(OrdinalDate),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    /// 60 for a leap second.
+    pub second: u8,
+    pub nanosecond: u32,
+}
+
+/// Offset from UTC, positive to the east.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Offset {
+    pub minutes: i16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateTime {
+    pub date: Date,
+    pub time: Time,
+    /// Absent for local date-times, see [`Rfc3339::local`].
+    pub offset: Option<Offset>,
+}
+
+/// Nominal duration, where each component keeps what is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Duration {
+    pub years: u32,
+    pub months: u32,
+    pub weeks: u32,
+    pub days: u32,
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+    pub nanoseconds: u32,
+}
+
+pub const fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub const fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+pub const fn days_in_year(year: i32) -> u16 {
+    if is_leap_year(year) { 366 } else { 365 }
+}
+
+/// 53 if the year starts on Thursday, or on Wednesday in a leap year.
+pub fn weeks_in_year(year: i32) -> u8 {
+    let jan1 = Date { year, month: 1, day: 1 }.weekday();
+    match jan1 == 4 || jan1 == 3 && is_leap_year(year) {
+        true => 53,
+        false => 52,
+    }
+}
+
+impl Date {
+    /// `None` if out of range.
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        ((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day)).then_some(Self {
+            year,
+            month,
+            day,
+        })
+    }
+
+    /// Days since 1970-01-01.
+    pub fn days_since_epoch(&self) -> i64 {
+        // See <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+        let (m, d) = (self.month as i64, self.day as i64);
+        let y = self.year as i64 - (m <= 2) as i64;
+        let era = y.div_euclid(400);
+        let yoe = y.rem_euclid(400);
+        let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    /// Inverse of [`Date::days_since_epoch`].
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + (month <= 2) as i64) as i32;
+        Self { year, month, day }
+    }
+
+    /// 1 for Monday to 7 for Sunday.
+    pub fn weekday(&self) -> u8 {
+        // 1970-01-01 was Thursday.
+        ((self.days_since_epoch() + 3).rem_euclid(7) + 1) as u8
+    }
+
+    pub fn ordinal(&self) -> OrdinalDate {
+        let day = (1..self.month).map(|m| days_in_month(self.year, m) as u16).sum::<u16>() + self.day as u16;
+        OrdinalDate { year: self.year, day }
+    }
+
+    pub fn week_date(&self) -> WeekDate {
+        let weekday = self.weekday();
+        // The week belongs to the year of its Thursday.
+        let thursday = Date::from_days_since_epoch(self.days_since_epoch() + 4 - weekday as i64);
+        let week = ((thursday.ordinal().day - 1) / 7 + 1) as u8;
+        WeekDate {
+            year: thursday.year,
+            week,
+            weekday,
+        }
+    }
+}
+
+impl WeekDate {
+    pub fn to_date(&self) -> Date {
+        let jan4 = Date {
+            year: self.year,
+            month: 1,
+            day: 4,
+        };
+        let monday = jan4.days_since_epoch() - (jan4.weekday() as i64 - 1);
+        Date::from_days_since_epoch(monday + (self.week as i64 - 1) * 7 + (self.weekday as i64 - 1))
+    }
+}
+
+impl OrdinalDate {
+    pub fn to_date(&self) -> Date {
+        let jan1 = Date {
+            year: self.year,
+            month: 1,
+            day: 1,
+        };
+        Date::from_days_since_epoch(jan1.days_since_epoch() + self.day as i64 - 1)
+    }
+}
+
+impl IsoDate {
+    pub fn to_date(&self) -> Date {
+        match self {
+            IsoDate::Calendar(date) => *date,
+            IsoDate::Week(date) => date.to_date(),
+            IsoDate::Ordinal(date) => date.to_date(),
+        }
+    }
+}
+
+impl Time {
+    pub fn seconds_from_midnight(&self) -> u32 {
+        self.hour as u32 * 3600 + self.minute as u32 * 60 + self.second as u32
+    }
+}
+
+impl Offset {
+    pub const UTC: Self = Self { minutes: 0 };
+}
+
+impl DateTime {
+    /// Seconds since 1970-01-01T00:00:00Z, where a leap second counts as the next second.
+    /// `None` for local date-times.
+    pub fn unix_timestamp(&self) -> Option<i64> {
+        let offset = self.offset?;
+        Some(
+            self.date.days_since_epoch() * 86400 + self.time.seconds_from_midnight() as i64
+                - offset.minutes as i64 * 60,
+        )
+    }
+}
+
+impl Duration {
+    /// Takes a week as 7 days and a day as 24 hours, `None` if there are years or months, or on overflow.
+    pub fn to_std(&self) -> Option<core::time::Duration> {
+        if self.years != 0 || self.months != 0 {
+            return None;
+        }
+        let secs = [
+            (self.weeks, 7 * 86400),
+            (self.days, 86400),
+            (self.hours, 3600),
+            (self.minutes, 60),
+            (self.seconds, 1),
+        ]
+        .into_iter()
+        .try_fold(0u64, |acc, (n, unit)| acc.checked_add(n as u64 * unit))?;
+        Some(core::time::Duration::new(secs, self.nanoseconds))
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for WeekDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-W{:02}-{}", self.year, self.week, self.weekday)
+    }
+}
+
+impl fmt::Display for OrdinalDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:03}", self.year, self.day)
+    }
+}
+
+impl fmt::Display for IsoDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IsoDate::Calendar(date) => date.fmt(f),
+            IsoDate::Week(date) => date.fmt(f),
+            IsoDate::Ordinal(date) => date.fmt(f),
+        }
+    }
+}
+
+/// The fraction is written in the fewest digits.
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        write_fraction(f, self.nanosecond)
+    }
+}
+
+fn write_fraction(f: &mut fmt::Formatter<'_>, nanos: u32) -> fmt::Result {
+    if nanos == 0 {
+        return Ok(());
+    }
+    let mut digits = 9;
+    let mut nanos = nanos;
+    while nanos.is_multiple_of(10) {
+        nanos /= 10;
+        digits -= 1;
+    }
+    write!(f, ".{:0digits$}", nanos)
+}
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.minutes {
+            0 => write!(f, "Z"),
+            m => write!(
+                f,
+                "{}{:02}:{:02}",
+                if m < 0 { '-' } else { '+' },
+                m.unsigned_abs() / 60,
+                m.unsigned_abs() % 60
+            ),
+        }
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}T{}", self.date, self.time)?;
+        match &self.offset {
+            Some(offset) => write!(f, "{}", offset),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "P")?;
+        for (n, designator) in [
+            (self.years, 'Y'),
+            (self.months, 'M'),
+            (self.weeks, 'W'),
+            (self.days, 'D'),
+        ] {
+            if n != 0 {
+                write!(f, "{}{}", n, designator)?;
+            }
+        }
+        let seconds = self.seconds != 0 || self.nanoseconds != 0;
+        if self.hours != 0 || self.minutes != 0 || seconds {
+            write!(f, "T")?;
+            for (n, designator) in [(self.hours, 'H'), (self.minutes, 'M')] {
+                if n != 0 {
+                    write!(f, "{}{}", n, designator)?;
+                }
+            }
+            if seconds {
+                write!(f, "{}", self.seconds)?;
+                write_fraction(f, self.nanoseconds)?;
+                write!(f, "S")?;
+            }
+        } else if *self == Self::default() {
+            write!(f, "T0S")?;
+        }
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+pub struct CalendarDateFormat;
+
+#[derive(Debug, Clone, Copy)]
+pub struct WeekDateFormat;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OrdinalDateFormat;
+
+#[derive(Debug, Clone, Copy)]
+pub struct IsoDateFormat;
+
+#[derive(Debug, Clone, Copy)]
+pub struct TimeFormat;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OffsetFormat;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DurationFormat;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Rfc3339 {
+    local: bool,
+}
+
+impl Rfc3339 {
+    /// Makes the offset optional, as in the local date-times of ISO 8601 such as `2024-03-01T12:00:00`.
+    pub const fn local(mut self) -> Self {
+        self.local = true;
+        self
+    }
+}
+
+impl_pattern_for_scanner! {
+    CalendarDateFormat => Date, |_this, s| s.date();
+    WeekDateFormat => WeekDate, |_this, s| s.year_dash().and_then(|year| s.week_date(year));
+    OrdinalDateFormat => OrdinalDate, |_this, s| s.year_dash().and_then(|year| s.ordinal_date(year));
+    IsoDateFormat => IsoDate, |_this, s| s.iso_date();
+    TimeFormat => Time, |_this, s| s.time(false);
+    OffsetFormat => Offset, |_this, s| s.offset(false);
+    DurationFormat => Duration, |_this, s| s.duration();
+    Rfc3339 => DateTime, |this, s| s.date_time(this.local);
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct OutOfRange(&'static str);
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl core::error::Error for OutOfRange {}
+
+static MONTH: OutOfRange = OutOfRange("month shall be 01 to 12");
+static DAY: OutOfRange = OutOfRange("day exceeds the days in the month");
+static WEEK: OutOfRange = OutOfRange("week exceeds the weeks in the year");
+static WEEKDAY: OutOfRange = OutOfRange("weekday shall be 1 to 7");
+static ORDINAL: OutOfRange = OutOfRange("day exceeds the days in the year");
+static HOUR: OutOfRange = OutOfRange("hour shall be 00 to 23");
+static MINUTE: OutOfRange = OutOfRange("minute shall be 00 to 59");
+static SECOND: OutOfRange = OutOfRange("second shall be 00 to 60");
+static LEAP_SECOND: OutOfRange = OutOfRange("leap second shall be in the last minute of a UTC day");
+static OFFSET_HOUR: OutOfRange = OutOfRange("offset hour shall be 00 to 23");
+static DESIGNATOR: OutOfRange = OutOfRange("designator out of order or repeated");
+static FRACTION: OutOfRange = OutOfRange("fraction only allowed for seconds");
+
+fn out_of_range(range: Range<usize>, what: &'static OutOfRange) -> Failure {
+    Failure {
+        range,
+        kind: FailureKind::Other(what),
+    }
+}
+
+type Res<T> = Result<T, Failure>;
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    hit_end: bool,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            hit_end: false,
+        }
+    }
+
+    fn peek_at(&mut self, off: usize) -> Option<u8> {
+        let b = self.bytes.get(self.pos + off).copied();
+        self.hit_end |= b.is_none();
+        b
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let hit = self.peek_at(0) == Some(byte);
+        self.pos += hit as usize;
+        hit
+    }
+
+    fn expect(&mut self, byte: u8, what: &'static str) -> Res<()> {
+        match self.eat(byte) {
+            true => Ok(()),
+            false => Err(Failure::expected(self.pos, what)),
+        }
+    }
+
+    fn at_digit(&mut self, off: usize) -> bool {
+        self.peek_at(off).is_some_and(|b| b.is_ascii_digit())
+    }
+
+    /// Exactly `n` digits.
+    fn digits(&mut self, n: usize, what: &'static str) -> Res<u32> {
+        let mut val = 0;
+        for _ in 0..n {
+            match self.peek_at(0) {
+                Some(b @ b'0'..=b'9') => val = val * 10 + (b - b'0') as u32,
+                _ => return Err(Failure::expected(self.pos, what)),
+            }
+            self.pos += 1;
+        }
+        Ok(val)
+    }
+
+    /// Exactly `n` digits in `range`.
+    fn field(&mut self, n: usize, what: &'static str, range: Range<u32>, err: &'static OutOfRange) -> Res<u32> {
+        let start = self.pos;
+        let val = self.digits(n, what)?;
+        match range.contains(&val) {
+            true => Ok(val),
+            false => Err(out_of_range(start..self.pos, err)),
+        }
+    }
+
+    fn year_dash(&mut self) -> Res<i32> {
+        let year = self.digits(4, "a year of four digits")? as i32;
+        self.expect(b'-', "`-`")?;
+        Ok(year)
+    }
+
+    fn date(&mut self) -> Res<Date> {
+        let year = self.year_dash()?;
+        self.month_day(year)
+    }
+
+    fn month_day(&mut self, year: i32) -> Res<Date> {
+        let month = self.field(2, "a month of two digits", 1..13, &MONTH)? as u8;
+        self.expect(b'-', "`-`")?;
+        let days = days_in_month(year, month) as u32;
+        let day = self.field(2, "a day of two digits", 1..days + 1, &DAY)? as u8;
+        Ok(Date { year, month, day })
+    }
+
+    fn week_date(&mut self, year: i32) -> Res<WeekDate> {
+        self.expect(b'W', "`W`")?;
+        let weeks = weeks_in_year(year) as u32;
+        let week = self.field(2, "a week of two digits", 1..weeks + 1, &WEEK)? as u8;
+        self.expect(b'-', "`-`")?;
+        let weekday = self.field(1, "a weekday digit", 1..8, &WEEKDAY)? as u8;
+        Ok(WeekDate { year, week, weekday })
+    }
+
+    fn ordinal_date(&mut self, year: i32) -> Res<OrdinalDate> {
+        let days = days_in_year(year) as u32;
+        let day = self.field(3, "a day of three digits", 1..days + 1, &ORDINAL)? as u16;
+        Ok(OrdinalDate { year, day })
+    }
+
+    fn iso_date(&mut self) -> Res<IsoDate> {
+        let year = self.year_dash()?;
+        if self.peek_at(0) == Some(b'W') {
+            self.week_date(year).map(IsoDate::Week)
+        } else if self.peek_at(2) == Some(b'-') {
+            self.month_day(year).map(IsoDate::Calendar)
+        } else {
+            self.ordinal_date(year).map(IsoDate::Ordinal)
+        }
+    }
+
+    /// Digits after `.` or `,` as nanoseconds, beyond which are truncated.
+    fn fraction(&mut self, strict: bool) -> u32 {
+        let point = self.peek_at(0);
+        if !(point == Some(b'.') || !strict && point == Some(b',')) || !self.at_digit(1) {
+            return 0;
+        }
+        self.pos += 1;
+        let mut nanos = 0;
+        let mut scale = 100_000_000;
+        while let Some(b @ b'0'..=b'9') = self.peek_at(0) {
+            nanos += (b - b'0') as u32 * scale;
+            scale /= 10;
+            self.pos += 1;
+        }
+        nanos
+    }
+
+    /// Seconds are required if `strict`.
+    fn time(&mut self, strict: bool) -> Res<Time> {
+        let hour = self.field(2, "an hour of two digits", 0..24, &HOUR)? as u8;
+        self.expect(b':', "`:`")?;
+        let minute = self.field(2, "a minute of two digits", 0..60, &MINUTE)? as u8;
+        let mut time = Time {
+            hour,
+            minute,
+            ..Time::default()
+        };
+        if strict || self.peek_at(0) == Some(b':') && self.at_digit(1) {
+            self.expect(b':', "`:`")?;
+            time.second = self.field(2, "a second of two digits", 0..61, &SECOND)? as u8;
+            time.nanosecond = self.fraction(strict);
+        }
+        Ok(time)
+    }
+
+    /// Only `Z` and `±hh:mm` if `strict`.
+    fn offset(&mut self, strict: bool) -> Res<Offset> {
+        let sign = match self.peek_at(0) {
+            Some(b'Z' | b'z') => {
+                self.pos += 1;
+                return Ok(Offset::UTC);
+            }
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Err(Failure::expected(self.pos, "`Z` or an offset")),
+        };
+        self.pos += 1;
+        let hours = self.field(2, "an offset hour of two digits", 0..24, &OFFSET_HOUR)? as i16;
+        let minutes = match strict || self.peek_at(0) == Some(b':') {
+            true => {
+                self.expect(b':', "`:`")?;
+                self.field(2, "an offset minute of two digits", 0..60, &MINUTE)?
+            }
+            false if self.at_digit(0) => self.field(2, "an offset minute of two digits", 0..60, &MINUTE)?,
+            false => 0,
+        } as i16;
+        Ok(Offset {
+            minutes: sign * (hours * 60 + minutes),
+        })
+    }
+
+    fn date_time(&mut self, local: bool) -> Res<DateTime> {
+        let date = self.date()?;
+        match self.peek_at(0) {
+            Some(b'T' | b't' | b' ') => self.pos += 1,
+            _ => return Err(Failure::expected(self.pos, "`T`")),
+        }
+        let time_start = self.pos;
+        let time = self.time(true)?;
+        let offset = match local && !matches!(self.peek_at(0), Some(b'Z' | b'z' | b'+' | b'-')) {
+            true => None,
+            false => Some(self.offset(true)?),
+        };
+
+        if let Some(offset) = offset
+            && time.second == 60
+            && (time.hour as i32 * 60 + time.minute as i32 - offset.minutes as i32).rem_euclid(1440) != 1439
+        {
+            return Err(out_of_range(time_start + 6..time_start + 8, &LEAP_SECOND));
+        }
+        Ok(DateTime { date, time, offset })
+    }
+
+    /// Decimal number, which may overflow.
+    fn number(&mut self) -> Res<u32> {
+        let start = self.pos;
+        let mut val = 0u32;
+        let mut overflow = false;
+        while let Some(b @ b'0'..=b'9') = self.peek_at(0) {
+            match val.checked_mul(10).and_then(|v| v.checked_add((b - b'0') as u32)) {
+                Some(v) => val = v,
+                None => overflow = true,
+            }
+            self.pos += 1;
+        }
+        match overflow {
+            false => Ok(val),
+            true => Err(Failure {
+                range: start..self.pos,
+                kind: FailureKind::Overflow,
+            }),
+        }
+    }
+
+    /// Components in the order of `designators`, where `fields` are set in the same order.
+    fn components(&mut self, designators: &[u8], fields: &mut [&mut u32], nanos: &mut u32) -> Res<bool> {
+        let mut next = 0;
+        let mut any = false;
+        while self.at_digit(0) {
+            let n = self.number()?;
+            let fraction_start = self.pos;
+            let fraction = self.fraction(false);
+            let at = self.pos;
+            let found = self.peek_at(0).and_then(|b| designators.iter().position(|d| *d == b));
+            match found {
+                Some(i) if i >= next => {
+                    if fraction_start != at && designators[i] != b'S' {
+                        return Err(out_of_range(fraction_start..at, &FRACTION));
+                    }
+                    *fields[i] = n;
+                    if designators[i] == b'S' {
+                        *nanos = fraction;
+                    }
+                    next = i + 1;
+                    any = true;
+                    self.pos += 1;
+                }
+                Some(_) => return Err(out_of_range(at..at + 1, &DESIGNATOR)),
+                None => {
+                    return Err(Failure::expected(
+                        at,
+                        match designators {
+                            b"YMWD" => "a designator `Y`, `M`, `W` or `D`",
+                            _ => "a designator `H`, `M` or `S`",
+                        },
+                    ));
+                }
+            }
+        }
+        Ok(any)
+    }
+
+    fn duration(&mut self) -> Res<Duration> {
+        self.expect(b'P', "`P`")?;
+        let mut d = Duration::default();
+        let mut nanos = 0;
+        let date = self.components(
+            b"YMWD",
+            &mut [&mut d.years, &mut d.months, &mut d.weeks, &mut d.days],
+            &mut nanos,
+        )?;
+        if self.eat(b'T') {
+            let time = self.components(b"HMS", &mut [&mut d.hours, &mut d.minutes, &mut d.seconds], &mut nanos)?;
+            if !time {
+                return Err(Failure::expected(self.pos, "an hour, minute or second component"));
+            }
+        } else if !date {
+            return Err(Failure::expected(self.pos, "a duration component"));
+        }
+        d.nanoseconds = nanos;
+        Ok(d)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(feature = "chrono")]
+impl Date {
+    /// `None` if out of range.
+    pub fn to_chrono(&self) -> Option<chrono::NaiveDate> {
+        chrono::NaiveDate::from_ymd_opt(self.year, self.month as u32, self.day as u32)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Time {
+    /// `None` if out of range, where a leap second is represented as `chrono` does.
+    pub fn to_chrono(&self) -> Option<chrono::NaiveTime> {
+        let (second, nano) = match self.second {
+            60 => (59, self.nanosecond.checked_add(1_000_000_000)?),
+            second => (second, self.nanosecond),
+        };
+        chrono::NaiveTime::from_hms_nano_opt(self.hour as u32, self.minute as u32, second as u32, nano)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Offset {
+    pub fn to_chrono(&self) -> Option<chrono::FixedOffset> {
+        chrono::FixedOffset::east_opt(self.minutes as i32 * 60)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl DateTime {
+    /// `None` for local date-times, or if out of range.
+    pub fn to_chrono(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        self.to_chrono_naive()?
+            .and_local_timezone(self.offset?.to_chrono()?)
+            .single()
+    }
+
+    /// Ignores the offset.
+    pub fn to_chrono_naive(&self) -> Option<chrono::NaiveDateTime> {
+        Some(self.date.to_chrono()?.and_time(self.time.to_chrono()?))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Duration {
+    /// See [`Duration::to_std`].
+    pub fn to_chrono(&self) -> Option<chrono::TimeDelta> {
+        chrono::TimeDelta::from_std(self.to_std()?).ok()
+    }
+}
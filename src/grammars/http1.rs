@@ -0,0 +1,711 @@
+//! Heads of HTTP/1.1 messages and chunked bodies, as in [RFC 9112](https://www.rfc-editor.org/rfc/rfc9112),
+//! parsed incrementally as bytes arrive.
+//!
+//! ```
+//! use kaparser::{error::EmptyErr, grammars::http1::*, input::partial::PartialInput, parser::*};
+//!
+//! let buf = b"POST /items HTTP/1.1\r\nHost: example.com\r\nContent-Length: 5\r\n\r\nhello";
+//! let head = request().max_headers(64);
+//!
+//! // Only a part of the head has been received, so read more into `buf` and parse again.
+//! let mut input = PartialInput::new(&buf[..30]);
+//! assert!(Parser::<_, Request, EmptyErr>::parse(&head, &mut input, 0).into_output().is_none());
+//! assert!(input.is_incomplete());
+//!
+//! let mut input = PartialInput::new(&buf[..]);
+//! let (req, body_start) = Parser::<_, Request, EmptyErr>::parse(&head, &mut input, 0).into_output().unwrap();
+//! assert_eq!((req.method, req.target), ("POST", "/items"));
+//! assert_eq!(req.framing(), Some(Framing::Length(5)));
+//! assert_eq!(&buf[body_start..], b"hello");
+//! ```
+//!
+//! Whenever the bytes fetched so far end in the middle of a message, more are fetched as long as
+//! `eof == false`, so that over [`PartialInput`](crate::input::partial::PartialInput) the parsers ask for more,
+//! and over other streaming inputs they pull more. The scan then resumes after the last complete field line.
+//!
+//! Lines may also end with a bare LF, but a bare CR is rejected. Empty lines before a request line are ignored.
+//! Limits bound the head to be buffered, which are checked before asking for more.
+
+use crate::{
+    common::*,
+    extra::*,
+    grammars::{uri::UNRESERVED, *},
+    parser::*,
+    predicate::ByteSet,
+    private,
+};
+use alloc::{borrow::Cow, vec::Vec};
+use core::{fmt, marker::PhantomData, ops::Range, str};
+
+/// `"!" / "#" / "$" / "%" / "&" / "'" / "*" / "+" / "-" / "." / "^" / "_" / "`" / "|" / "~" / DIGIT / ALPHA`
+pub const TCHAR: ByteSet = UNRESERVED.union(ByteSet::new(b"!#$%&'*+^`|"));
+
+/// Visible ASCII characters.
+pub const VCHAR: ByteSet = ByteSet::range(0x21, 0x7e);
+
+/// Bytes that are not ASCII, tolerated in field values and reason phrases.
+pub const OBS_TEXT: ByteSet = ByteSet::range(0x80, 0xff);
+
+/// Bytes of field values, and of reason phrases, except line endings.
+pub const FIELD_BYTES: ByteSet = VCHAR.union(OBS_TEXT).union(ByteSet::new(b" \t"));
+
+const WS: ByteSet = ByteSet::new(b" \t");
+
+/// Parses a request line and header fields, up to the empty line.
+pub const fn request() -> Http1<RequestHead> {
+    Http1::new()
+}
+
+/// Parses a status line and header fields, up to the empty line.
+pub const fn response() -> Http1<ResponseHead> {
+    Http1::new()
+}
+
+/// Parses a chunk of a chunked body, where the last chunk also carries the trailer fields.
+///
+/// The whole chunk data is borrowed once received. Limits apply to the chunk-size line and the trailer section.
+pub const fn chunk() -> Http1<ChunkFrame> {
+    Http1::new()
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request<'src> {
+    pub method: &'src str,
+    /// Not validated further, see [`uri::uri_reference`].
+    pub target: &'src str,
+    pub version: Version,
+    pub headers: Vec<Header<'src>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response<'src> {
+    pub version: Version,
+    pub status: u16,
+    /// Possibly empty, and may contain bytes that are not ASCII.
+    pub reason: &'src [u8],
+    pub headers: Vec<Header<'src>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Chunk<'src> {
+    Data {
+        data: &'src [u8],
+        /// Chunk extensions after `;`, unparsed.
+        ext: &'src [u8],
+    },
+    Last {
+        ext: &'src [u8],
+        trailers: Vec<Header<'src>>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u8,
+    pub minor: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header<'src> {
+    pub name: &'src str,
+    /// Without the surrounding whitespace, owned only if folded lines were joined.
+    pub value: Cow<'src, [u8]>,
+    /// Of the whole field line.
+    pub span: Range<usize>,
+}
+
+/// How the body of a message is delimited, see [RFC 9112, section 6.3](https://www.rfc-editor.org/rfc/rfc9112#section-6.3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Framing {
+    /// No body at all.
+    Empty,
+    Length(u64),
+    Chunked,
+    /// Until the connection closes, only for responses.
+    UntilClose,
+}
+
+impl Version {
+    pub const HTTP_10: Self = Self { major: 1, minor: 0 };
+    pub const HTTP_11: Self = Self { major: 1, minor: 1 };
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HTTP/{}.{}", self.major, self.minor)
+    }
+}
+
+impl<'src> Header<'src> {
+    /// Compares the name case-insensitively.
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    pub fn value_str(&self) -> Option<&str> {
+        str::from_utf8(&self.value).ok()
+    }
+}
+
+/// The value of the first field named `name`, case-insensitively.
+pub fn header<'a>(headers: &'a [Header<'_>], name: &str) -> Option<&'a [u8]> {
+    headers.iter().find(|h| h.is(name)).map(|h| &*h.value)
+}
+
+impl Request<'_> {
+    pub fn header(&self, name: &str) -> Option<&[u8]> {
+        header(&self.headers, name)
+    }
+
+    /// `None` if the framing is invalid, which shall be answered with 400 and closing the connection.
+    pub fn framing(&self) -> Option<Framing> {
+        framing(&self.headers, None)
+    }
+}
+
+impl Response<'_> {
+    pub fn header(&self, name: &str) -> Option<&[u8]> {
+        header(&self.headers, name)
+    }
+
+    /// `None` if the framing is invalid. Responses to HEAD requests have no body regardless.
+    pub fn framing(&self) -> Option<Framing> {
+        framing(&self.headers, Some(self.status))
+    }
+}
+
+fn framing(headers: &[Header], status: Option<u16>) -> Option<Framing> {
+    if let Some(status) = status
+        && ((100..200).contains(&status) || status == 204 || status == 304)
+    {
+        return Some(Framing::Empty);
+    }
+
+    let mut codings = headers
+        .iter()
+        .filter(|h| h.is("transfer-encoding"))
+        .flat_map(|h| h.value.split(|b| *b == b','))
+        .map(|coding| coding.trim_ascii())
+        .filter(|coding| !coding.is_empty())
+        .peekable();
+    if codings.peek().is_some() {
+        return match codings.last().is_some_and(|last| last.eq_ignore_ascii_case(b"chunked")) {
+            true => Some(Framing::Chunked),
+            false if status.is_none() => None,
+            false => Some(Framing::UntilClose),
+        };
+    }
+
+    let mut length = None;
+    for value in headers
+        .iter()
+        .filter(|h| h.is("content-length"))
+        .flat_map(|h| h.value.split(|b| *b == b','))
+    {
+        let value = value.trim_ascii();
+        if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let n = str::from_utf8(value).ok()?.parse::<u64>().ok()?;
+        if length.replace(n).is_some_and(|prev| prev != n) {
+            return None;
+        }
+    }
+    match (length, status) {
+        (Some(n), _) => Some(Framing::Length(n)),
+        (None, None) => Some(Framing::Empty),
+        (None, Some(_)) => Some(Framing::UntilClose),
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+pub struct RequestHead;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseHead;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkFrame;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Http1<K> {
+    max_headers: usize,
+    max_head_len: usize,
+    obs_fold: bool,
+    phantom: PhantomData<K>,
+}
+
+impl<K> Http1<K> {
+    const fn new() -> Self {
+        Self {
+            max_headers: 100,
+            max_head_len: 16 * 1024,
+            obs_fold: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Number of header (or trailer) fields, 100 by default.
+    pub const fn max_headers(mut self, n: usize) -> Self {
+        self.max_headers = n;
+        self
+    }
+
+    /// Bytes of the head including the start line, 16 KiB by default.
+    pub const fn max_head_len(mut self, n: usize) -> Self {
+        self.max_head_len = n;
+        self
+    }
+
+    /// Replaces each `obs-fold` with spaces instead of rejecting it.
+    ///
+    /// Proxies shall not forward folded lines, see [RFC 9112, section 5.2](https://www.rfc-editor.org/rfc/rfc9112#section-5.2).
+    pub const fn allow_obs_fold(mut self) -> Self {
+        self.obs_fold = true;
+        self
+    }
+}
+
+macro_rules! impl_parser_for_http1 {
+    ($($kind:ty => $out:ident, |$scanner:ident| $scan:expr;)*) => {$(
+        impl<'src, I, Ext> Parser<'src, I, $out<'src>, Ext> for Http1<$kind>
+        where
+            I: InputSlice<'src> + StaticInput,
+            I::Slice: AsRef<[u8]>,
+            Ext: Extra<'src, I>,
+        {
+            fn __parse(
+                &self,
+                input: &mut I,
+                start: I::Cursor,
+                mut state: MaybeMut<Ext::State>,
+                ctx: MaybeRef<Ext::Context>,
+                _: private::Token,
+            ) -> PResult<($out<'src>, I::Cursor), Ext::Error> {
+                let end = trip!(measure(self, input, start.clone(), |$scanner| $scan.map(drop)));
+                let mut driver = Driver::<I, Ext> {
+                    input,
+                    state: &mut state,
+                    ctx: &ctx,
+                };
+                let (bytes, _) = trip!(driver.capture(SliceTo(end.clone()), start.clone()));
+
+                let mut $scanner = Scanner::new(bytes.as_ref(), self);
+                match $scan {
+                    Ok(mut out) => {
+                        out.rebase::<I>(start);
+                        PResult::emit((out, end))
+                    }
+                    Err(failure) => PResult::raise(failure.raise::<I, _>(start)),
+                }
+            }
+
+            fn __check(
+                &self,
+                input: &mut I,
+                start: I::Cursor,
+                state: MaybeMut<Ext::State>,
+                ctx: MaybeRef<Ext::Context>,
+                _: private::Token,
+            ) -> PResult<I::Cursor, Ext::Error> {
+                Parser::<I, $out, Ext>::__parse(self, input, start, state, ctx, private::Token).raise_or_map(|(_, cur)| cur)
+            }
+        }
+    )*};
+}
+
+impl_parser_for_http1! {
+    RequestHead => Request, |s| s.request();
+    ResponseHead => Response, |s| s.response();
+    ChunkFrame => Chunk, |s| s.chunk();
+}
+
+/// Finds the end of the head, where the scan is rerun from the last complete field line whenever more is fetched.
+fn measure<'src, I, E, K>(
+    opts: &Http1<K>,
+    input: &mut I,
+    start: I::Cursor,
+    scan: impl Fn(&mut Scanner) -> Res<()>,
+) -> Result<I::Cursor, E>
+where
+    I: InputByteSlice<'src>,
+    E: Error,
+{
+    let mut mark = Mark::default();
+    let mut more = false;
+    loop {
+        let (bytes, eof) = match more {
+            false => input.fetch_byte_slice(start.clone())?,
+            true => input.fetch_more_byte_slice(start.clone())?,
+        };
+        let mut scanner = Scanner::new(bytes, opts);
+        scanner.skim = true;
+        scanner.mark = mark;
+        let res = scan(&mut scanner);
+
+        if let (Err(_), Some(limit)) = (&res, scanner.over_limit) {
+            return Err(malformed(limit..limit, &HEAD_TOO_LARGE).raise::<I, _>(start));
+        }
+        if scanner.hit_end && !eof {
+            mark = scanner.mark;
+            more = true;
+            continue;
+        }
+
+        return match res {
+            Ok(()) => Ok(I::bump_cursor(start, scanner.pos)),
+            Err(failure) => Err(failure.raise::<I, _>(start)),
+        };
+    }
+}
+
+/// Spans are scanned relative to the start.
+trait Rebase {
+    fn rebase<'src, I: InputSlice<'src>>(&mut self, start: I::Cursor);
+}
+
+fn rebase_headers<'src, I: InputSlice<'src>>(headers: &mut [Header], start: I::Cursor) {
+    for header in headers {
+        let range = I::bump_cursor(start.clone(), header.span.start)..I::bump_cursor(start.clone(), header.span.end);
+        header.span = I::span(range);
+    }
+}
+
+impl Rebase for Request<'_> {
+    fn rebase<'src, I: InputSlice<'src>>(&mut self, start: I::Cursor) {
+        rebase_headers::<I>(&mut self.headers, start);
+    }
+}
+
+impl Rebase for Response<'_> {
+    fn rebase<'src, I: InputSlice<'src>>(&mut self, start: I::Cursor) {
+        rebase_headers::<I>(&mut self.headers, start);
+    }
+}
+
+impl Rebase for Chunk<'_> {
+    fn rebase<'src, I: InputSlice<'src>>(&mut self, start: I::Cursor) {
+        if let Chunk::Last { trailers, .. } = self {
+            rebase_headers::<I>(trailers, start);
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct Malformed(&'static str);
+
+impl fmt::Display for Malformed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl core::error::Error for Malformed {}
+
+static HEAD_TOO_LARGE: Malformed = Malformed("message head too large");
+static TOO_MANY_HEADERS: Malformed = Malformed("too many header fields");
+static WS_BEFORE_COLON: Malformed = Malformed("whitespace between field name and colon");
+static OBS_FOLD: Malformed = Malformed("obsolete line folding");
+static BARE_CR: Malformed = Malformed("bare CR");
+
+fn malformed(range: Range<usize>, what: &'static Malformed) -> Failure {
+    Failure {
+        range,
+        kind: FailureKind::Other(what),
+    }
+}
+
+type Res<T> = Result<T, Failure>;
+
+/// Field lines scanned so far, up to the start of the last one.
+#[derive(Debug, Clone, Copy, Default)]
+struct Mark {
+    pos: usize,
+    fields: usize,
+}
+
+struct Scanner<'src> {
+    bytes: &'src [u8],
+    pos: usize,
+    hit_end: bool,
+    /// Bytes at or after are not looked at.
+    limit: usize,
+    over_limit: Option<usize>,
+    max_headers: usize,
+    max_head_len: usize,
+    obs_fold: bool,
+    /// Whether field lines are only counted, and skipped up to the mark.
+    skim: bool,
+    mark: Mark,
+}
+
+impl<'src> Scanner<'src> {
+    fn new<K>(bytes: &'src [u8], opts: &Http1<K>) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            hit_end: false,
+            limit: opts.max_head_len,
+            over_limit: None,
+            max_headers: opts.max_headers,
+            max_head_len: opts.max_head_len,
+            obs_fold: opts.obs_fold,
+            skim: false,
+            mark: Mark::default(),
+        }
+    }
+
+    fn peek_at(&mut self, off: usize) -> Option<u8> {
+        let i = self.pos + off;
+        if i >= self.limit {
+            self.over_limit = Some(self.limit);
+            return None;
+        }
+        let b = self.bytes.get(i).copied();
+        self.hit_end |= b.is_none();
+        b
+    }
+
+    fn expect(&mut self, byte: u8, what: &'static str) -> Res<()> {
+        match self.peek_at(0) == Some(byte) {
+            true => {
+                self.pos += 1;
+                Ok(())
+            }
+            false => Err(Failure::expected(self.pos, what)),
+        }
+    }
+
+    /// Consumes bytes in `set`.
+    fn run(&mut self, set: ByteSet) -> &'src [u8] {
+        let start = self.pos;
+        while self.peek_at(0).is_some_and(|b| set.contains(b)) {
+            self.pos += 1;
+        }
+        &self.bytes[start..self.pos]
+    }
+
+    /// CRLF, or a bare LF.
+    fn at_line_end(&mut self) -> Res<Option<usize>> {
+        match self.peek_at(0) {
+            Some(b'\n') => Ok(Some(1)),
+            Some(b'\r') => match self.peek_at(1) {
+                Some(b'\n') => Ok(Some(2)),
+                _ => Err(malformed(self.pos..self.pos + 1, &BARE_CR)),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn line_end(&mut self, what: &'static str) -> Res<()> {
+        match self.at_line_end()? {
+            Some(len) => {
+                self.pos += len;
+                Ok(())
+            }
+            None => Err(Failure::expected(self.pos, what)),
+        }
+    }
+
+    fn token(&mut self, what: &'static str) -> Res<&'src str> {
+        let token = self.run(TCHAR);
+        match token.is_empty() {
+            // SAFETY: Only ASCII bytes are in the set.
+            false => Ok(unsafe { str::from_utf8_unchecked(token) }),
+            true => Err(Failure::expected(self.pos, what)),
+        }
+    }
+
+    fn digit(&mut self, what: &'static str) -> Res<u8> {
+        match self.peek_at(0) {
+            Some(b @ b'0'..=b'9') => {
+                self.pos += 1;
+                Ok(b - b'0')
+            }
+            _ => Err(Failure::expected(self.pos, what)),
+        }
+    }
+
+    fn version(&mut self) -> Res<Version> {
+        for &b in b"HTTP/" {
+            self.expect(b, "`HTTP/`")?;
+        }
+        let major = self.digit("a digit")?;
+        self.expect(b'.', "`.`")?;
+        let minor = self.digit("a digit")?;
+        Ok(Version { major, minor })
+    }
+
+    fn request(&mut self) -> Res<Request<'src>> {
+        while let Some(len) = self.at_line_end()? {
+            self.pos += len;
+        }
+        let method = self.token("a method")?;
+        self.expect(b' ', "a space")?;
+        let target = self.run(VCHAR);
+        if target.is_empty() {
+            return Err(Failure::expected(self.pos, "a request target"));
+        }
+        self.expect(b' ', "a space")?;
+        let version = self.version()?;
+        self.line_end("a line ending")?;
+        Ok(Request {
+            method,
+            // SAFETY: Only ASCII bytes are in the set.
+            target: unsafe { str::from_utf8_unchecked(target) },
+            version,
+            headers: self.fields()?,
+        })
+    }
+
+    fn response(&mut self) -> Res<Response<'src>> {
+        let version = self.version()?;
+        self.expect(b' ', "a space")?;
+        let mut status = 0;
+        for _ in 0..3 {
+            status = status * 10 + self.digit("a status code of three digits")? as u16;
+        }
+        // The space before an empty reason phrase is often omitted.
+        let reason = match self.peek_at(0) {
+            Some(b' ') => {
+                self.pos += 1;
+                self.run(FIELD_BYTES)
+            }
+            _ => &[],
+        };
+        self.line_end("a line ending")?;
+        Ok(Response {
+            version,
+            status,
+            reason,
+            headers: self.fields()?,
+        })
+    }
+
+    /// Field lines up to and including the empty line.
+    fn fields(&mut self) -> Res<Vec<Header<'src>>> {
+        let mut headers = Vec::new();
+        let mut count = 0;
+        if self.skim && self.mark.pos > self.pos {
+            (self.pos, count) = (self.mark.pos, self.mark.fields);
+        }
+        loop {
+            if self.skim {
+                self.mark = Mark {
+                    pos: self.pos,
+                    fields: count,
+                };
+            }
+            if let Some(len) = self.at_line_end()? {
+                self.pos += len;
+                return Ok(headers);
+            }
+            if count == self.max_headers {
+                return Err(malformed(self.pos..self.pos, &TOO_MANY_HEADERS));
+            }
+            let header = self.field()?;
+            count += 1;
+            if !self.skim {
+                headers.push(header);
+            }
+        }
+    }
+
+    fn field(&mut self) -> Res<Header<'src>> {
+        let start = self.pos;
+        let name = self.token("a field name")?;
+        if self.peek_at(0).is_some_and(|b| WS.contains(b)) {
+            return Err(malformed(self.pos..self.pos + 1, &WS_BEFORE_COLON));
+        }
+        self.expect(b':', "`:`")?;
+        self.run(WS);
+
+        let value_start = self.pos;
+        let mut folded = false;
+        let value_end = loop {
+            self.run(FIELD_BYTES);
+            let end = self.pos;
+            match self.at_line_end()? {
+                Some(len) => self.pos += len,
+                None => return Err(Failure::expected(self.pos, "a field value character")),
+            }
+            if !self.peek_at(0).is_some_and(|b| WS.contains(b)) {
+                break end;
+            }
+            if !self.obs_fold {
+                return Err(malformed(end..self.pos + 1, &OBS_FOLD));
+            }
+            folded = true;
+        };
+
+        let raw = self.bytes[value_start..value_end].trim_ascii_end();
+        let value = match folded {
+            false => Cow::Borrowed(raw),
+            true => Cow::Owned(
+                raw.iter()
+                    .map(|&b| if b == b'\r' || b == b'\n' { b' ' } else { b })
+                    .collect(),
+            ),
+        };
+        Ok(Header {
+            name,
+            value,
+            span: start..self.pos,
+        })
+    }
+
+    fn chunk(&mut self) -> Res<Chunk<'src>> {
+        let start = self.pos;
+        let digits = self.run(ByteSet::new(b"0123456789abcdefABCDEF"));
+        if digits.is_empty() {
+            return Err(Failure::expected(self.pos, "a chunk size"));
+        }
+        let size = digits
+            .iter()
+            .try_fold(0u64, |acc, d| {
+                acc.checked_mul(16)?
+                    .checked_add((*d as char).to_digit(16).unwrap_or_default() as u64)
+            })
+            .ok_or(Failure {
+                range: start..self.pos,
+                kind: FailureKind::Overflow,
+            })?;
+        let ext = self.run(FIELD_BYTES);
+        if !ext.is_empty() && ext.trim_ascii_start().first() != Some(&b';') {
+            return Err(Failure::expected(self.pos - ext.len(), "`;` or a line ending"));
+        }
+        self.line_end("a line ending")?;
+
+        if size == 0 {
+            self.limit = self.pos.saturating_add(self.max_head_len);
+            let trailers = self.fields()?;
+            return Ok(Chunk::Last { ext, trailers });
+        }
+
+        let data_start = self.pos;
+        let data_end = usize::try_from(size)
+            .ok()
+            .and_then(|size| data_start.checked_add(size))
+            .ok_or(Failure {
+                range: start..start + digits.len(),
+                kind: FailureKind::Overflow,
+            })?;
+        if data_end > self.bytes.len() {
+            self.hit_end = true;
+            return Err(Failure::expected(self.bytes.len(), "chunk data"));
+        }
+        self.pos = data_end;
+        self.limit = data_end.saturating_add(self.max_head_len);
+        self.line_end("a line ending after the chunk data")?;
+        Ok(Chunk::Data {
+            data: &self.bytes[data_start..data_end],
+            ext,
+        })
+    }
+}
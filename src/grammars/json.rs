@@ -0,0 +1,502 @@
+//! [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259) JSON.
+//!
+//! ```
+//! use kaparser::{error::EmptyErr, grammars::json::*, parser::*};
+//!
+//! let res = Parser::<&str, Value, EmptyErr>::fullmatch(&json(), r#"{"name": "kaparser", "tags": ["parser"]}"#);
+//! let value = res.into_output().unwrap();
+//! assert_eq!(value.get("name").and_then(Value::as_str), Some("kaparser"));
+//! ```
+
+use crate::{common::*, error::*, extra::*, grammars::*, literal::*, parser::*, predicate::*, private};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{fmt, str};
+
+/// Nesting of arrays and objects deeper than this is rejected, unless configured by [`Json::max_depth`].
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Borrows from the source wherever possible.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'src> {
+    Null,
+    Bool(bool),
+    Number(Number<'src>),
+    /// Borrowed unless the string contains escapes.
+    String(Cow<'src, str>),
+    Array(Vec<Value<'src>>),
+    /// Members are kept in source order, and duplicate names are kept as well.
+    Object(Vec<(Cow<'src, str>, Value<'src>)>),
+}
+
+impl<'src> Value<'src> {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<Number<'src>> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value<'src>]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(Cow<'src, str>, Value<'src>)]> {
+        match self {
+            Value::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Looks up the last member with the name, as most implementations do.
+    pub fn get(&self, name: &str) -> Option<&Value<'src>> {
+        self.as_object()?.iter().rev().find(|(k, _)| k == name).map(|(_, v)| v)
+    }
+}
+
+/// Number lexeme as it is in the source, converted on demand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Number<'src>(&'src str);
+
+impl<'src> Number<'src> {
+    pub fn as_str(&self) -> &'src str {
+        self.0
+    }
+
+    /// Whether there is neither a fraction nor an exponent.
+    pub fn is_integer(&self) -> bool {
+        !self.0.contains(['.', 'e', 'E'])
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.0.parse().ok()
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.0.parse().ok()
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        self.0.parse().unwrap()
+    }
+}
+
+impl fmt::Display for Number<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Parses a JSON text, i.e. a value surrounded by optional whitespaces.
+pub const fn json() -> Json {
+    Json {
+        max_depth: DEFAULT_MAX_DEPTH,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Json {
+    max_depth: usize,
+}
+
+impl Json {
+    pub const fn max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+}
+
+#[derive(Debug)]
+struct DepthExceeded;
+
+impl fmt::Display for DepthExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nesting too deep")
+    }
+}
+
+impl core::error::Error for DepthExceeded {}
+
+impl<'src, I, Ext> Parser<'src, I, Value<'src>, Ext> for Json
+where
+    I: InputSlice<'src> + StaticInput,
+    I::Slice: AsRef<[u8]>,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Value<'src>, I::Cursor), Ext::Error> {
+        let mut descent = Descent {
+            driver: Driver::<I, Ext> {
+                input,
+                state: &mut state,
+                ctx: &ctx,
+            },
+            depth: self.max_depth,
+        };
+        descent.text(start).into()
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        Parser::<I, Value, Ext>::__parse(self, input, start, state, ctx, private::Token).raise_or_map(|(_, cur)| cur)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+const WS: ByteSet = ByteSet::new(b" \t\n\r");
+const DIGIT: ByteSet = ByteSet::range(b'0', b'9');
+const HEX: ByteSet = DIGIT
+    .union(ByteSet::range(b'a', b'f'))
+    .union(ByteSet::range(b'A', b'F'));
+const CONTROL: ByteSet = ByteSet::range(0x00, 0x1f);
+/// Ends a run of characters taken as they are in strings.
+const SPECIAL: ByteSet = CONTROL.with(b'"').with(b'\\');
+
+#[derive(Clone, Copy)]
+enum Lead {
+    Object,
+    Array,
+    String,
+    Number,
+    Null,
+    True,
+    False,
+}
+
+#[rustfmt::skip]
+const VALUE: OneOfLiterals<'static, Lead, 17> = one_of_literals([
+    ("{", Lead::Object), ("[", Lead::Array), ("\"", Lead::String), ("-", Lead::Number),
+    ("0", Lead::Number), ("1", Lead::Number), ("2", Lead::Number), ("3", Lead::Number), ("4", Lead::Number),
+    ("5", Lead::Number), ("6", Lead::Number), ("7", Lead::Number), ("8", Lead::Number), ("9", Lead::Number),
+    ("null", Lead::Null), ("true", Lead::True), ("false", Lead::False),
+]);
+
+#[derive(Clone, Copy)]
+enum Escape {
+    End,
+    Char(char),
+    Unicode,
+    /// A backslash not followed by a known escape.
+    Bad,
+}
+
+#[rustfmt::skip]
+const ESCAPE: OneOfLiterals<'static, Escape, 11> = one_of_literals([
+    ("\"", Escape::End), ("\\\"", Escape::Char('"')), ("\\\\", Escape::Char('\\')), ("\\/", Escape::Char('/')),
+    ("\\b", Escape::Char('\x08')), ("\\f", Escape::Char('\x0c')), ("\\n", Escape::Char('\n')),
+    ("\\r", Escape::Char('\r')), ("\\t", Escape::Char('\t')), ("\\u", Escape::Unicode), ("\\", Escape::Bad),
+]);
+
+const LOW_SURROGATE: OneOfLiterals<'static, (), 1> = one_of_literals([("\\u", ())]);
+const ARRAY_END: OneOfLiterals<'static, (), 1> = one_of_literals([("]", ())]);
+const ARRAY_NEXT: OneOfLiterals<'static, bool, 2> = one_of_literals([(",", true), ("]", false)]);
+const OBJECT_START: OneOfLiterals<'static, bool, 2> = one_of_literals([("\"", true), ("}", false)]);
+const OBJECT_NEXT: OneOfLiterals<'static, bool, 2> = one_of_literals([(",", true), ("}", false)]);
+const QUOTE: OneOfLiterals<'static, (), 1> = one_of_literals([("\"", ())]);
+const COLON: OneOfLiterals<'static, (), 1> = one_of_literals([(":", ())]);
+
+#[rustfmt::skip]
+const INT_LEAD: OneOfLiterals<'static, bool, 10> = one_of_literals([
+    ("0", false), ("1", true), ("2", true), ("3", true), ("4", true),
+    ("5", true), ("6", true), ("7", true), ("8", true), ("9", true),
+]);
+const MINUS: OneOfLiterals<'static, (), 1> = one_of_literals([("-", ())]);
+const POINT: OneOfLiterals<'static, (), 1> = one_of_literals([(".", ())]);
+const EXPONENT: OneOfLiterals<'static, (), 6> =
+    one_of_literals([("e", ()), ("E", ()), ("e+", ()), ("E+", ()), ("e-", ()), ("E-", ())]);
+
+/// Number lexeme, whose view is the slice it spans.
+struct NumberLexeme;
+
+impl<'src, I, Ext> Pattern<'src, I, Ext> for NumberLexeme
+where
+    I: InputSlice<'src>,
+    I::Slice: AsRef<[u8]>,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    type View<'tmp>
+        = &'tmp I::Slice
+    where
+        'src: 'tmp;
+
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        Pattern::<I, Ext>::__check(self, input, start.clone(), state, ctx, private::Token)
+            .raise_or_map(|end| (input.release_slice(start..end.clone()), end))
+    }
+
+    fn __check<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        let mut driver = Driver::<I, Ext> {
+            input,
+            state: &mut state,
+            ctx: &ctx,
+        };
+        let digits = |driver: &mut Driver<'_, 'src, I, Ext>, cur: I::Cursor| {
+            driver
+                .check(&take(DIGIT, 1..), cur.clone())
+                .map_err(|_| expected::<I, _>(cur, "a digit"))
+        };
+
+        let cur = driver.check(&MINUS, start.clone()).unwrap_or(start);
+        let (more, mut cur) = match driver.dispatch(&INT_LEAD, cur.clone()) {
+            Ok(res) => res,
+            Err(_) => return PResult::raise(expected::<I, _>(cur, "a digit")),
+        };
+        if more {
+            cur = trip!(driver.check(&take(DIGIT, ..), cur));
+        }
+        if let Ok(((), next)) = driver.dispatch(&POINT, cur.clone()) {
+            cur = trip!(digits(&mut driver, next));
+        }
+        if let Ok(((), next)) = driver.dispatch(&EXPONENT, cur.clone()) {
+            cur = trip!(digits(&mut driver, next));
+        }
+        PResult::emit(cur)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Recursive descent over the patterns above.
+struct Descent<'a, 'src, I, Ext>
+where
+    I: Input<'src>,
+    Ext: Extra<'src, I>,
+{
+    driver: Driver<'a, 'src, I, Ext>,
+    /// Remaining nesting allowed.
+    depth: usize,
+}
+
+impl<'src, I, Ext> Descent<'_, 'src, I, Ext>
+where
+    I: InputSlice<'src> + StaticInput,
+    I::Slice: AsRef<[u8]>,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    fn ws(&mut self, cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        self.driver.check(&take(WS, ..), cur)
+    }
+
+    fn text(&mut self, start: I::Cursor) -> Res<'src, I, Ext, Value<'src>> {
+        let cur = self.ws(start)?;
+        let (value, cur) = self.value(cur)?;
+        Ok((value, self.ws(cur)?))
+    }
+
+    fn value(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Value<'src>> {
+        let (lead, next) = self
+            .driver
+            .dispatch(&VALUE, cur.clone())
+            .map_err(|_| expected::<I, _>(cur.clone(), "a JSON value"))?;
+        match lead {
+            Lead::Object => self.nested(cur, next, Self::object),
+            Lead::Array => self.nested(cur, next, Self::array),
+            Lead::String => self.string(next).map(|(s, end)| (Value::String(s), end)),
+            Lead::Number => {
+                let (lexeme, end) = self.driver.capture(NumberLexeme, cur)?;
+                // SAFETY: Only ASCII bytes are matched by the lexeme.
+                let lexeme = unsafe { str::from_utf8_unchecked(AsRef::<[u8]>::as_ref(lexeme)) };
+                Ok((Value::Number(Number(lexeme)), end))
+            }
+            Lead::Null => Ok((Value::Null, next)),
+            Lead::True => Ok((Value::Bool(true), next)),
+            Lead::False => Ok((Value::Bool(false), next)),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        cur: I::Cursor,
+        next: I::Cursor,
+        f: fn(&mut Self, I::Cursor) -> Res<'src, I, Ext, Value<'src>>,
+    ) -> Res<'src, I, Ext, Value<'src>> {
+        if self.depth == 0 {
+            return Err(Ext::Error::new(I::span(cur..next), ErrorKind::Other(&DepthExceeded)));
+        }
+        self.depth -= 1;
+        let res = f(self, next);
+        self.depth += 1;
+        res
+    }
+
+    fn array(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Value<'src>> {
+        let mut items = Vec::new();
+        let mut cur = self.ws(cur)?;
+        if let Ok(((), end)) = self.driver.dispatch(&ARRAY_END, cur.clone()) {
+            return Ok((Value::Array(items), end));
+        }
+        loop {
+            let (item, next) = self.value(cur)?;
+            items.push(item);
+            let next = self.ws(next)?;
+            match self.driver.dispatch(&ARRAY_NEXT, next.clone()) {
+                Ok((true, after)) => cur = self.ws(after)?,
+                Ok((false, end)) => return Ok((Value::Array(items), end)),
+                Err(_) => return Err(expected::<I, _>(next, "`,` or `]`")),
+            }
+        }
+    }
+
+    fn object(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Value<'src>> {
+        let mut members = Vec::new();
+        let mut cur = self.ws(cur)?;
+        if let Ok((false, end)) = self.driver.dispatch(&OBJECT_START, cur.clone()) {
+            return Ok((Value::Object(members), end));
+        }
+        loop {
+            let (_, next) = self
+                .driver
+                .dispatch(&QUOTE, cur.clone())
+                .map_err(|_| expected::<I, _>(cur, "a member name"))?;
+            let (name, next) = self.string(next)?;
+            let next = self.ws(next)?;
+            let (_, next) = self
+                .driver
+                .dispatch(&COLON, next.clone())
+                .map_err(|_| expected::<I, _>(next, "`:`"))?;
+            let next = self.ws(next)?;
+            let (value, next) = self.value(next)?;
+            members.push((name, value));
+            let next = self.ws(next)?;
+            match self.driver.dispatch(&OBJECT_NEXT, next.clone()) {
+                Ok((true, after)) => cur = self.ws(after)?,
+                Ok((false, end)) => return Ok((Value::Object(members), end)),
+                Err(_) => return Err(expected::<I, _>(next, "`,` or `}`")),
+            }
+        }
+    }
+
+    /// Parses the rest of a string after the opening quote.
+    fn string(&mut self, mut cur: I::Cursor) -> Res<'src, I, Ext, Cow<'src, str>> {
+        let mut owned = None::<String>;
+        loop {
+            let (run, at) = self.driver.capture(take(except(SPECIAL), ..), cur.clone())?;
+            let run = utf8::<I, _>(AsRef::<[u8]>::as_ref(run), cur)?;
+            let (escape, next) = match self.driver.dispatch(&ESCAPE, at.clone()) {
+                Ok(res) => res,
+                Err(_) => {
+                    return Err(match self.driver.check(&take(CONTROL, 1), at.clone()) {
+                        Ok(end) => invalid::<I, _>(at..end),
+                        Err(_) => expected::<I, _>(at, "`\"`"),
+                    });
+                }
+            };
+            let ch = match escape {
+                Escape::End => {
+                    let text = match owned {
+                        None => Cow::Borrowed(run),
+                        Some(mut s) => {
+                            s.push_str(run);
+                            Cow::Owned(s)
+                        }
+                    };
+                    return Ok((text, next));
+                }
+                Escape::Char(ch) => {
+                    cur = next;
+                    ch
+                }
+                Escape::Unicode => {
+                    let (ch, end) = self.unicode(at, next)?;
+                    cur = end;
+                    ch
+                }
+                Escape::Bad => {
+                    return Err(match self.driver.check(&take(ANY, 1), next.clone()) {
+                        Ok(end) => invalid::<I, _>(at..end),
+                        Err(_) => expected::<I, _>(next, "an escape sequence"),
+                    });
+                }
+            };
+            let s = owned.get_or_insert_with(String::new);
+            s.push_str(run);
+            s.push(ch);
+        }
+    }
+
+    /// Parses the code after `\u`, where `at` is the backslash.
+    fn unicode(&mut self, at: I::Cursor, cur: I::Cursor) -> Res<'src, I, Ext, char> {
+        let (hi, pair) = self.hex4(cur)?;
+        match hi {
+            0xd800..0xdc00 => {
+                let (_, next) = self
+                    .driver
+                    .dispatch(&LOW_SURROGATE, pair.clone())
+                    .map_err(|_| expected::<I, _>(pair.clone(), "a low surrogate escape"))?;
+                let (lo, end) = self.hex4(next)?;
+                if !(0xdc00..0xe000).contains(&lo) {
+                    return Err(invalid::<I, _>(pair..end));
+                }
+                let code = 0x10000 + ((hi as u32 - 0xd800) << 10) + (lo as u32 - 0xdc00);
+                Ok((char::from_u32(code).unwrap(), end))
+            }
+            0xdc00..0xe000 => Err(invalid::<I, _>(at..pair)),
+            _ => Ok((char::from_u32(hi as u32).unwrap(), pair)),
+        }
+    }
+
+    fn hex4(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, u16> {
+        let (digits, end) = self.driver.capture(take(HEX, 4), cur)?;
+        let code = AsRef::<[u8]>::as_ref(digits)
+            .iter()
+            .fold(0, |code, b| code << 4 | (*b as char).to_digit(16).unwrap() as u16);
+        Ok((code, end))
+    }
+}
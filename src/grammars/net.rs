@@ -0,0 +1,391 @@
+//! Network addresses as patterns, to be composed into log and config grammars.
+//!
+//! ```
+//! use core::net::Ipv6Addr;
+//! use kaparser::{error::EmptyErr, grammars::net::*, parser::*, pattern::*};
+//!
+//! let addr = Parser::<&str, Ipv6Addr, EmptyErr>::fullmatch(&ipv6().captured(), "2001:db8::192.0.2.1");
+//! assert_eq!(addr.into_output(), Some("2001:db8::c000:201".parse().unwrap()));
+//!
+//! let net = Parser::<&str, IpNet, EmptyErr>::fullmatch(&cidr().strict().captured(), "10.0.0.0/8");
+//! assert!(net.into_output().unwrap().contains("10.1.2.3".parse().unwrap()));
+//! ```
+//!
+//! The textual forms are those of [RFC 4291](https://www.rfc-editor.org/rfc/rfc4291#section-2.2)
+//! with the precautions of [RFC 6943](https://www.rfc-editor.org/rfc/rfc6943#section-3.1.1):
+//! decimal octets shall not have leading zeros, which other tools may read as octal,
+//! and hexadecimal groups shall not exceed four digits.
+//!
+//! Patterns stop at the longest valid address, without caring about what follows,
+//! such as a port in `192.0.2.1:80`. As addresses are short, they also work over streaming inputs.
+
+use crate::grammars::*;
+use core::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    ops::Range,
+};
+
+/// An IPv4 address in dotted decimal, such as `192.0.2.1`.
+pub const fn ipv4() -> Ipv4 {
+    Ipv4
+}
+
+/// An IPv6 address, such as `2001:db8::1` or `::ffff:192.0.2.1`.
+pub const fn ipv6() -> Ipv6 {
+    Ipv6
+}
+
+/// Either an IPv4 or an IPv6 address.
+pub const fn ip() -> Ip {
+    Ip
+}
+
+/// An address followed by `/` and the prefix length, such as `192.0.2.0/24` or `2001:db8::/32`.
+pub const fn cidr() -> Cidr {
+    Cidr { strict: false }
+}
+
+/// A 48-bit MAC address, such as `00:1a:2b:3c:4d:5e`, `00-1A-2B-3C-4D-5E` or `001a.2b3c.4d5e`.
+pub const fn mac() -> Mac {
+    Mac
+}
+
+//------------------------------------------------------------------------------
+
+/// An IP network, or an interface address with the prefix length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpNet {
+    pub addr: IpAddr,
+    pub prefix_len: u8,
+}
+
+impl IpNet {
+    /// The address with host bits cleared.
+    pub fn network(&self) -> IpAddr {
+        match self.addr {
+            IpAddr::V4(addr) => IpAddr::V4(Ipv4Addr::from_bits(addr.to_bits() & mask32(self.prefix_len))),
+            IpAddr::V6(addr) => IpAddr::V6(Ipv6Addr::from_bits(addr.to_bits() & mask128(self.prefix_len))),
+        }
+    }
+
+    /// Whether `addr` is of the same family and in the network.
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self.addr, addr) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => (net.to_bits() ^ addr.to_bits()) & mask32(self.prefix_len) == 0,
+            (IpAddr::V6(net), IpAddr::V6(addr)) => (net.to_bits() ^ addr.to_bits()) & mask128(self.prefix_len) == 0,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+fn mask32(len: u8) -> u32 {
+    u32::MAX.checked_shl(32 - len as u32).unwrap_or(0)
+}
+
+fn mask128(len: u8) -> u128 {
+    u128::MAX.checked_shl(128 - len as u32).unwrap_or(0)
+}
+
+/// Displayed in lowercase and separated by colons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ipv4;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ipv6;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ip;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Cidr {
+    strict: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Mac;
+
+impl Cidr {
+    /// Rejects host bits set after the prefix, as in `192.0.2.1/24`.
+    pub const fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+}
+
+impl_pattern_for_scanner! {
+    Ipv4 => Ipv4Addr, |_this, s| s.ipv4();
+    Ipv6 => Ipv6Addr, |_this, s| s.ipv6();
+    Ip => IpAddr, |_this, s| s.ip();
+    Cidr => IpNet, |this, s| s.cidr(this.strict);
+    Mac => MacAddr, |_this, s| s.mac();
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct Malformed(&'static str);
+
+impl fmt::Display for Malformed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl core::error::Error for Malformed {}
+
+static LEADING_ZERO: Malformed = Malformed("leading zeros in a decimal number");
+static LONG_GROUP: Malformed = Malformed("more than four hexadecimal digits in a group");
+static SECOND_GAP: Malformed = Malformed("`::` shall appear only once");
+static EMPTY_GAP: Malformed = Malformed("`::` shall stand for at least one group");
+static MISPLACED_IPV4: Malformed = Malformed("embedded IPv4 address shall take the last 32 bits");
+static LONG_PREFIX: Malformed = Malformed("prefix length exceeds the address");
+static HOST_BITS: Malformed = Malformed("host bits set after the prefix");
+
+fn malformed(range: Range<usize>, what: &'static Malformed) -> Failure {
+    Failure {
+        range,
+        kind: FailureKind::Other(what),
+    }
+}
+
+type Res<T> = Result<T, Failure>;
+
+/// Also usable on a complete slice by grammars embedding addresses, where `hit_end` is ignored.
+pub(crate) struct Scanner<'a> {
+    bytes: &'a [u8],
+    pub(crate) pos: usize,
+    pub(crate) hit_end: bool,
+}
+
+impl<'a> Scanner<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            hit_end: false,
+        }
+    }
+
+    fn peek_at(&mut self, off: usize) -> Option<u8> {
+        let b = self.bytes.get(self.pos + off).copied();
+        self.hit_end |= b.is_none();
+        b
+    }
+
+    /// Counts bytes satisfying `f` from the position without consuming them.
+    fn count(&mut self, f: impl Fn(&u8) -> bool) -> usize {
+        let mut n = 0;
+        while self.peek_at(n).is_some_and(|b| f(&b)) {
+            n += 1;
+        }
+        n
+    }
+
+    fn expect(&mut self, byte: u8, what: &'static str) -> Res<()> {
+        match self.peek_at(0) == Some(byte) {
+            true => {
+                self.pos += 1;
+                Ok(())
+            }
+            false => Err(Failure::expected(self.pos, what)),
+        }
+    }
+
+    /// Decimal digits without leading zeros, up to `max`.
+    fn decimal(&mut self, max: u32, what: &'static str, too_large: Failure) -> Res<u32> {
+        let start = self.pos;
+        let len = self.count(u8::is_ascii_digit);
+        let range = start..start + len;
+        let digits = &self.bytes[range.clone()];
+        match digits {
+            [] => return Err(Failure::expected(start, what)),
+            [b'0', _, ..] => return Err(malformed(range, &LEADING_ZERO)),
+            _ => (),
+        }
+        let val = digits
+            .iter()
+            .try_fold(0u32, |acc, d| acc.checked_mul(10)?.checked_add((d - b'0') as u32))
+            .filter(|val| *val <= max);
+        self.pos += len;
+        val.ok_or(Failure { range, ..too_large })
+    }
+
+    fn octet(&mut self) -> Res<u8> {
+        let start = self.pos;
+        let overflow = Failure {
+            range: start..start,
+            kind: FailureKind::Overflow,
+        };
+        self.decimal(255, "a decimal octet", overflow).map(|val| val as u8)
+    }
+
+    /// Whether decimal digits followed by `.` come next.
+    fn at_ipv4(&mut self) -> bool {
+        let n = self.count(u8::is_ascii_digit);
+        n > 0 && self.peek_at(n) == Some(b'.')
+    }
+
+    pub(crate) fn ipv4(&mut self) -> Res<Ipv4Addr> {
+        let a = self.octet()?;
+        self.expect(b'.', "`.`")?;
+        let b = self.octet()?;
+        self.expect(b'.', "`.`")?;
+        let c = self.octet()?;
+        self.expect(b'.', "`.`")?;
+        let d = self.octet()?;
+        Ok(Ipv4Addr::new(a, b, c, d))
+    }
+
+    fn group(&mut self) -> Res<u16> {
+        let start = self.pos;
+        let len = self.count(u8::is_ascii_hexdigit);
+        match len {
+            0 => return Err(Failure::expected(start, "a hexadecimal group")),
+            5.. => return Err(malformed(start..start + len, &LONG_GROUP)),
+            _ => (),
+        }
+        self.pos += len;
+        Ok(self.bytes[start..start + len].iter().fold(0, |acc, d| {
+            acc << 4 | (*d as char).to_digit(16).unwrap_or_default() as u16
+        }))
+    }
+
+    pub(crate) fn ipv6(&mut self) -> Res<Ipv6Addr> {
+        let mut groups = [0u16; 8];
+        let mut count = 0;
+        // Where `::` is, as the index of the following group and the byte offset.
+        let mut gap = None;
+
+        if self.peek_at(0) == Some(b':') && self.peek_at(1) == Some(b':') {
+            gap = Some((0, self.pos));
+            self.pos += 2;
+        }
+
+        loop {
+            if gap.is_some() && !self.peek_at(0).is_some_and(|b| b.is_ascii_hexdigit()) {
+                break;
+            }
+            if self.at_ipv4() {
+                let start = self.pos;
+                let ipv4 = self.ipv4()?;
+                let fits = match gap {
+                    Some(_) => count <= 5,
+                    None => count == 6,
+                };
+                if !fits {
+                    return Err(malformed(start..self.pos, &MISPLACED_IPV4));
+                }
+                let [a, b, c, d] = ipv4.octets();
+                groups[count] = u16::from_be_bytes([a, b]);
+                groups[count + 1] = u16::from_be_bytes([c, d]);
+                count += 2;
+                break;
+            }
+
+            groups[count] = self.group()?;
+            count += 1;
+
+            if count == 8 || self.peek_at(0) != Some(b':') {
+                break;
+            }
+            match self.peek_at(1) {
+                Some(b':') if gap.is_some() => return Err(malformed(self.pos..self.pos + 2, &SECOND_GAP)),
+                Some(b':') => {
+                    gap = Some((count, self.pos));
+                    self.pos += 2;
+                }
+                Some(b) if b.is_ascii_hexdigit() => self.pos += 1,
+                _ => break,
+            }
+        }
+
+        match gap {
+            None if count < 8 => return Err(Failure::expected(self.pos, "`:`")),
+            None => (),
+            Some((_, at)) if count == 8 => return Err(malformed(at..at + 2, &EMPTY_GAP)),
+            Some((index, _)) => {
+                let moved = count - index;
+                groups.copy_within(index..count, 8 - moved);
+                groups[index..8 - moved].fill(0);
+            }
+        }
+        Ok(Ipv6Addr::from(groups))
+    }
+
+    fn ip(&mut self) -> Res<IpAddr> {
+        match self.at_ipv4() {
+            true => self.ipv4().map(IpAddr::V4),
+            false => self.ipv6().map(IpAddr::V6),
+        }
+    }
+
+    fn cidr(&mut self, strict: bool) -> Res<IpNet> {
+        let start = self.pos;
+        let addr = self.ip()?;
+        self.expect(b'/', "`/`")?;
+        let max = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let too_long = malformed(0..0, &LONG_PREFIX);
+        let prefix_len = self.decimal(max, "a prefix length", too_long)? as u8;
+        let net = IpNet { addr, prefix_len };
+        if strict && net.network() != addr {
+            return Err(malformed(start..self.pos, &HOST_BITS));
+        }
+        Ok(net)
+    }
+
+    fn mac(&mut self) -> Res<MacAddr> {
+        let mut octets = [0u8; 6];
+        let hex = |b: &u8| (*b as char).to_digit(16).unwrap_or_default() as u8;
+
+        let dotted = self.count(u8::is_ascii_hexdigit) == 4 && self.peek_at(4) == Some(b'.');
+        let (groups, width, sep, what) = match (dotted, self.peek_at(2)) {
+            (true, _) => (3, 4, b'.', "`.`"),
+            (false, Some(b'-')) => (6, 2, b'-', "`-`"),
+            _ => (6, 2, b':', "`:`"),
+        };
+
+        for i in 0..groups {
+            if i > 0 {
+                self.expect(sep, what)?;
+            }
+            let start = self.pos;
+            let len = self.count(u8::is_ascii_hexdigit);
+            if len != width {
+                return Err(Failure {
+                    range: start..start + len,
+                    kind: FailureKind::Expected(Expect(match width {
+                        2 => "two hexadecimal digits",
+                        _ => "four hexadecimal digits",
+                    })),
+                });
+            }
+            for (j, pair) in self.bytes[start..start + len].chunks(2).enumerate() {
+                octets[i * width / 2 + j] = hex(&pair[0]) << 4 | hex(&pair[1]);
+            }
+            self.pos += len;
+        }
+        Ok(MacAddr(octets))
+    }
+}
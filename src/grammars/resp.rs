@@ -0,0 +1,528 @@
+//! Frames of the [Redis serialization protocol](https://redis.io/docs/latest/develop/reference/protocol-spec/),
+//! both RESP2 and RESP3, decoded from a stream of bytes.
+//!
+//! ```
+//! use kaparser::{error::EmptyErr, grammars::resp::*, input::partial::PartialInput, parser::*};
+//!
+//! let buf = b"*2\r\n$4\r\nECHO\r\n$5\r\nhello\r\n";
+//!
+//! // The frame has not been fully received, so read more into `buf` and decode again.
+//! let mut input = PartialInput::new(&buf[..20]);
+//! assert!(Parser::<_, Frame, EmptyErr>::parse(&frame(), &mut input, 0).into_output().is_none());
+//! assert!(input.is_incomplete());
+//!
+//! let mut input = PartialInput::new(&buf[..]);
+//! let (frame, end) = Parser::<_, Frame, EmptyErr>::parse(&frame(), &mut input, 0).into_output().unwrap();
+//! assert_eq!(frame, Frame::Array(vec![Frame::Bulk(b"ECHO"), Frame::Bulk(b"hello")]));
+//! // Then drop `buf[..end]`.
+//! assert_eq!(end, buf.len());
+//! ```
+//!
+//! Whenever the bytes fetched so far end in the middle of a frame, more are fetched as long as `eof == false`,
+//! so that over [`PartialInput`](crate::input::partial::PartialInput) incomplete frames are told apart from
+//! malformed ones. The scan then resumes after the last complete element, and bulk payloads are borrowed
+//! once fully received.
+//!
+//! Streamed strings and aggregates of unknown length (`$?` and `*?`) are not supported.
+
+use crate::{common::*, extra::*, grammars::*, parser::*, private};
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, ops::Range, str};
+
+/// Decodes a single frame, including all its elements.
+pub const fn frame() -> Resp {
+    Resp {
+        resp2: false,
+        max_bulk_len: 512 * 1024 * 1024,
+        max_elements: 1024 * 1024,
+        max_depth: 128,
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame<'src> {
+    /// `+`, without CR or LF.
+    Simple(&'src [u8]),
+    /// `-`, starting with an error code by convention.
+    Error(&'src [u8]),
+    /// `:`
+    Integer(i64),
+    /// `$`
+    Bulk(&'src [u8]),
+    /// `_` in RESP3, or the null bulk string `$-1` and null array `*-1` in RESP2.
+    Null,
+    /// `*`
+    Array(Vec<Frame<'src>>),
+    /// `#`
+    Boolean(bool),
+    /// `,` with `inf`, `-inf` and `nan` included.
+    Double(f64),
+    /// `(` as an optionally signed decimal number.
+    BigNumber(&'src str),
+    /// `!`
+    BulkError(&'src [u8]),
+    /// `=` with a format such as `txt` or `mkd`.
+    Verbatim { format: &'src str, text: &'src [u8] },
+    /// `%`
+    Map(Vec<(Frame<'src>, Frame<'src>)>),
+    /// `|` followed by the frame the attributes are about.
+    Attribute {
+        attrs: Vec<(Frame<'src>, Frame<'src>)>,
+        frame: Box<Frame<'src>>,
+    },
+    /// `~`
+    Set(Vec<Frame<'src>>),
+    /// `>` with the kind of push data first.
+    Push(Vec<Frame<'src>>),
+}
+
+impl<'src> Frame<'src> {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Frame::Null)
+    }
+
+    /// Whether it is [`Frame::Error`] or [`Frame::BulkError`].
+    pub fn is_error(&self) -> bool {
+        matches!(self, Frame::Error(_) | Frame::BulkError(_))
+    }
+
+    /// The bytes of simple, bulk and verbatim strings, and of errors.
+    pub fn as_bytes(&self) -> Option<&'src [u8]> {
+        match *self {
+            Frame::Simple(bytes) | Frame::Error(bytes) | Frame::Bulk(bytes) | Frame::BulkError(bytes) => Some(bytes),
+            Frame::Verbatim { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match *self {
+            Frame::Integer(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// The elements of arrays, sets and pushes.
+    pub fn as_slice(&self) -> Option<&[Frame<'src>]> {
+        match self {
+            Frame::Array(frames) | Frame::Set(frames) | Frame::Push(frames) => Some(frames),
+            _ => None,
+        }
+    }
+
+    /// Without the attributes if any.
+    pub fn without_attrs(&self) -> &Self {
+        match self {
+            Frame::Attribute { frame, .. } => frame.without_attrs(),
+            frame => frame,
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+pub struct Resp {
+    resp2: bool,
+    max_bulk_len: usize,
+    max_elements: usize,
+    max_depth: usize,
+}
+
+impl Resp {
+    /// Rejects the types introduced in RESP3.
+    pub const fn resp2(mut self) -> Self {
+        self.resp2 = true;
+        self
+    }
+
+    /// Bytes of a bulk payload or a line, 512 MiB by default as in Redis.
+    pub const fn max_bulk_len(mut self, n: usize) -> Self {
+        self.max_bulk_len = n;
+        self
+    }
+
+    /// Elements of an aggregate, or pairs of a map, 1 Mi by default.
+    pub const fn max_elements(mut self, n: usize) -> Self {
+        self.max_elements = n;
+        self
+    }
+
+    /// Levels of nested aggregates, 128 by default.
+    pub const fn max_depth(mut self, n: usize) -> Self {
+        self.max_depth = n;
+        self
+    }
+}
+
+impl<'src, I, Ext> Parser<'src, I, Frame<'src>, Ext> for Resp
+where
+    I: InputSlice<'src> + StaticInput,
+    I::Slice: AsRef<[u8]>,
+    Ext: Extra<'src, I>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Frame<'src>, I::Cursor), Ext::Error> {
+        let end = trip!(measure(self, input, start.clone()));
+        let mut driver = Driver::<I, Ext> {
+            input,
+            state: &mut state,
+            ctx: &ctx,
+        };
+        let (bytes, _) = trip!(driver.capture(SliceTo(end.clone()), start.clone()));
+
+        match Scanner::new(bytes.as_ref(), self).frame(0) {
+            Ok(frame) => PResult::emit((frame, end)),
+            Err(failure) => PResult::raise(failure.raise::<I, _>(start)),
+        }
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        Parser::<I, Frame, Ext>::__parse(self, input, start, state, ctx, private::Token).raise_or_map(|(_, cur)| cur)
+    }
+}
+
+/// Finds the end of the frame, where the scan is rerun from the last complete element whenever more is fetched.
+fn measure<'src, I, E>(opts: &Resp, input: &mut I, start: I::Cursor) -> Result<I::Cursor, E>
+where
+    I: InputByteSlice<'src>,
+    E: Error,
+{
+    let mut pending = Vec::from([(1, 0)]);
+    let mut mark = 0;
+    let mut more = false;
+    loop {
+        let (bytes, eof) = match more {
+            false => input.fetch_byte_slice(start.clone())?,
+            true => input.fetch_more_byte_slice(start.clone())?,
+        };
+        let mut scanner = Scanner::new(bytes, opts);
+        scanner.pos = mark;
+        let res = scanner.skim(&mut pending, &mut mark);
+
+        if scanner.hit_end && !eof {
+            more = true;
+            continue;
+        }
+
+        return match res {
+            Ok(()) => Ok(I::bump_cursor(start, scanner.pos)),
+            Err(failure) => Err(failure.raise::<I, _>(start)),
+        };
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct Malformed(&'static str);
+
+impl fmt::Display for Malformed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl core::error::Error for Malformed {}
+
+static BULK_TOO_LONG: Malformed = Malformed("bulk length exceeds the limit");
+static TOO_MANY_ELEMENTS: Malformed = Malformed("aggregate length exceeds the limit");
+static TOO_DEEP: Malformed = Malformed("aggregates nested too deep");
+static BARE_LF: Malformed = Malformed("LF without CR");
+static NEGATIVE_LENGTH: Malformed = Malformed("negative length other than -1");
+static NULL_LENGTH: Malformed = Malformed("length of -1 other than for a bulk string or an array");
+
+fn malformed(range: Range<usize>, what: &'static Malformed) -> Failure {
+    Failure {
+        range,
+        kind: FailureKind::Other(what),
+    }
+}
+
+type Res<T> = Result<T, Failure>;
+
+/// A frame without its elements, which follow.
+enum Node<'src> {
+    Leaf(Frame<'src>),
+    /// `*`, `~` or `>` with the number of elements.
+    Elements(u8, usize),
+    /// `%` or `|` with the number of pairs.
+    Pairs(u8, usize),
+}
+
+struct Scanner<'src, 'a> {
+    bytes: &'src [u8],
+    pos: usize,
+    hit_end: bool,
+    opts: &'a Resp,
+}
+
+impl<'src, 'a> Scanner<'src, 'a> {
+    fn new(bytes: &'src [u8], opts: &'a Resp) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            hit_end: false,
+            opts,
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        let b = self.bytes.get(self.pos).copied();
+        self.hit_end |= b.is_none();
+        b
+    }
+
+    /// Up to and excluding CRLF, which is then consumed.
+    fn line(&mut self) -> Res<&'src [u8]> {
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(b'\r') => break,
+                Some(b'\n') => return Err(malformed(self.pos..self.pos + 1, &BARE_LF)),
+                Some(_) if self.pos - start >= self.opts.max_bulk_len => {
+                    return Err(malformed(self.pos..self.pos, &BULK_TOO_LONG));
+                }
+                Some(_) => self.pos += 1,
+                None => return Err(Failure::expected(self.pos, "`\\r\\n`")),
+            }
+        }
+        let line = &self.bytes[start..self.pos];
+        self.crlf()?;
+        Ok(line)
+    }
+
+    fn crlf(&mut self) -> Res<()> {
+        for b in [b'\r', b'\n'] {
+            match self.peek() == Some(b) {
+                true => self.pos += 1,
+                false => return Err(Failure::expected(self.pos, "`\\r\\n`")),
+            }
+        }
+        Ok(())
+    }
+
+    fn integer(&mut self) -> Res<i64> {
+        let start = self.pos;
+        let line = self.line()?;
+        let (negative, digits) = match line {
+            [b'-', digits @ ..] => (true, digits),
+            [b'+', digits @ ..] => (false, digits),
+            digits => (false, digits),
+        };
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+            return Err(Failure::expected(start, "an integer"));
+        }
+        let overflow = Failure {
+            range: start..start + line.len(),
+            kind: FailureKind::Overflow,
+        };
+        digits.iter().try_fold(0i64, |acc, d| {
+            let acc = acc.checked_mul(10).ok_or(overflow.clone())?;
+            let d = (d - b'0') as i64;
+            match negative {
+                true => acc.checked_sub(d),
+                false => acc.checked_add(d),
+            }
+            .ok_or(overflow.clone())
+        })
+    }
+
+    /// `None` for `-1`.
+    fn length(&mut self, limit: usize, too_long: &'static Malformed) -> Res<Option<usize>> {
+        let start = self.pos;
+        let n = self.integer()?;
+        let range = start..self.pos - 2;
+        match n {
+            -1 => Ok(None),
+            ..0 => Err(malformed(range, &NEGATIVE_LENGTH)),
+            n if n as u64 > limit as u64 => Err(malformed(range, too_long)),
+            n => Ok(Some(n as usize)),
+        }
+    }
+
+    /// Where `-1` is not allowed, as only bulk strings and arrays may be null.
+    fn non_null<T>(&mut self, scan: impl FnOnce(&mut Self) -> Res<Option<T>>) -> Res<T> {
+        let start = self.pos;
+        scan(self)?.ok_or_else(|| malformed(start..self.pos - 2, &NULL_LENGTH))
+    }
+
+    fn bulk(&mut self) -> Res<Option<&'src [u8]>> {
+        let start = self.pos;
+        let Some(len) = self.length(self.opts.max_bulk_len, &BULK_TOO_LONG)? else {
+            return Ok(None);
+        };
+        // With a limit up to `usize::MAX`, the end may overflow on 32-bit targets.
+        let end = self
+            .pos
+            .checked_add(len)
+            .ok_or_else(|| malformed(start..self.pos - 2, &BULK_TOO_LONG))?;
+        if end > self.bytes.len() {
+            self.hit_end = true;
+            return Err(Failure::expected(self.bytes.len(), "bulk data"));
+        }
+        let data = &self.bytes[self.pos..end];
+        self.pos = end;
+        self.crlf()?;
+        Ok(Some(data))
+    }
+
+    fn elements(&mut self, len: usize, depth: usize) -> Res<Vec<Frame<'src>>> {
+        // Each element takes at least three bytes, so the declared length is not trusted for allocation.
+        let mut frames = Vec::with_capacity(len.min((self.bytes.len() - self.pos) / 3));
+        for _ in 0..len {
+            frames.push(self.frame(depth + 1)?);
+        }
+        Ok(frames)
+    }
+
+    fn pairs(&mut self, len: usize, depth: usize) -> Res<Vec<(Frame<'src>, Frame<'src>)>> {
+        let mut pairs = Vec::with_capacity(len.min((self.bytes.len() - self.pos) / 6));
+        for _ in 0..len {
+            pairs.push((self.frame(depth + 1)?, self.frame(depth + 1)?));
+        }
+        Ok(pairs)
+    }
+
+    fn frame(&mut self, depth: usize) -> Res<Frame<'src>> {
+        Ok(match self.node(depth)? {
+            Node::Leaf(frame) => frame,
+            Node::Elements(kind, len) => {
+                let frames = self.elements(len, depth)?;
+                match kind {
+                    b'*' => Frame::Array(frames),
+                    b'~' => Frame::Set(frames),
+                    _ => Frame::Push(frames),
+                }
+            }
+            Node::Pairs(b'%', len) => Frame::Map(self.pairs(len, depth)?),
+            Node::Pairs(_, len) => {
+                let attrs = self.pairs(len, depth)?;
+                Frame::Attribute {
+                    attrs,
+                    frame: Box::new(self.frame(depth)?),
+                }
+            }
+        })
+    }
+
+    /// Scans frames without keeping them, given the frames left at each level of nesting with their depth,
+    /// and marks the end of the last complete one.
+    fn skim(&mut self, pending: &mut Vec<(usize, usize)>, mark: &mut usize) -> Res<()> {
+        while let Some(&(left, depth)) = pending.last() {
+            if left == 0 {
+                pending.pop();
+                continue;
+            }
+            let node = self.node(depth)?;
+            let top = pending.len() - 1;
+            pending[top].0 -= 1;
+            match node {
+                Node::Leaf(_) => {}
+                Node::Elements(_, len) => pending.push((len, depth + 1)),
+                // Keys and values.
+                Node::Pairs(b'%', len) => pending.extend([(len, depth + 1), (len, depth + 1)]),
+                // Then the frame the attributes are about.
+                Node::Pairs(_, len) => pending.extend([(1, depth), (len, depth + 1), (len, depth + 1)]),
+            }
+            *mark = self.pos;
+        }
+        Ok(())
+    }
+
+    fn node(&mut self, depth: usize) -> Res<Node<'src>> {
+        let start = self.pos;
+        if depth > self.opts.max_depth {
+            return Err(malformed(start..start, &TOO_DEEP));
+        }
+        let Some(kind) = self.peek() else {
+            return Err(Failure::expected(start, "a frame"));
+        };
+        if self.opts.resp2 && !b"+-:$*".contains(&kind) {
+            return Err(Failure::expected(start, "a RESP2 frame"));
+        }
+        self.pos += 1;
+
+        Ok(Node::Leaf(match kind {
+            b'+' => Frame::Simple(self.line()?),
+            b'-' => Frame::Error(self.line()?),
+            b':' => Frame::Integer(self.integer()?),
+            b'$' => self.bulk()?.map_or(Frame::Null, Frame::Bulk),
+            b'*' => match self.length(self.opts.max_elements, &TOO_MANY_ELEMENTS)? {
+                Some(len) => return Ok(Node::Elements(kind, len)),
+                None => Frame::Null,
+            },
+            b'_' => {
+                self.crlf()?;
+                Frame::Null
+            }
+            b'#' => {
+                let value = match self.peek() {
+                    Some(b't') => true,
+                    Some(b'f') => false,
+                    _ => return Err(Failure::expected(self.pos, "`t` or `f`")),
+                };
+                self.pos += 1;
+                self.crlf()?;
+                Frame::Boolean(value)
+            }
+            b',' => {
+                let at = self.pos;
+                let line = self.line()?;
+                let valid = line.iter().all(|b| b.is_ascii_alphanumeric() || b"+-.".contains(b));
+                // SAFETY: Only ASCII bytes are accepted.
+                match valid.then(|| unsafe { str::from_utf8_unchecked(line) }.parse()) {
+                    Some(Ok(value)) => Frame::Double(value),
+                    _ => return Err(Failure::invalid(at..at + line.len())),
+                }
+            }
+            b'(' => {
+                let at = self.pos;
+                let line = self.line()?;
+                let digits = line.strip_prefix(b"-").or(line.strip_prefix(b"+")).unwrap_or(line);
+                if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+                    return Err(Failure::expected(at, "a big number"));
+                }
+                // SAFETY: Only ASCII bytes are accepted.
+                Frame::BigNumber(unsafe { str::from_utf8_unchecked(line) })
+            }
+            b'!' => Frame::BulkError(self.non_null(Self::bulk)?),
+            b'=' => {
+                let at = self.pos;
+                let data = self.non_null(Self::bulk)?;
+                match data.split_at_checked(3) {
+                    Some((format, [b':', text @ ..])) if format.is_ascii() => Frame::Verbatim {
+                        // SAFETY: Checked to be ASCII.
+                        format: unsafe { str::from_utf8_unchecked(format) },
+                        text,
+                    },
+                    _ => return Err(Failure::expected(at, "a verbatim string with a format of three bytes")),
+                }
+            }
+            b'%' | b'|' => {
+                let len = self.non_null(|s| s.length(s.opts.max_elements, &TOO_MANY_ELEMENTS))?;
+                return Ok(Node::Pairs(kind, len));
+            }
+            b'~' | b'>' => {
+                let len = self.non_null(|s| s.length(s.opts.max_elements, &TOO_MANY_ELEMENTS))?;
+                return Ok(Node::Elements(kind, len));
+            }
+            _ => return Err(Failure::expected(start, "a frame")),
+        }))
+    }
+}
@@ -0,0 +1,684 @@
+//! [Semantic Versioning 2.0.0](https://semver.org/spec/v2.0.0.html), and Cargo-style version requirements.
+//!
+//! ```
+//! use kaparser::{error::EmptyErr, grammars::semver::*, parser::*};
+//!
+//! let version = Parser::<&str, Version, EmptyErr>::fullmatch(&semver(), "1.4.0-rc.1+build.5")
+//!     .into_output()
+//!     .unwrap();
+//! assert_eq!((version.major, version.minor, version.patch), (1, 4, 0));
+//! assert_eq!(version.pre.identifiers().collect::<Vec<_>>(), ["rc", "1"]);
+//! assert!(version < Version::new(1, 4, 0));
+//! ```
+//!
+//! Pre-release and build identifiers are borrowed from the input.
+
+use crate::{common::*, extra::*, grammars::*, literal::*, number::*, parser::*, predicate::*, private};
+use core::{cmp::Ordering, fmt};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Ordered by precedence, then by build metadata to be consistent with [`Eq`],
+/// see [`cmp_precedence`](Self::cmp_precedence) for the precedence alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Version<'src> {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Prerelease<'src>,
+    pub build: BuildMetadata<'src>,
+}
+
+impl Version<'_> {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: Prerelease::EMPTY,
+            build: BuildMetadata::EMPTY,
+        }
+    }
+
+    /// Ignores build metadata, as the specification requires.
+    pub fn cmp_precedence(&self, other: &Version<'_>) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| self.pre.cmp(&other.pre))
+    }
+}
+
+impl fmt::Display for Version<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build)?;
+        }
+        Ok(())
+    }
+}
+
+/// Dot-separated identifiers after `-`, empty for releases.
+///
+/// A pre-release has lower precedence than the release, and identifiers are compared one by one:
+/// numeric ones by value, below alphanumeric ones compared in ASCII order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Prerelease<'src>(&'src str);
+
+/// Dot-separated identifiers after `+`, empty if absent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct BuildMetadata<'src>(&'src str);
+
+impl<'src> Prerelease<'src> {
+    pub const EMPTY: Self = Self("");
+
+    pub fn as_str(&self) -> &'src str {
+        self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn identifiers(&self) -> impl Iterator<Item = &'src str> + use<'src> {
+        self.0.split('.').filter(|id| !id.is_empty())
+    }
+}
+
+impl<'src> BuildMetadata<'src> {
+    pub const EMPTY: Self = Self("");
+
+    pub fn as_str(&self) -> &'src str {
+        self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn identifiers(&self) -> impl Iterator<Item = &'src str> + use<'src> {
+        self.0.split('.').filter(|id| !id.is_empty())
+    }
+}
+
+impl Ord for Prerelease<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => (),
+        }
+
+        let numeric = |id: &str| id.bytes().all(|b| b.is_ascii_digit());
+        let mut lhs = self.identifiers();
+        let mut rhs = other.identifiers();
+        loop {
+            let ord = match (lhs.next(), rhs.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                // Without leading zeros, longer numbers are greater, which never overflows.
+                (Some(a), Some(b)) => match (numeric(a), numeric(b)) {
+                    (true, true) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (false, false) => a.cmp(b),
+                },
+            };
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+    }
+}
+
+impl PartialOrd for Prerelease<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Prerelease<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for BuildMetadata<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Comma-separated comparators that shall all match, where `*` alone matches any release.
+///
+/// Pre-releases only match if some comparator has the same `major.minor.patch` and a pre-release as well,
+/// so that `>=1.2.3-beta` matches `1.2.3-rc` but not `1.3.0-rc`.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct VersionReq<'src> {
+    pub comparators: Vec<Comparator<'src>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Comparator<'src> {
+    pub op: Op,
+    pub major: u64,
+    /// `None` if omitted or a wildcard.
+    pub minor: Option<u64>,
+    /// `None` if omitted or a wildcard.
+    pub patch: Option<u64>,
+    /// Only given along with the patch.
+    pub pre: Prerelease<'src>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// `=1.2.3`, and `=1.2` for `>=1.2.0, <1.3.0`.
+    Exact,
+    /// `>1.2.3`
+    Greater,
+    /// `>=1.2.3`
+    GreaterEq,
+    /// `<1.2.3`
+    Less,
+    /// `<=1.2.3`
+    LessEq,
+    /// `~1.2.3` for `>=1.2.3, <1.3.0`.
+    Tilde,
+    /// `^1.2.3` or `1.2.3` for `>=1.2.3, <2.0.0`, and `^0.2.3` for `>=0.2.3, <0.3.0`.
+    Caret,
+    /// `1.*` or `1.2.x`, the same as the exact requirement on the given parts.
+    Wildcard,
+}
+
+#[cfg(feature = "alloc")]
+impl VersionReq<'_> {
+    pub fn matches(&self, version: &Version<'_>) -> bool {
+        self.comparators.iter().all(|cmp| cmp.matches_ignoring_pre(version))
+            && (version.pre.is_empty() || self.comparators.iter().any(|cmp| cmp.allows_pre(version)))
+    }
+}
+
+impl Comparator<'_> {
+    /// Matches the comparator alone, where pre-releases only match if the comparator has the same
+    /// `major.minor.patch` and a pre-release as well.
+    pub fn matches(&self, version: &Version<'_>) -> bool {
+        self.matches_ignoring_pre(version) && (version.pre.is_empty() || self.allows_pre(version))
+    }
+
+    fn allows_pre(&self, ver: &Version<'_>) -> bool {
+        !self.pre.is_empty() && (self.major, self.minor, self.patch) == (ver.major, Some(ver.minor), Some(ver.patch))
+    }
+
+    fn matches_ignoring_pre(&self, ver: &Version<'_>) -> bool {
+        match self.op {
+            Op::Exact | Op::Wildcard => self.matches_exact(ver),
+            Op::Greater => self.matches_greater(ver),
+            Op::GreaterEq => self.matches_exact(ver) || self.matches_greater(ver),
+            Op::Less => self.matches_less(ver),
+            Op::LessEq => self.matches_exact(ver) || self.matches_less(ver),
+            Op::Tilde => self.matches_tilde(ver),
+            Op::Caret => self.matches_caret(ver),
+        }
+    }
+
+    fn matches_exact(&self, ver: &Version<'_>) -> bool {
+        ver.major == self.major
+            && self.minor.is_none_or(|minor| ver.minor == minor)
+            && self.patch.is_none_or(|patch| ver.patch == patch)
+            && ver.pre == self.pre
+    }
+
+    fn matches_greater(&self, ver: &Version<'_>) -> bool {
+        if ver.major != self.major {
+            return ver.major > self.major;
+        }
+        match self.minor {
+            None => return false,
+            Some(minor) if ver.minor != minor => return ver.minor > minor,
+            Some(_) => (),
+        }
+        match self.patch {
+            None => return false,
+            Some(patch) if ver.patch != patch => return ver.patch > patch,
+            Some(_) => (),
+        }
+        ver.pre > self.pre
+    }
+
+    fn matches_less(&self, ver: &Version<'_>) -> bool {
+        if ver.major != self.major {
+            return ver.major < self.major;
+        }
+        match self.minor {
+            None => return false,
+            Some(minor) if ver.minor != minor => return ver.minor < minor,
+            Some(_) => (),
+        }
+        match self.patch {
+            None => return false,
+            Some(patch) if ver.patch != patch => return ver.patch < patch,
+            Some(_) => (),
+        }
+        ver.pre < self.pre
+    }
+
+    fn matches_tilde(&self, ver: &Version<'_>) -> bool {
+        if ver.major != self.major {
+            return false;
+        }
+        match self.minor {
+            None => return true,
+            Some(minor) if ver.minor != minor => return false,
+            Some(_) => (),
+        }
+        match self.patch {
+            None => true,
+            Some(patch) if ver.patch != patch => ver.patch > patch,
+            Some(_) => ver.pre >= self.pre,
+        }
+    }
+
+    fn matches_caret(&self, ver: &Version<'_>) -> bool {
+        if ver.major != self.major {
+            return false;
+        }
+        let Some(minor) = self.minor else {
+            return true;
+        };
+        let Some(patch) = self.patch else {
+            return match self.major {
+                0 => ver.minor == minor,
+                _ => ver.minor >= minor,
+            };
+        };
+
+        if self.major > 0 {
+            if ver.minor != minor {
+                return ver.minor > minor;
+            }
+            if ver.patch != patch {
+                return ver.patch > patch;
+            }
+        } else if minor > 0 {
+            if ver.minor != minor {
+                return false;
+            }
+            if ver.patch != patch {
+                return ver.patch > patch;
+            }
+        } else if ver.minor != minor || ver.patch != patch {
+            return false;
+        }
+        ver.pre >= self.pre
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for VersionReq<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return write!(f, "*");
+        }
+        for (i, cmp) in self.comparators.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", cmp)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Comparator<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self.op {
+            Op::Exact => "=",
+            Op::Greater => ">",
+            Op::GreaterEq => ">=",
+            Op::Less => "<",
+            Op::LessEq => "<=",
+            Op::Tilde => "~",
+            Op::Caret => "^",
+            Op::Wildcard => "",
+        };
+        write!(f, "{}{}", op, self.major)?;
+        match (self.minor, self.patch) {
+            (Some(minor), Some(patch)) => {
+                write!(f, ".{}.{}", minor, patch)?;
+                if !self.pre.is_empty() {
+                    write!(f, "-{}", self.pre)?;
+                }
+            }
+            (Some(minor), None) => {
+                write!(f, ".{}", minor)?;
+                if self.op == Op::Wildcard {
+                    write!(f, ".*")?;
+                }
+            }
+            (None, _) if self.op == Op::Wildcard => write!(f, ".*")?,
+            (None, _) => (),
+        }
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Parses a version such as `1.0.0-alpha.1+001`.
+pub const fn semver() -> Semver {
+    Semver
+}
+
+/// Parses a requirement such as `>=1.2, <1.5` or `~0.3.1`.
+///
+/// ```
+/// use kaparser::{error::EmptyErr, grammars::semver::*, parser::*};
+///
+/// let req = Parser::<&str, VersionReq, EmptyErr>::fullmatch(&semver_req(), ">=1.2.3, <2")
+///     .into_output()
+///     .unwrap();
+/// assert!(req.matches(&Version::new(1, 9, 0)));
+/// // Pre-releases only match requirements that mention them.
+/// let version = Parser::<&str, Version, EmptyErr>::fullmatch(&semver(), "1.4.0-rc.1")
+///     .into_output()
+///     .unwrap();
+/// assert!(!req.matches(&version));
+/// ```
+#[cfg(feature = "alloc")]
+pub const fn semver_req() -> SemverReq {
+    SemverReq
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Semver;
+
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy)]
+pub struct SemverReq;
+
+impl<'src, I, Ext> Parser<'src, I, Version<'src>, Ext> for Semver
+where
+    I: InputByteSlice<'src> + StaticInput,
+    I::Slice: AsRef<[u8]>,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Version<'src>, I::Cursor), Ext::Error> {
+        let mut descent = Descent {
+            driver: Driver::<I, Ext> {
+                input,
+                state: &mut state,
+                ctx: &ctx,
+            },
+        };
+        descent.version(start).into()
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        Parser::<I, Version, Ext>::__parse(self, input, start, state, ctx, private::Token).raise_or_map(|(_, cur)| cur)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'src, I, Ext> Parser<'src, I, VersionReq<'src>, Ext> for SemverReq
+where
+    I: InputByteSlice<'src> + StaticInput,
+    I::Slice: AsRef<[u8]>,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(VersionReq<'src>, I::Cursor), Ext::Error> {
+        let mut descent = Descent {
+            driver: Driver::<I, Ext> {
+                input,
+                state: &mut state,
+                ctx: &ctx,
+            },
+        };
+        descent.req(start).into()
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        Parser::<I, VersionReq, Ext>::__parse(self, input, start, state, ctx, private::Token)
+            .raise_or_map(|(_, cur)| cur)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+const NUMBER: Int<u64> = int(10).unsigned();
+const DIGIT: ByteSet = ByteSet::range(b'0', b'9');
+const IDENTIFIER: ByteSet = DIGIT
+    .union(ByteSet::range(b'A', b'Z'))
+    .union(ByteSet::range(b'a', b'z'))
+    .with(b'-');
+
+const DOT: OneOfLiterals<'static, (), 1> = one_of_literals([(".", ())]);
+const PRERELEASE: OneOfLiterals<'static, (), 1> = one_of_literals([("-", ())]);
+const BUILD: OneOfLiterals<'static, (), 1> = one_of_literals([("+", ())]);
+
+#[cfg(feature = "alloc")]
+const WS: ByteSet = ByteSet::new(b" \t");
+#[cfg(feature = "alloc")]
+const COMMA: OneOfLiterals<'static, (), 1> = one_of_literals([(",", ())]);
+#[cfg(feature = "alloc")]
+const WILDCARD: OneOfLiterals<'static, (), 3> = one_of_literals([("*", ()), ("x", ()), ("X", ())]);
+#[cfg(feature = "alloc")]
+const OPS: OneOfLiterals<'static, Op, 7> = one_of_literals([
+    ("=", Op::Exact),
+    (">", Op::Greater),
+    (">=", Op::GreaterEq),
+    ("<", Op::Less),
+    ("<=", Op::LessEq),
+    ("~", Op::Tilde),
+    ("^", Op::Caret),
+]);
+
+/// Recursive descent over the patterns above.
+struct Descent<'a, 'src, I, Ext>
+where
+    I: Input<'src>,
+    Ext: Extra<'src, I>,
+{
+    driver: Driver<'a, 'src, I, Ext>,
+}
+
+impl<'src, I, Ext> Descent<'_, 'src, I, Ext>
+where
+    I: InputByteSlice<'src> + StaticInput,
+    I::Slice: AsRef<[u8]>,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    fn expect(
+        &mut self,
+        table: &OneOfLiterals<'static, (), 1>,
+        cur: I::Cursor,
+        what: &'static str,
+    ) -> Result<I::Cursor, Ext::Error> {
+        match self.driver.dispatch(table, cur.clone()) {
+            Ok(((), next)) => Ok(next),
+            Err(_) => Err(expected::<I, _>(cur, what)),
+        }
+    }
+
+    /// Decimal without leading zeros.
+    fn number(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, u64> {
+        let (digits, end) = self
+            .driver
+            .capture(take(DIGIT, 1..), cur.clone())
+            .map_err(|_| expected::<I, _>(cur.clone(), "a version number"))?;
+        if digits.as_ref().len() > 1 && digits.as_ref()[0] == b'0' {
+            return Err(invalid::<I, _>(cur..end));
+        }
+        self.driver.capture(NUMBER, cur)
+    }
+
+    /// Dot-separated, non-empty identifiers of ASCII alphanumerics and hyphens.
+    fn identifiers(&mut self, start: I::Cursor, pre: bool) -> Res<'src, I, Ext, &'src str> {
+        let mut cur = start.clone();
+        loop {
+            let (id, end) = self
+                .driver
+                .capture(take(IDENTIFIER, 1..), cur.clone())
+                .map_err(|_| expected::<I, _>(cur.clone(), "an identifier"))?;
+            let id = id.as_ref();
+            // Numeric identifiers of pre-releases are compared by value.
+            if pre && id.len() > 1 && id[0] == b'0' && id.iter().all(u8::is_ascii_digit) {
+                return Err(invalid::<I, _>(cur..end));
+            }
+            match self.driver.dispatch(&DOT, end.clone()) {
+                Ok(((), next)) => cur = next,
+                Err(_) => {
+                    let (text, _) = self.driver.capture(SliceTo(end.clone()), start.clone())?;
+                    return Ok((utf8::<I, _>(text.as_ref(), start)?, end));
+                }
+            }
+        }
+    }
+
+    fn tail(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, (Prerelease<'src>, BuildMetadata<'src>)> {
+        let (pre, cur) = match self.driver.dispatch(&PRERELEASE, cur.clone()) {
+            Ok(((), next)) => self.identifiers(next, true).map(|(ids, end)| (Prerelease(ids), end))?,
+            Err(_) => (Prerelease::EMPTY, cur),
+        };
+        let (build, cur) = match self.driver.dispatch(&BUILD, cur.clone()) {
+            Ok(((), next)) => self
+                .identifiers(next, false)
+                .map(|(ids, end)| (BuildMetadata(ids), end))?,
+            Err(_) => (BuildMetadata::EMPTY, cur),
+        };
+        Ok(((pre, build), cur))
+    }
+
+    fn version(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Version<'src>> {
+        let (major, cur) = self.number(cur)?;
+        let cur = self.expect(&DOT, cur, "`.`")?;
+        let (minor, cur) = self.number(cur)?;
+        let cur = self.expect(&DOT, cur, "`.`")?;
+        let (patch, cur) = self.number(cur)?;
+        let ((pre, build), cur) = self.tail(cur)?;
+        let version = Version {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        };
+        Ok((version, cur))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ws(&mut self, cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        self.driver.check(&take(WS, ..), cur)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn req(&mut self, mut cur: I::Cursor) -> Res<'src, I, Ext, VersionReq<'src>> {
+        let mut comparators = Vec::new();
+        loop {
+            cur = self.ws(cur)?;
+            let (cmp, end) = self.comparator(cur)?;
+            comparators.extend(cmp);
+            let next = self.ws(end.clone())?;
+            match self.driver.dispatch(&COMMA, next) {
+                Ok(((), next)) => cur = next,
+                Err(_) => return Ok((VersionReq { comparators }, end)),
+            }
+        }
+    }
+
+    /// Moves past the wildcard, if any.
+    #[cfg(feature = "alloc")]
+    fn wildcard(&mut self, cur: I::Cursor) -> Option<I::Cursor> {
+        self.driver.dispatch(&WILDCARD, cur).ok().map(|((), next)| next)
+    }
+
+    /// `None` for `*`.
+    #[cfg(feature = "alloc")]
+    fn comparator(&mut self, start: I::Cursor) -> Res<'src, I, Ext, Option<Comparator<'src>>> {
+        let (op, cur) = match self.driver.dispatch(&OPS, start.clone()) {
+            Ok((op, next)) => (Some(op), next),
+            Err(_) => (None, start.clone()),
+        };
+        let cur = self.ws(cur)?;
+        if op.is_none()
+            && let Some(end) = self.wildcard(cur.clone())
+        {
+            return Ok((None, end));
+        }
+
+        let (major, mut cur) = self.number(cur)?;
+        let mut wild = false;
+        let mut minor = None;
+        let mut patch = None;
+        if let Ok(((), next)) = self.driver.dispatch(&DOT, cur.clone()) {
+            match self.wildcard(next.clone()) {
+                Some(end) => (wild, cur) = (true, end),
+                None => (minor, cur) = self.number(next).map(|(minor, end)| (Some(minor), end))?,
+            }
+            if !wild && let Ok(((), next)) = self.driver.dispatch(&DOT, cur.clone()) {
+                match self.wildcard(next.clone()) {
+                    Some(end) => (wild, cur) = (true, end),
+                    None => (patch, cur) = self.number(next).map(|(patch, end)| (Some(patch), end))?,
+                }
+            }
+        }
+
+        let op = match (op, wild) {
+            (None, false) => Op::Caret,
+            (None | Some(Op::Exact), true) => Op::Wildcard,
+            (Some(op), false) => op,
+            (Some(_), true) => return Err(invalid::<I, _>(start..cur)),
+        };
+        let (pre, cur) = match patch.is_some() {
+            true => self.tail(cur).map(|((pre, _), end)| (pre, end))?,
+            false => (Prerelease::EMPTY, cur),
+        };
+        let cmp = Comparator {
+            op,
+            major,
+            minor,
+            patch,
+            pre,
+        };
+        Ok((Some(cmp), cur))
+    }
+}
@@ -0,0 +1,947 @@
+//! [TOML 1.0](https://toml.io/en/v1.0.0).
+//!
+//! ```
+//! use kaparser::{error::EmptyErr, grammars::toml::*, parser::*};
+//!
+//! let res = Parser::<&str, Table, EmptyErr>::fullmatch(&toml(), "[package]\nname = \"kaparser\"\n");
+//! let doc = res.into_output().unwrap();
+//! let name = doc.get("package").and_then(|item| item.get("name")).and_then(Item::as_str);
+//! assert_eq!(name, Some("kaparser"));
+//! ```
+//!
+//! Every key and value carries its span in the input. Tables keep their entries in source order,
+//! and remember how they were defined, so that the document can be reported on or rewritten faithfully.
+
+use crate::{common::*, error::*, extra::*, grammars::*, literal::*, number::*, parser::*, predicate::*, private};
+use alloc::{borrow::Cow, collections::BTreeMap, string::String, vec, vec::Vec};
+use core::{fmt, ops::Range};
+
+/// Nesting of arrays and inline tables deeper than this is rejected, unless configured by [`Toml::max_depth`].
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Key<'src> {
+    /// Borrowed unless quoted with escapes.
+    pub name: Cow<'src, str>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item<'src> {
+    pub value: Value<'src>,
+    /// Of the value, or of the header for tables defined by one,
+    /// or of the key for tables implicitly defined by headers or dotted keys.
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'src> {
+    /// Borrowed unless the string contains escapes or line ending backslashes.
+    String(Cow<'src, str>),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Datetime(Datetime<'src>),
+    Array(Array<'src>),
+    Table(Table<'src>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Array<'src> {
+    pub items: Vec<Item<'src>>,
+    /// Defined by `[[headers]]`, rather than as a value.
+    pub of_tables: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table<'src> {
+    /// In source order, with unique names.
+    entries: Vec<(Key<'src>, Item<'src>)>,
+    /// Positions in `entries` by name.
+    index: BTreeMap<Cow<'src, str>, usize>,
+    pub kind: TableKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
+    Root,
+    /// Created as a parent of a `[header]`, which may be defined by its own header later.
+    Implicit,
+    /// Defined by a `[header]` or `[[header]]`.
+    Header,
+    /// Created by dotted keys.
+    Dotted,
+    Inline,
+}
+
+/// Validated, but otherwise kept as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Datetime<'src> {
+    pub kind: DatetimeKind,
+    pub text: &'src str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatetimeKind {
+    OffsetDateTime,
+    LocalDateTime,
+    LocalDate,
+    LocalTime,
+}
+
+impl fmt::Display for Datetime<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl<'src> Table<'src> {
+    fn new(kind: TableKind) -> Self {
+        Self {
+            entries: Vec::new(),
+            index: BTreeMap::new(),
+            kind,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Item<'src>> {
+        self.position(name).map(|idx| &self.entries[idx].1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Item<'src>)> {
+        self.entries.iter().map(|(key, item)| (&*key.name, item))
+    }
+
+    /// In source order.
+    pub fn entries(&self) -> &[(Key<'src>, Item<'src>)] {
+        &self.entries
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    /// The table of the entry, or the last table of the array of tables.
+    fn child(&mut self, idx: usize) -> &mut Table<'src> {
+        match &mut self.entries[idx].1.value {
+            Value::Table(table) => table,
+            Value::Array(array) => match &mut array.items.last_mut().unwrap().value {
+                Value::Table(table) => table,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn insert_dotted(&mut self, keys: Vec<Key<'src>>, item: Item<'src>) -> Result<(), Conflict> {
+        let mut keys = keys.into_iter();
+        let last = keys.next_back().unwrap();
+        let mut table = self;
+        for key in keys {
+            let idx = match table.position(&key.name) {
+                None => {
+                    let span = key.span.clone();
+                    table.push(key, Value::Table(Table::new(TableKind::Dotted)), span)
+                }
+                Some(idx) => match &table.entries[idx].1.value {
+                    Value::Table(Table {
+                        kind: TableKind::Dotted,
+                        ..
+                    }) => idx,
+                    _ => return Err(Conflict(key.span, &NOT_EXTENSIBLE)),
+                },
+            };
+            table = table.child(idx);
+        }
+        if table.position(&last.name).is_some() {
+            return Err(Conflict(last.span, &DUPLICATE_KEY));
+        }
+        table.push(last, item.value, item.span);
+        Ok(())
+    }
+
+    fn push(&mut self, key: Key<'src>, value: Value<'src>, span: Range<usize>) -> usize {
+        let idx = self.entries.len();
+        self.index.insert(key.name.clone(), idx);
+        self.entries.push((key, Item { value, span }));
+        idx
+    }
+
+    fn freeze(&mut self) {
+        self.kind = TableKind::Inline;
+        for (_, item) in &mut self.entries {
+            if let Value::Table(table) = &mut item.value {
+                table.freeze();
+            }
+        }
+    }
+}
+
+impl<'src> Item<'src> {
+    /// Looks up the member of a table.
+    pub fn get(&self, name: &str) -> Option<&Item<'src>> {
+        self.as_table()?.get(name)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self.value {
+            Value::Integer(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self.value {
+            Value::Float(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.value {
+            Value::Boolean(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_datetime(&self) -> Option<Datetime<'src>> {
+        match self.value {
+            Value::Datetime(dt) => Some(dt),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Item<'src>]> {
+        match &self.value {
+            Value::Array(array) => Some(&array.items),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&Table<'src>> {
+        match &self.value {
+            Value::Table(table) => Some(table),
+            _ => None,
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Parses a TOML document into its root table.
+pub const fn toml() -> Toml {
+    Toml {
+        max_depth: DEFAULT_MAX_DEPTH,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Toml {
+    max_depth: usize,
+}
+
+impl Toml {
+    pub const fn max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+}
+
+#[derive(Debug)]
+struct Message(&'static str);
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl core::error::Error for Message {}
+
+static DUPLICATE_KEY: Message = Message("duplicate key");
+static DUPLICATE_TABLE: Message = Message("duplicate table");
+static NOT_EXTENSIBLE: Message = Message("cannot extend a value defined elsewhere");
+static DEPTH_EXCEEDED: Message = Message("nesting too deep");
+
+/// Key which cannot be defined, at its span.
+struct Conflict(Range<usize>, &'static Message);
+
+impl Conflict {
+    fn raise<E: Error>(self) -> E {
+        E::new(self.0, ErrorKind::Other(self.1))
+    }
+}
+
+impl<'src, I, Ext> Parser<'src, I, Table<'src>, Ext> for Toml
+where
+    I: InputByteSlice<'src> + StaticInput,
+    I::Slice: AsRef<[u8]>,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        mut state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Table<'src>, I::Cursor), Ext::Error> {
+        let mut descent = Descent {
+            driver: Driver::<I, Ext> {
+                input,
+                state: &mut state,
+                ctx: &ctx,
+            },
+            depth: self.max_depth,
+            root: Table::new(TableKind::Root),
+            path: Vec::new(),
+        };
+        let end = trip!(descent.document(start));
+        PResult::emit((descent.root, end))
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        Parser::<I, Table, Ext>::__parse(self, input, start, state, ctx, private::Token).raise_or_map(|(_, cur)| cur)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+const WS: ByteSet = ByteSet::new(b" \t");
+const DIGIT: ByteSet = ByteSet::range(b'0', b'9');
+const HEX: ByteSet = DIGIT
+    .union(ByteSet::range(b'a', b'f'))
+    .union(ByteSet::range(b'A', b'F'));
+const BARE: ByteSet = DIGIT
+    .union(ByteSet::range(b'a', b'z'))
+    .union(ByteSet::range(b'A', b'Z'))
+    .union(ByteSet::new(b"_-"));
+/// Control characters other than tab and line breaks, which are allowed nowhere.
+const FORBIDDEN: ByteSet = ByteSet::range(0x00, 0x08)
+    .union(ByteSet::range(0x0b, 0x0c))
+    .union(ByteSet::range(0x0e, 0x1f))
+    .with(0x7f);
+const CONTROL: ByteSet = FORBIDDEN.with(b'\n').with(b'\r');
+/// Ends a run of characters taken as they are in strings, and likewise below.
+const BASIC_SPECIAL: ByteSet = CONTROL.with(b'"').with(b'\\');
+const LITERAL_SPECIAL: ByteSet = CONTROL.with(b'\'');
+const ML_BASIC_SPECIAL: ByteSet = BASIC_SPECIAL.without(ByteSet::new(b"\n"));
+const ML_LITERAL_SPECIAL: ByteSet = LITERAL_SPECIAL.without(ByteSet::new(b"\n"));
+
+#[derive(Clone, Copy)]
+enum Lead {
+    BasicString,
+    LiteralString,
+    MultilineBasicString,
+    MultilineLiteralString,
+    Boolean(bool),
+    Array,
+    InlineTable,
+    /// A number, or a date-time if it starts with a digit.
+    Digit,
+    Sign,
+    Special(f64),
+}
+
+#[rustfmt::skip]
+const VALUE: OneOfLiterals<'static, Lead, 26> = one_of_literals([
+    ("\"", Lead::BasicString), ("'", Lead::LiteralString),
+    ("\"\"\"", Lead::MultilineBasicString), ("'''", Lead::MultilineLiteralString),
+    ("true", Lead::Boolean(true)), ("false", Lead::Boolean(false)), ("[", Lead::Array), ("{", Lead::InlineTable),
+    ("0", Lead::Digit), ("1", Lead::Digit), ("2", Lead::Digit), ("3", Lead::Digit), ("4", Lead::Digit),
+    ("5", Lead::Digit), ("6", Lead::Digit), ("7", Lead::Digit), ("8", Lead::Digit), ("9", Lead::Digit),
+    ("+", Lead::Sign), ("-", Lead::Sign),
+    ("inf", Lead::Special(f64::INFINITY)), ("+inf", Lead::Special(f64::INFINITY)),
+    ("-inf", Lead::Special(f64::NEG_INFINITY)), ("nan", Lead::Special(f64::NAN)),
+    ("+nan", Lead::Special(f64::NAN)), ("-nan", Lead::Special(-f64::NAN)),
+]);
+
+#[derive(Clone, Copy)]
+enum Escape {
+    Char(char),
+    /// `\u` or `\U` with the number of hexadecimal digits.
+    Unicode(usize),
+    /// A backslash not followed by a known escape.
+    Bad,
+}
+
+#[rustfmt::skip]
+const ESCAPE: OneOfLiterals<'static, Escape, 10> = one_of_literals([
+    ("\\b", Escape::Char('\x08')), ("\\t", Escape::Char('\t')), ("\\n", Escape::Char('\n')),
+    ("\\f", Escape::Char('\x0c')), ("\\r", Escape::Char('\r')), ("\\\"", Escape::Char('"')),
+    ("\\\\", Escape::Char('\\')), ("\\u", Escape::Unicode(4)), ("\\U", Escape::Unicode(8)), ("\\", Escape::Bad),
+]);
+
+/// Lone `\r` is not a line break.
+const NEWLINE: OneOfLiterals<'static, bool, 3> = one_of_literals([("\n", true), ("\r\n", true), ("\r", false)]);
+const HASH: OneOfLiterals<'static, (), 1> = one_of_literals([("#", ())]);
+/// Whether it is an array of tables.
+const HEADER: OneOfLiterals<'static, bool, 2> = one_of_literals([("[", false), ("[[", true)]);
+const HEADER_END: OneOfLiterals<'static, (), 1> = one_of_literals([("]", ())]);
+const ARRAY_HEADER_END: OneOfLiterals<'static, (), 1> = one_of_literals([("]]", ())]);
+const KEY_QUOTE: OneOfLiterals<'static, bool, 2> = one_of_literals([("\"", true), ("'", false)]);
+const DOT: OneOfLiterals<'static, (), 1> = one_of_literals([(".", ())]);
+const EQUALS: OneOfLiterals<'static, (), 1> = one_of_literals([("=", ())]);
+const ARRAY_END: OneOfLiterals<'static, (), 1> = one_of_literals([("]", ())]);
+const ARRAY_NEXT: OneOfLiterals<'static, bool, 2> = one_of_literals([(",", true), ("]", false)]);
+const INLINE_TABLE_END: OneOfLiterals<'static, (), 1> = one_of_literals([("}", ())]);
+const INLINE_TABLE_NEXT: OneOfLiterals<'static, bool, 2> = one_of_literals([(",", true), ("}", false)]);
+const QUOTE: OneOfLiterals<'static, (), 1> = one_of_literals([("\"", ())]);
+const APOSTROPHE: OneOfLiterals<'static, (), 1> = one_of_literals([("'", ())]);
+const BACKSLASH: OneOfLiterals<'static, (), 1> = one_of_literals([("\\", ())]);
+const RADIX: OneOfLiterals<'static, u32, 3> = one_of_literals([("0x", 16), ("0o", 8), ("0b", 2)]);
+const DASH: OneOfLiterals<'static, (), 1> = one_of_literals([("-", ())]);
+const COLON: OneOfLiterals<'static, (), 1> = one_of_literals([(":", ())]);
+/// Whether it is a space, which separates the time only if one follows.
+const TIME_SEPARATOR: OneOfLiterals<'static, bool, 3> = one_of_literals([("T", false), ("t", false), (" ", true)]);
+
+//------------------------------------------------------------------------------
+
+/// Recursive descent over the patterns above, line by line.
+struct Descent<'a, 'src, I, Ext>
+where
+    I: Input<'src>,
+    Ext: Extra<'src, I>,
+{
+    driver: Driver<'a, 'src, I, Ext>,
+    /// Remaining nesting allowed.
+    depth: usize,
+    root: Table<'src>,
+    /// Indices of entries from the root to the table of the last header.
+    path: Vec<usize>,
+}
+
+impl<'src, I, Ext> Descent<'_, 'src, I, Ext>
+where
+    I: InputByteSlice<'src> + StaticInput,
+    I::Slice: AsRef<[u8]>,
+    Ext: Extra<'src, I>,
+    ByteSet: Predicate<I::Token>,
+{
+    fn ws(&mut self, cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        self.driver.check(&take(WS, ..), cur)
+    }
+
+    fn at_end(&mut self, cur: I::Cursor) -> bool {
+        self.driver.input.shall_reached_end::<Ext::Error>(cur).is_none()
+    }
+
+    /// Moves past the line break, if any.
+    fn newline(&mut self, cur: I::Cursor) -> Result<Option<I::Cursor>, Ext::Error> {
+        match self.driver.dispatch(&NEWLINE, cur.clone()) {
+            Ok((true, next)) => Ok(Some(next)),
+            Ok((false, next)) => Err(invalid::<I, _>(cur..next)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Raises at a character which cannot appear here, or where `what` is expected instead.
+    fn stray(&mut self, cur: I::Cursor, what: &'static str) -> Ext::Error {
+        match self.driver.check(&take(FORBIDDEN, 1), cur.clone()) {
+            Ok(end) => invalid::<I, _>(cur..end),
+            Err(_) => expected::<I, _>(cur, what),
+        }
+    }
+
+    fn comment(&mut self, cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let Ok(((), next)) = self.driver.dispatch(&HASH, cur.clone()) else {
+            return Ok(cur);
+        };
+        let (text, end) = self.driver.capture(take(except(CONTROL), ..), next.clone())?;
+        utf8::<I, _>(text.as_ref(), next)?;
+        match self.driver.check(&take(FORBIDDEN, 1), end.clone()) {
+            Ok(after) => Err(invalid::<I, _>(end..after)),
+            Err(_) => Ok(end),
+        }
+    }
+
+    /// Only blanks or a comment shall follow, until the line break.
+    fn line_end(&mut self, cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let cur = self.ws(cur)?;
+        let cur = self.comment(cur)?;
+        match self.newline(cur.clone())? {
+            Some(next) => Ok(next),
+            None if self.at_end(cur.clone()) => Ok(cur),
+            None => Err(expected::<I, _>(cur, "a line break")),
+        }
+    }
+
+    /// Blanks, line breaks and comments within arrays.
+    fn ws_multiline(&mut self, mut cur: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        loop {
+            cur = self.ws(cur)?;
+            cur = self.comment(cur)?;
+            match self.newline(cur.clone())? {
+                Some(next) => cur = next,
+                None => return Ok(cur),
+            }
+        }
+    }
+
+    fn document(&mut self, start: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let mut cur = start;
+        loop {
+            cur = self.ws(cur)?;
+            if self.at_end(cur.clone()) {
+                return Ok(cur);
+            }
+            if let Ok((array, next)) = self.driver.dispatch(&HEADER, cur.clone()) {
+                cur = self.header(cur, next, array)?;
+            } else if self
+                .driver
+                .check(&take(ByteSet::new(b"#\n\r"), 1), cur.clone())
+                .is_err()
+            {
+                let ((keys, item), next) = self.keyval(cur)?;
+                let mut table = &mut self.root;
+                for &idx in &self.path {
+                    table = table.child(idx);
+                }
+                table.insert_dotted(keys, item).map_err(Conflict::raise)?;
+                cur = next;
+            }
+            cur = self.line_end(cur)?;
+        }
+    }
+
+    /// Parses the rest of a header after the opening bracket, and moves into its table.
+    fn header(&mut self, start: I::Cursor, cur: I::Cursor, array: bool) -> Result<I::Cursor, Ext::Error> {
+        let cur = self.ws(cur)?;
+        let (keys, cur) = self.keys(cur)?;
+        let cur = self.ws(cur)?;
+        let (close, what) = match array {
+            true => (&ARRAY_HEADER_END, "`]]`"),
+            false => (&HEADER_END, "`]`"),
+        };
+        let (_, end) = self
+            .driver
+            .dispatch(close, cur.clone())
+            .map_err(|_| expected::<I, _>(cur, what))?;
+        let span = I::span(start..end.clone());
+
+        self.path.clear();
+        let mut table = &mut self.root;
+        let count = keys.len();
+        for (i, key) in keys.into_iter().enumerate() {
+            let last = i + 1 == count;
+            let idx = match table.position(&key.name) {
+                None if !last => {
+                    let span = key.span.clone();
+                    table.push(key, Value::Table(Table::new(TableKind::Implicit)), span)
+                }
+                None if array => {
+                    let item = Item {
+                        value: Value::Table(Table::new(TableKind::Header)),
+                        span: span.clone(),
+                    };
+                    let value = Value::Array(Array {
+                        items: vec![item],
+                        of_tables: true,
+                    });
+                    table.push(key, value, span.clone())
+                }
+                None => table.push(key, Value::Table(Table::new(TableKind::Header)), span.clone()),
+                Some(idx) => {
+                    match (&mut table.entries[idx].1.value, last) {
+                        (Value::Table(child), false) if child.kind != TableKind::Inline => (),
+                        (Value::Table(child), true) if !array && child.kind == TableKind::Implicit => {
+                            child.kind = TableKind::Header
+                        }
+                        (Value::Array(Array { of_tables: true, .. }), false) => (),
+                        (Value::Array(Array { items, of_tables: true }), true) if array => items.push(Item {
+                            value: Value::Table(Table::new(TableKind::Header)),
+                            span: span.clone(),
+                        }),
+                        (Value::Table(_), true) if !array => {
+                            return Err(Conflict(key.span, &DUPLICATE_TABLE).raise());
+                        }
+                        _ => return Err(Conflict(key.span, &NOT_EXTENSIBLE).raise()),
+                    }
+                    idx
+                }
+            };
+            self.path.push(idx);
+            table = table.child(idx);
+        }
+        Ok(end)
+    }
+
+    fn keyval(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, (Vec<Key<'src>>, Item<'src>)> {
+        let (keys, next) = self.keys(cur)?;
+        let next = self.ws(next)?;
+        let (_, next) = self
+            .driver
+            .dispatch(&EQUALS, next.clone())
+            .map_err(|_| expected::<I, _>(next, "`=`"))?;
+        let next = self.ws(next)?;
+        let (item, end) = self.value(next)?;
+        Ok(((keys, item), end))
+    }
+
+    fn keys(&mut self, mut cur: I::Cursor) -> Res<'src, I, Ext, Vec<Key<'src>>> {
+        let mut keys = Vec::new();
+        loop {
+            let (key, next) = self.key(cur)?;
+            keys.push(key);
+            let next = self.ws(next)?;
+            match self.driver.dispatch(&DOT, next.clone()) {
+                Ok(((), after)) => cur = self.ws(after)?,
+                Err(_) => return Ok((keys, next)),
+            }
+        }
+    }
+
+    fn key(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Key<'src>> {
+        let (name, end) = match self.driver.dispatch(&KEY_QUOTE, cur.clone()) {
+            Ok((true, next)) => self.basic(next)?,
+            Ok((false, next)) => self.literal(next)?,
+            Err(_) => {
+                let (name, end) = self
+                    .driver
+                    .capture(take(BARE, 1..), cur.clone())
+                    .map_err(|_| expected::<I, _>(cur.clone(), "a key"))?;
+                (Cow::Borrowed(utf8::<I, _>(name.as_ref(), cur.clone())?), end)
+            }
+        };
+        let span = I::span(cur..end.clone());
+        Ok((Key { name, span }, end))
+    }
+
+    //------------------------------------------------------------------------------
+
+    fn value(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Item<'src>> {
+        let (lead, next) = self
+            .driver
+            .dispatch(&VALUE, cur.clone())
+            .map_err(|_| expected::<I, _>(cur.clone(), "a value"))?;
+        let (value, end) = match lead {
+            Lead::BasicString => self.basic(next).map(|(s, end)| (Value::String(s), end))?,
+            Lead::LiteralString => self.literal(next).map(|(s, end)| (Value::String(s), end))?,
+            Lead::MultilineBasicString => self.multiline_basic(next).map(|(s, end)| (Value::String(s), end))?,
+            Lead::MultilineLiteralString => self.multiline_literal(next).map(|(s, end)| (Value::String(s), end))?,
+            Lead::Boolean(b) => (Value::Boolean(b), next),
+            Lead::Array => self.nested(cur.clone(), next, Self::array)?,
+            Lead::InlineTable => self.nested(cur.clone(), next, Self::inline_table)?,
+            Lead::Digit if self.is_datetime(cur.clone()) => {
+                self.datetime(cur.clone()).map(|(dt, end)| (Value::Datetime(dt), end))?
+            }
+            Lead::Digit | Lead::Sign => self.number(cur.clone())?,
+            Lead::Special(f) => (Value::Float(f), next),
+        };
+        let span = I::span(cur..end.clone());
+        Ok((Item { value, span }, end))
+    }
+
+    fn nested(
+        &mut self,
+        cur: I::Cursor,
+        next: I::Cursor,
+        f: fn(&mut Self, I::Cursor) -> Res<'src, I, Ext, Value<'src>>,
+    ) -> Res<'src, I, Ext, Value<'src>> {
+        if self.depth == 0 {
+            return Err(Conflict(I::span(cur..next), &DEPTH_EXCEEDED).raise());
+        }
+        self.depth -= 1;
+        let res = f(self, next);
+        self.depth += 1;
+        res
+    }
+
+    fn array(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Value<'src>> {
+        let mut items = Vec::new();
+        let mut cur = self.ws_multiline(cur)?;
+        loop {
+            if let Ok(((), end)) = self.driver.dispatch(&ARRAY_END, cur.clone()) {
+                let array = Array {
+                    items,
+                    of_tables: false,
+                };
+                return Ok((Value::Array(array), end));
+            }
+            let (item, next) = self.value(cur)?;
+            items.push(item);
+            let next = self.ws_multiline(next)?;
+            match self.driver.dispatch(&ARRAY_NEXT, next.clone()) {
+                Ok((true, after)) => cur = self.ws_multiline(after)?,
+                Ok((false, _)) => cur = next,
+                Err(_) => return Err(expected::<I, _>(next, "`,` or `]`")),
+            }
+        }
+    }
+
+    fn inline_table(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Value<'src>> {
+        let mut table = Table::new(TableKind::Dotted);
+        let mut cur = self.ws(cur)?;
+        let end = match self.driver.dispatch(&INLINE_TABLE_END, cur.clone()) {
+            Ok(((), end)) => end,
+            Err(_) => loop {
+                let ((keys, item), next) = self.keyval(cur)?;
+                table.insert_dotted(keys, item).map_err(Conflict::raise)?;
+                let next = self.ws(next)?;
+                match self.driver.dispatch(&INLINE_TABLE_NEXT, next.clone()) {
+                    Ok((true, after)) => cur = self.ws(after)?,
+                    Ok((false, end)) => break end,
+                    Err(_) => return Err(expected::<I, _>(next, "`,` or `}`")),
+                }
+            },
+        };
+        table.freeze();
+        Ok((Value::Table(table), end))
+    }
+
+    //------------------------------------------------------------------------------
+
+    /// Integers of decimal, or of radix 16, 8 or 2 after the prefix, and floats except `inf` and `nan`.
+    fn number(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Value<'src>> {
+        if let Ok((radix, next)) = self.driver.dispatch(&RADIX, cur.clone()) {
+            let (i, end) = self
+                .driver
+                .capture(int::<i64>(radix).unsigned().separator(b'_'), next)?;
+            return Ok((Value::Integer(i), end));
+        }
+
+        let end = self.driver.check(&float::<f64>().separator(b'_'), cur.clone())?;
+        let (lexeme, _) = self.driver.capture(SliceTo(end), cur.clone())?;
+        let lexeme = lexeme.as_ref();
+
+        // Leading zeros are not allowed in the integral part.
+        let sign = matches!(lexeme[0], b'+' | b'-') as usize;
+        let int_len = lexeme[sign..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit() || **b == b'_')
+            .count();
+        if lexeme[sign] == b'0' && int_len > 1 {
+            let int_start = I::bump_cursor(cur.clone(), sign);
+            return Err(invalid::<I, _>(int_start.clone()..I::bump_cursor(int_start, int_len)));
+        }
+
+        match sign + int_len < lexeme.len() {
+            true => {
+                let (f, end) = self.driver.capture(float::<f64>().separator(b'_'), cur)?;
+                Ok((Value::Float(f), end))
+            }
+            false => {
+                let (i, end) = self.driver.capture(int::<i64>(10).separator(b'_'), cur)?;
+                Ok((Value::Integer(i), end))
+            }
+        }
+    }
+
+    /// Whether it starts with `dddd-` or `dd:`.
+    fn is_datetime(&mut self, cur: I::Cursor) -> bool {
+        let date = self.driver.check(&take(DIGIT, 4), cur.clone());
+        let date = date.and_then(|next| self.driver.check(&DASH, next));
+        date.is_ok() || self.at_time(cur)
+    }
+
+    fn at_time(&mut self, cur: I::Cursor) -> bool {
+        let time = self.driver.check(&take(DIGIT, 2), cur);
+        time.and_then(|next| self.driver.check(&COLON, next)).is_ok()
+    }
+
+    fn datetime(&mut self, start: I::Cursor) -> Res<'src, I, Ext, Datetime<'src>> {
+        let (kind, end) = match self.at_time(start.clone()) {
+            true => (DatetimeKind::LocalTime, self.local_time(start.clone())?),
+            false => {
+                let date_end = self.driver.check(&datetime::date(), start.clone())?;
+                let time = match self.driver.dispatch(&TIME_SEPARATOR, date_end.clone()) {
+                    Ok((space, next)) => !space || self.at_time(next),
+                    Err(_) => false,
+                };
+                match time {
+                    true => {
+                        let (dt, end) = self.driver.capture(datetime::rfc3339().local(), start.clone())?;
+                        match dt.offset {
+                            Some(_) => (DatetimeKind::OffsetDateTime, end),
+                            None => (DatetimeKind::LocalDateTime, end),
+                        }
+                    }
+                    false => (DatetimeKind::LocalDate, date_end),
+                }
+            }
+        };
+        let (text, _) = self.driver.capture(SliceTo(end.clone()), start.clone())?;
+        let text = utf8::<I, _>(text.as_ref(), start)?;
+        Ok((Datetime { kind, text }, end))
+    }
+
+    /// Seconds are required, and the fraction shall follow a point.
+    fn local_time(&mut self, start: I::Cursor) -> Result<I::Cursor, Ext::Error> {
+        let end = self.driver.check(&datetime::time(), start.clone())?;
+        let (text, _) = self.driver.capture(SliceTo(end.clone()), start.clone())?;
+        let text = text.as_ref();
+        match text.get(8) {
+            _ if text.len() < 8 => Err(expected::<I, _>(end, "`:`")),
+            Some(b',') => Err(invalid::<I, _>(I::bump_cursor(start, 8)..end)),
+            _ => Ok(end),
+        }
+    }
+
+    //------------------------------------------------------------------------------
+
+    /// Parses the escape at the backslash.
+    fn escape(&mut self, at: I::Cursor) -> Res<'src, I, Ext, char> {
+        let (escape, next) = self.driver.dispatch(&ESCAPE, at.clone())?;
+        match escape {
+            Escape::Char(ch) => Ok((ch, next)),
+            Escape::Unicode(len) => {
+                let (digits, end) = self.driver.capture(take(HEX, len), next)?;
+                let code = AsRef::<[u8]>::as_ref(digits)
+                    .iter()
+                    .fold(0, |code, b| code << 4 | (*b as char).to_digit(16).unwrap());
+                match char::from_u32(code) {
+                    Some(ch) => Ok((ch, end)),
+                    None => Err(invalid::<I, _>(at..end)),
+                }
+            }
+            Escape::Bad => Err(match self.driver.check(&take(ANY, 1), next.clone()) {
+                Ok(end) => invalid::<I, _>(at..end),
+                Err(_) => expected::<I, _>(next, "an escape sequence"),
+            }),
+        }
+    }
+
+    /// Appends the source between the cursors.
+    fn push_src(&mut self, owned: &mut Option<String>, range: Range<I::Cursor>) -> Result<(), Ext::Error> {
+        let (piece, _) = self.driver.capture(SliceTo(range.end), range.start.clone())?;
+        let piece = utf8::<I, _>(piece.as_ref(), range.start)?;
+        owned.get_or_insert_with(String::new).push_str(piece);
+        Ok(())
+    }
+
+    /// The source between the cursors, after what has been appended if any.
+    fn finish(&mut self, owned: Option<String>, range: Range<I::Cursor>) -> Result<Cow<'src, str>, Ext::Error> {
+        let (tail, _) = self.driver.capture(SliceTo(range.end), range.start.clone())?;
+        let tail = utf8::<I, _>(tail.as_ref(), range.start)?;
+        Ok(match owned {
+            None => Cow::Borrowed(tail),
+            Some(s) => Cow::Owned(s + tail),
+        })
+    }
+
+    /// Parses the rest of a basic string after the opening quote.
+    fn basic(&mut self, mut cur: I::Cursor) -> Res<'src, I, Ext, Cow<'src, str>> {
+        let mut owned = None::<String>;
+        loop {
+            let at = self.driver.check(&take(except(BASIC_SPECIAL), ..), cur.clone())?;
+            if let Ok(((), end)) = self.driver.dispatch(&QUOTE, at.clone()) {
+                return Ok((self.finish(owned, cur..at)?, end));
+            }
+            if self.driver.check(&BACKSLASH, at.clone()).is_err() {
+                return Err(self.stray(at, "`\"`"));
+            }
+            self.push_src(&mut owned, cur..at.clone())?;
+            let (ch, next) = self.escape(at)?;
+            owned.as_mut().unwrap().push(ch);
+            cur = next;
+        }
+    }
+
+    /// Parses the rest of a literal string after the opening apostrophe.
+    fn literal(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Cow<'src, str>> {
+        let at = self.driver.check(&take(except(LITERAL_SPECIAL), ..), cur.clone())?;
+        match self.driver.dispatch(&APOSTROPHE, at.clone()) {
+            Ok(((), end)) => Ok((self.finish(None, cur..at)?, end)),
+            Err(_) => Err(self.stray(at, "`'`")),
+        }
+    }
+
+    /// Handles the quotes at `cur` in multi-line strings, which close the string if there are three to five,
+    /// the first one or two of which are in the content.
+    ///
+    /// Returns where the string ends if closed, and where the content continues or ends.
+    fn closing(&mut self, cur: I::Cursor, quote: u8) -> Res<'src, I, Ext, Option<I::Cursor>> {
+        let (quotes, end) = self
+            .driver
+            .capture(take(ByteSet::EMPTY.with(quote), 1..), cur.clone())?;
+        match quotes.as_ref().len() {
+            0..3 => Ok((None, end)),
+            count @ 3..=5 => Ok((Some(end), I::bump_cursor(cur, count - 3))),
+            _ => Err(invalid::<I, _>(I::bump_cursor(cur, 5)..end)),
+        }
+    }
+
+    /// Parses the rest of a multi-line basic string after the opening delimiter.
+    fn multiline_basic(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Cow<'src, str>> {
+        // A line break right after the delimiter is trimmed.
+        let mut cur = self.newline(cur.clone())?.unwrap_or(cur);
+        let mut run = cur.clone();
+        let mut owned = None::<String>;
+        loop {
+            cur = self.driver.check(&take(except(ML_BASIC_SPECIAL), ..), cur)?;
+            if self.driver.check(&QUOTE, cur.clone()).is_ok() {
+                match self.closing(cur, b'"')? {
+                    (Some(end), content_end) => return Ok((self.finish(owned, run..content_end)?, end)),
+                    (None, next) => cur = next,
+                }
+            } else if let Ok(((), next)) = self.driver.dispatch(&BACKSLASH, cur.clone()) {
+                self.push_src(&mut owned, run..cur.clone())?;
+                let after = self.ws(next)?;
+                match self.newline(after)? {
+                    // Line ending backslash trims all the following whitespaces and line breaks.
+                    Some(mut next) => {
+                        next = self.ws(next)?;
+                        while let Some(after) = self.newline(next.clone())? {
+                            next = self.ws(after)?;
+                        }
+                        cur = next;
+                    }
+                    None => {
+                        let (ch, next) = self.escape(cur)?;
+                        owned.as_mut().unwrap().push(ch);
+                        cur = next;
+                    }
+                }
+                run = cur.clone();
+            } else {
+                cur = match self.newline(cur.clone())? {
+                    Some(next) => next,
+                    None => return Err(self.stray(cur, "`\"\"\"`")),
+                };
+            }
+        }
+    }
+
+    /// Parses the rest of a multi-line literal string after the opening delimiter.
+    fn multiline_literal(&mut self, cur: I::Cursor) -> Res<'src, I, Ext, Cow<'src, str>> {
+        // A line break right after the delimiter is trimmed.
+        let start = self.newline(cur.clone())?.unwrap_or(cur);
+        let mut cur = start.clone();
+        loop {
+            cur = self.driver.check(&take(except(ML_LITERAL_SPECIAL), ..), cur)?;
+            if self.driver.check(&APOSTROPHE, cur.clone()).is_ok() {
+                match self.closing(cur, b'\'')? {
+                    (Some(end), content_end) => return Ok((self.finish(None, start..content_end)?, end)),
+                    (None, next) => cur = next,
+                }
+            } else {
+                cur = match self.newline(cur.clone())? {
+                    Some(next) => next,
+                    None => return Err(self.stray(cur, "`'''`")),
+                };
+            }
+        }
+    }
+}
@@ -0,0 +1,541 @@
+//! [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986) URIs, split into components without copying.
+//!
+//! ```
+//! use kaparser::{error::EmptyErr, grammars::uri::*, parser::*};
+//!
+//! let text = "https://user@example.com:8042/over/there?name=ferret#nose";
+//! let uri = Parser::<&str, Uri, EmptyErr>::fullmatch(&uri(), text).into_output().unwrap();
+//! assert_eq!(uri.scheme.unwrap().as_str(), "https");
+//! assert_eq!(uri.authority.unwrap().port_number(), Some(8042));
+//! assert_eq!(uri.query.unwrap().decode().collect::<Vec<u8>>(), b"name=ferret");
+//! ```
+//!
+//! A URI ends before the first byte that is neither reserved nor unreserved nor `%`, such as a space,
+//! therefore it can be parsed out of running text. Within that extent, every byte shall be allowed
+//! in its component, otherwise an error is raised at the byte.
+//!
+//! Components are kept percent-encoded, see [`percent_decode`] to decode them.
+
+use crate::{common::*, extra::*, grammars::*, parser::*, predicate::ByteSet, private};
+use core::{
+    fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+    ops::Range,
+    str,
+};
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+/// `ALPHA / DIGIT / "-" / "." / "_" / "~"`
+pub const UNRESERVED: ByteSet = ByteSet::range(b'A', b'Z')
+    .union(ByteSet::range(b'a', b'z'))
+    .union(ByteSet::range(b'0', b'9'))
+    .union(ByteSet::new(b"-._~"));
+
+/// `"!" / "$" / "&" / "'" / "(" / ")" / "*" / "+" / "," / ";" / "="`
+pub const SUB_DELIMS: ByteSet = ByteSet::new(b"!$&'()*+,;=");
+
+/// `":" / "/" / "?" / "#" / "[" / "]" / "@"`
+pub const GEN_DELIMS: ByteSet = ByteSet::new(b":/?#[]@");
+
+/// Every byte that may appear in a URI, where `%` shall start a percent-encoded octet.
+pub const URI_BYTES: ByteSet = UNRESERVED.union(SUB_DELIMS).union(GEN_DELIMS).with(b'%');
+
+/// `unreserved / pct-encoded / sub-delims / ":" / "@"`, without `%` that is checked separately.
+pub const PCHAR: ByteSet = UNRESERVED.union(SUB_DELIMS).union(ByteSet::new(b":@"));
+
+/// `*( pchar / "/" / "?" )`
+pub const QUERY: ByteSet = PCHAR.union(ByteSet::new(b"/?"));
+
+/// Same as [`QUERY`].
+pub const FRAGMENT: ByteSet = QUERY;
+
+const SCHEME: ByteSet = ByteSet::range(b'A', b'Z')
+    .union(ByteSet::range(b'a', b'z'))
+    .union(ByteSet::range(b'0', b'9'))
+    .union(ByteSet::new(b"+-."));
+
+const USERINFO: ByteSet = UNRESERVED.union(SUB_DELIMS).with(b':');
+
+const REG_NAME: ByteSet = UNRESERVED.union(SUB_DELIMS);
+
+const PATH: ByteSet = PCHAR.with(b'/');
+
+//------------------------------------------------------------------------------
+
+/// Absolute URI, or relative reference without the scheme.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Uri<'src> {
+    pub scheme: Option<Component<'src>>,
+    /// After `//`, which is absent for such as `mailto:` URIs.
+    pub authority: Option<Authority<'src>>,
+    /// Possibly empty, but its span is still positioned.
+    pub path: Component<'src>,
+    /// After `?`.
+    pub query: Option<Component<'src>>,
+    /// After `#`.
+    pub fragment: Option<Component<'src>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Authority<'src> {
+    /// Before `@`, which should not carry passwords.
+    pub userinfo: Option<Component<'src>>,
+    pub host: Host<'src>,
+    pub host_span: Range<usize>,
+    /// After `:`, which may be empty.
+    pub port: Option<Component<'src>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Host<'src> {
+    /// Possibly empty, and still percent-encoded.
+    RegName(&'src str),
+    Ipv4(Ipv4Addr),
+    /// Written in brackets.
+    Ipv6(Ipv6Addr),
+    /// `[v1.anything]`, with the text in brackets.
+    IpFuture(&'src str),
+}
+
+/// Component borrowed from the input, still percent-encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Component<'src> {
+    text: &'src str,
+    span: (usize, usize),
+}
+
+impl<'src> Component<'src> {
+    pub fn as_str(&self) -> &'src str {
+        self.text
+    }
+
+    pub fn span(&self) -> Range<usize> {
+        self.span.0..self.span.1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn decode(&self) -> PercentDecode<'src> {
+        percent_decode(self.text)
+    }
+}
+
+impl fmt::Display for Component<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Uri<'_> {
+    /// Path segments split by `/`, where an absolute path starts with an empty segment.
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        let path = self.path.text;
+        path.strip_prefix('/').unwrap_or(path).split('/')
+    }
+
+    /// Relative references have no scheme.
+    pub fn is_relative(&self) -> bool {
+        self.scheme.is_none()
+    }
+}
+
+impl Authority<'_> {
+    /// `None` if the port is absent, empty, or too large.
+    pub fn port_number(&self) -> Option<u16> {
+        self.port.and_then(|port| port.text.parse().ok())
+    }
+}
+
+impl fmt::Display for Host<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Host::RegName(name) => write!(f, "{}", name),
+            Host::Ipv4(addr) => write!(f, "{}", addr),
+            Host::Ipv6(addr) => write!(f, "[{}]", addr),
+            Host::IpFuture(text) => write!(f, "[{}]", text),
+        }
+    }
+}
+
+impl fmt::Display for Uri<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(scheme) = &self.scheme {
+            write!(f, "{}:", scheme)?;
+        }
+        if let Some(authority) = &self.authority {
+            write!(f, "//")?;
+            if let Some(userinfo) = &authority.userinfo {
+                write!(f, "{}@", userinfo)?;
+            }
+            write!(f, "{}", authority.host)?;
+            if let Some(port) = &authority.port {
+                write!(f, ":{}", port)?;
+            }
+        }
+        write!(f, "{}", self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Decodes `%XX` octets, and passes malformed ones through as they are.
+pub fn percent_decode(text: &str) -> PercentDecode<'_> {
+    PercentDecode { bytes: text.as_bytes() }
+}
+
+/// See [`percent_decode`].
+#[derive(Debug, Clone)]
+pub struct PercentDecode<'a> {
+    bytes: &'a [u8],
+}
+
+fn hex(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
+
+impl Iterator for PercentDecode<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let (&b, rest) = self.bytes.split_first()?;
+        if b == b'%'
+            && let [hi, lo, rest @ ..] = rest
+            && let (Some(hi), Some(lo)) = (hex(*hi), hex(*lo))
+        {
+            self.bytes = rest;
+            return Some(hi << 4 | lo);
+        }
+        self.bytes = rest;
+        Some(b)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.bytes.len().div_ceil(3), Some(self.bytes.len()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> PercentDecode<'a> {
+    /// Borrowed if nothing is encoded.
+    pub fn decode_utf8(self) -> Result<Cow<'a, str>, str::Utf8Error> {
+        match self.bytes.contains(&b'%') {
+            false => str::from_utf8(self.bytes).map(Cow::Borrowed),
+            true => String::from_utf8(self.collect::<Vec<u8>>())
+                .map(Cow::Owned)
+                .map_err(|e| e.utf8_error()),
+        }
+    }
+
+    /// Borrowed if nothing is encoded, otherwise replaces invalid sequences with U+FFFD.
+    pub fn decode_utf8_lossy(self) -> Cow<'a, str> {
+        match self.bytes.contains(&b'%') {
+            false => String::from_utf8_lossy(self.bytes),
+            true => Cow::Owned(String::from_utf8_lossy(&self.collect::<Vec<u8>>()).into_owned()),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Parses an absolute URI, which starts with a scheme.
+pub const fn uri() -> UriParser {
+    UriParser { relative: false }
+}
+
+/// Parses either an absolute URI or a relative reference.
+pub const fn uri_reference() -> UriParser {
+    UriParser { relative: true }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct UriParser {
+    relative: bool,
+}
+
+impl<'src, I, Ext> Parser<'src, I, Uri<'src>, Ext> for UriParser
+where
+    I: InputByteSlice<'src> + StaticInput,
+    Ext: Extra<'src, I>,
+{
+    fn __parse(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        _: MaybeMut<Ext::State>,
+        _: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Uri<'src>, I::Cursor), Ext::Error> {
+        let bytes = trip!(rest_bytes(input, start.clone()));
+        let len = bytes
+            .iter()
+            .position(|&b| !URI_BYTES.contains(b))
+            .unwrap_or(bytes.len());
+        let mut scanner = Scanner {
+            // SAFETY: Only ASCII bytes are in the set.
+            text: unsafe { str::from_utf8_unchecked(&bytes[..len]) },
+            pos: 0,
+        };
+
+        let mut uri = match scanner.uri(self.relative) {
+            Ok(uri) => uri,
+            Err(failure) => return PResult::raise(failure.raise::<I, _>(start)),
+        };
+
+        let global = |span: (usize, usize)| {
+            let range = I::span(I::bump_cursor(start.clone(), span.0)..I::bump_cursor(start.clone(), span.1));
+            (range.start, range.end)
+        };
+        let rebase = |c: &mut Option<Component>| {
+            if let Some(c) = c {
+                c.span = global(c.span);
+            }
+        };
+        rebase(&mut uri.scheme);
+        if let Some(authority) = &mut uri.authority {
+            rebase(&mut authority.userinfo);
+            rebase(&mut authority.port);
+            let (lo, hi) = global((authority.host_span.start, authority.host_span.end));
+            authority.host_span = lo..hi;
+        }
+        uri.path.span = global(uri.path.span);
+        rebase(&mut uri.query);
+        rebase(&mut uri.fragment);
+
+        PResult::emit((uri, I::bump_cursor(start, scanner.pos)))
+    }
+
+    fn __check(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error> {
+        Parser::<I, Uri, Ext>::__parse(self, input, start, state, ctx, private::Token).raise_or_map(|(_, cur)| cur)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+type Res<T> = Result<T, Failure>;
+
+/// Scans the extent of the URI only.
+struct Scanner<'src> {
+    text: &'src str,
+    pos: usize,
+}
+
+impl<'src> Scanner<'src> {
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    #[inline]
+    fn eat(&mut self, byte: u8) -> bool {
+        let hit = self.peek() == Some(byte);
+        self.pos += hit as usize;
+        hit
+    }
+
+    fn component(&self, start: usize) -> Component<'src> {
+        Component {
+            text: &self.text[start..self.pos],
+            span: (start, self.pos),
+        }
+    }
+
+    /// Consumes bytes in the set or percent-encoded, until one of `stops` or the end.
+    fn run(&mut self, set: ByteSet, stops: &[u8], what: &'static str) -> Res<Component<'src>> {
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if set.contains(b) {
+                self.pos += 1;
+            } else if b == b'%' {
+                let bytes = self.text.as_bytes();
+                let valid = bytes.get(self.pos + 1).is_some_and(u8::is_ascii_hexdigit)
+                    && bytes.get(self.pos + 2).is_some_and(u8::is_ascii_hexdigit);
+                if !valid {
+                    return Err(Failure::expected(self.pos + 1, "two hexadecimal digits after `%`"));
+                }
+                self.pos += 3;
+            } else if stops.contains(&b) {
+                break;
+            } else {
+                return Err(Failure {
+                    range: self.pos..self.pos + 1,
+                    kind: FailureKind::Expected(Expect(what)),
+                });
+            }
+        }
+        Ok(self.component(start))
+    }
+
+    fn uri(&mut self, relative: bool) -> Res<Uri<'src>> {
+        let scheme = self.scheme();
+        if scheme.is_none() && !relative {
+            return Err(Failure::expected(0, "a scheme followed by `:`"));
+        }
+
+        let authority = match self.text[self.pos..].starts_with("//") {
+            true => {
+                self.pos += 2;
+                Some(self.authority()?)
+            }
+            false => None,
+        };
+
+        let path_start = self.pos;
+        let path = self.run(PATH, b"?#", "a path character")?;
+        // A colon in the first segment would be mistaken for a scheme.
+        if scheme.is_none() && authority.is_none() {
+            let first = path.text.split('/').next().unwrap_or_default();
+            if let Some(off) = first.find(':') {
+                return Err(Failure {
+                    range: path_start + off..path_start + off + 1,
+                    kind: FailureKind::Expected(Expect("`./` before a first path segment containing `:`")),
+                });
+            }
+        }
+        // Without an authority, a path shall not start with `//`.
+        if authority.is_none() && path.text.starts_with("//") {
+            return Err(Failure::expected(path_start + 1, "a path segment"));
+        }
+
+        let query = match self.eat(b'?') {
+            true => Some(self.run(QUERY, b"#", "a query character")?),
+            false => None,
+        };
+        let fragment = match self.eat(b'#') {
+            true => Some(self.run(FRAGMENT, b"", "a fragment character")?),
+            false => None,
+        };
+
+        Ok(Uri {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        })
+    }
+
+    fn scheme(&mut self) -> Option<Component<'src>> {
+        let bytes = self.text.as_bytes();
+        if !bytes.first()?.is_ascii_alphabetic() {
+            return None;
+        }
+        let len = bytes.iter().position(|&b| !SCHEME.contains(b)).unwrap_or(bytes.len());
+        (bytes.get(len) == Some(&b':')).then(|| {
+            self.pos = len + 1;
+            Component {
+                text: &self.text[..len],
+                span: (0, len),
+            }
+        })
+    }
+
+    fn authority(&mut self) -> Res<Authority<'src>> {
+        let start = self.pos;
+        let end = self.text[start..]
+            .find(['/', '?', '#'])
+            .map_or(self.text.len(), |off| start + off);
+
+        let userinfo = match self.text[start..end].find('@') {
+            Some(_) => {
+                let userinfo = self.run(USERINFO, b"@", "a userinfo character")?;
+                self.pos += 1;
+                Some(userinfo)
+            }
+            None => None,
+        };
+
+        let host_start = self.pos;
+        let host = match self.peek() {
+            Some(b'[') => self.ip_literal()?,
+            _ => {
+                let name = self.run(REG_NAME, b":/?#", "a host character")?.text;
+                // Otherwise such as `01.2.3.4` is a registered name.
+                let mut scanner = net::Scanner::new(name.as_bytes());
+                match scanner.ipv4() {
+                    Ok(addr) if scanner.pos == name.len() => Host::Ipv4(addr),
+                    _ => Host::RegName(name),
+                }
+            }
+        };
+        let host_span = host_start..self.pos;
+
+        let port = match self.eat(b':') {
+            true => {
+                let port_start = self.pos;
+                while let Some(b'0'..=b'9') = self.peek() {
+                    self.pos += 1;
+                }
+                if self.pos < end {
+                    return Err(Failure {
+                        range: self.pos..self.pos + 1,
+                        kind: FailureKind::Expected(Expect("a port digit")),
+                    });
+                }
+                Some(self.component(port_start))
+            }
+            false if self.pos < end => {
+                return Err(Failure {
+                    range: self.pos..self.pos + 1,
+                    kind: FailureKind::Expected(Expect("`:` or the end of the authority")),
+                });
+            }
+            false => None,
+        };
+
+        Ok(Authority {
+            userinfo,
+            host,
+            host_span,
+            port,
+        })
+    }
+
+    fn ip_literal(&mut self) -> Res<Host<'src>> {
+        let open = self.pos;
+        let Some(close) = self.text[open..].find(']') else {
+            return Err(Failure::expected(self.text.len(), "`]`"));
+        };
+        let inner = &self.text[open + 1..open + close];
+        self.pos = open + close + 1;
+
+        if let Some(future) = inner.strip_prefix(['v', 'V']) {
+            let valid = future.split_once('.').is_some_and(|(version, rest)| {
+                !version.is_empty()
+                    && version.bytes().all(|b| b.is_ascii_hexdigit())
+                    && !rest.is_empty()
+                    && rest.bytes().all(|b| USERINFO.contains(b))
+            });
+            return match valid {
+                true => Ok(Host::IpFuture(inner)),
+                false => Err(Failure::invalid(open..self.pos)),
+            };
+        }
+        let mut scanner = net::Scanner::new(inner.as_bytes());
+        match scanner.ipv6() {
+            Ok(addr) if scanner.pos == inner.len() => Ok(Host::Ipv6(addr)),
+            Ok(_) => Err(Failure::expected(open + 1 + scanner.pos, "`]`")),
+            Err(mut failure) => {
+                failure.range = open + 1 + failure.range.start..open + 1 + failure.range.end;
+                Err(failure)
+            }
+        }
+    }
+}
@@ -1,7 +1,20 @@
 use crate::{common::*, error::*, slice::*};
 use core::ops::Range;
 
+pub mod bits;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod partial;
+#[cfg(feature = "alloc")]
+pub mod rope;
+pub mod source;
+pub mod tokens;
+pub mod utf8;
+
 // TODO: In other words, they are inputs that do not need to be mutated when getting a slice or item.
+/// # Safety
+///
+/// Slices and tokens shall be borrowed from `'src`.
 pub unsafe trait StaticInput {}
 
 pub trait Input<'src>: 'src {
@@ -61,6 +74,17 @@ pub trait InputSlice<'src>: Input<'src> {
     where
         'src: 'tmp;
 
+    /// Fetches at `start` again, after the slice fetched there ended without `eof` before a match was decided.
+    ///
+    /// Inputs that grow the slice on a repeated fetch need not override this, unlike [`PartialInput`](partial::PartialInput).
+    #[inline]
+    fn fetch_more_slice<'tmp, E: Error>(&'tmp mut self, start: Self::Cursor) -> Result<(&'tmp Self::Slice, bool), E>
+    where
+        'src: 'tmp,
+    {
+        self.fetch_slice(start)
+    }
+
     fn release_slice<'tmp>(&'tmp mut self, range: Range<Self::Cursor>) -> &'tmp Self::Slice
     where
         'src: 'tmp;
@@ -72,6 +96,17 @@ pub trait InputByteSlice<'src>: InputSlice<'src> {
     fn fetch_byte_slice<'tmp, E: Error>(&'tmp mut self, start: Self::Cursor) -> Result<(&'tmp [u8], bool), E>
     where
         'src: 'tmp;
+
+    fn fetch_more_byte_slice<'tmp, E: Error>(&'tmp mut self, start: Self::Cursor) -> Result<(&'tmp [u8], bool), E>
+    where
+        'src: 'tmp;
+}
+
+/// Inputs that can be cut off at a cursor, so that the end of input is there.
+///
+/// Cursors of the truncated input are interchangeable with the original one.
+pub trait InputTruncatable<'src>: InputSlice<'src> + Sized {
+    fn truncate(&self, end: Self::Cursor) -> Option<Self>;
 }
 
 #[cfg(feature = "alloc")]
@@ -94,6 +129,13 @@ where
     {
         self.fetch_slice(start).map(|(slice, eof)| (slice.as_ref(), eof))
     }
+
+    fn fetch_more_byte_slice<'tmp, E: Error>(&'tmp mut self, start: Self::Cursor) -> Result<(&'tmp [u8], bool), E>
+    where
+        'src: 'tmp,
+    {
+        self.fetch_more_slice(start).map(|(slice, eof)| (slice.as_ref(), eof))
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -196,6 +238,16 @@ where
     }
 }
 
+impl<'src, S> InputTruncatable<'src> for &'src S
+where
+    S: ?Sized + Slice<'src>,
+{
+    #[inline]
+    fn truncate(&self, end: Self::Cursor) -> Option<Self> {
+        (end <= self.len() && self.is_item_boundary(end)).then(|| self.before(end))
+    }
+}
+
 impl<'src, S> InputOwnableToken<'src> for &'src S
 where
     S: ?Sized + Slice<'src>,
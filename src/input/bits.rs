@@ -0,0 +1,300 @@
+use crate::{common::*, error::*, extra::*, input::*, number::PrimInt, pattern::*, private};
+use core::{fmt, marker::PhantomData, ops::Range};
+
+/// Bit order within each byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The most significant bit comes first, and multi-bit fields are big-endian, as in H.264 and CAN.
+    MsbFirst,
+    /// The least significant bit comes first, and multi-bit fields are little-endian, as in DEFLATE.
+    LsbFirst,
+}
+
+/// Bit-level input over bytes, where tokens are `bool`s.
+///
+/// The cursor is `(byte, bit)`, and offsets and spans are measured in bits.
+///
+/// Runs of bits do not start at byte boundaries, so they cannot be borrowed as slices and [`InputSlice`] is
+/// not implemented. Single bits matched by predicates such as `[Just(true)]`, as well as [`bits()`],
+/// [`bool_bit`] and [`align_to_byte`] are supported, while patterns taking runs of tokens are not.
+#[derive(Debug, Clone, Copy)]
+pub struct BitInput<'src> {
+    bytes: &'src [u8],
+    order: BitOrder,
+}
+
+impl<'src> BitInput<'src> {
+    pub const fn new(bytes: &'src [u8], order: BitOrder) -> Self {
+        Self { bytes, order }
+    }
+
+    pub const fn msb_first(bytes: &'src [u8]) -> Self {
+        Self::new(bytes, BitOrder::MsbFirst)
+    }
+
+    pub const fn lsb_first(bytes: &'src [u8]) -> Self {
+        Self::new(bytes, BitOrder::LsbFirst)
+    }
+
+    pub const fn bytes(&self) -> &'src [u8] {
+        self.bytes
+    }
+
+    pub const fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    #[inline]
+    fn bit(&self, (byte, bit): (usize, u8)) -> Option<bool> {
+        let b = *self.bytes.get(byte)?;
+        Some(match self.order {
+            BitOrder::MsbFirst => b & (0x80 >> bit) != 0,
+            BitOrder::LsbFirst => b & (0x01 << bit) != 0,
+        })
+    }
+
+    /// Reads `n <= 128` bits as an unsigned integer.
+    #[inline]
+    fn read(&self, cursor: (usize, u8), n: u32) -> Option<(u128, (usize, u8))> {
+        let end = bump(cursor, n as usize);
+        if end.0 > self.bytes.len() || end.0 == self.bytes.len() && end.1 > 0 {
+            return None;
+        }
+
+        let mut acc = 0u128;
+        let mut cur = cursor;
+        for i in 0..n {
+            let bit = self.bit(cur)? as u128;
+            acc = match self.order {
+                BitOrder::MsbFirst => acc << 1 | bit,
+                BitOrder::LsbFirst => acc | bit << i,
+            };
+            cur = bump(cur, 1);
+        }
+
+        Some((acc, end))
+    }
+}
+
+#[inline]
+fn bump((byte, bit): (usize, u8), n: usize) -> (usize, u8) {
+    let bits = bit as usize + n;
+    (byte + bits / 8, (bits % 8) as u8)
+}
+
+unsafe impl StaticInput for BitInput<'_> {}
+
+impl<'src> Input<'src> for BitInput<'src> {
+    type Token = bool;
+
+    type TokenMaybe<'tmp>
+        = bool
+    where
+        'src: 'tmp;
+
+    type Cursor = (usize, u8);
+
+    #[inline]
+    fn begin(&self) -> Self::Cursor {
+        (0, 0)
+    }
+
+    #[inline]
+    fn next_maybe_ref<'tmp, E: Error>(
+        &'tmp mut self,
+        cursor: &mut Self::Cursor,
+    ) -> Result<Option<Self::TokenMaybe<'tmp>>, E>
+    where
+        'src: 'tmp,
+    {
+        Ok(self.bit(*cursor).inspect(|_| *cursor = bump(*cursor, 1)))
+    }
+
+    #[inline]
+    fn has_reached_end(&mut self, cursor: Self::Cursor) -> bool {
+        cursor.0 >= self.bytes.len()
+    }
+
+    #[inline]
+    fn span(range: Range<Self::Cursor>) -> Range<usize> {
+        Self::offset(range.start)..Self::offset(range.end)
+    }
+
+    #[inline]
+    fn offset((byte, bit): Self::Cursor) -> usize {
+        byte * 8 + bit as usize
+    }
+}
+
+impl<'src> InputOwnableToken<'src> for BitInput<'src> {
+    fn get_owned(&self, cursor: Self::Cursor) -> Option<Self::Token> {
+        self.bit(cursor)
+    }
+
+    fn iter_owned(&self, range: Range<Self::Cursor>) -> impl Iterator<Item = Self::Token> {
+        let len = Self::offset(range.end) - Self::offset(range.start);
+        (0..len).map_while(move |i| self.bit(bump(range.start, i)))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Reads `n` bits as an integer, signed types are sign-extended from `n` bits.
+///
+/// # Panics
+///
+/// Panics if `n` is zero or greater than `T::BITS`.
+pub const fn bits<T: PrimInt>(n: u32) -> Bits<T> {
+    assert!(0 < n && n <= T::BITS, "invalid number of bits");
+    Bits {
+        n,
+        phantom: PhantomData,
+    }
+}
+
+/// Reads a single bit as `bool`.
+pub const fn bool_bit() -> Bits<bool> {
+    Bits {
+        n: 1,
+        phantom: PhantomData,
+    }
+}
+
+/// Skips to the next byte boundary, does nothing if already aligned.
+pub const fn align_to_byte() -> AlignToByte {
+    AlignToByte
+}
+
+//------------------------------------------------------------------------------
+
+pub struct Bits<T> {
+    n: u32,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Describe for Bits<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.n {
+            1 => write!(f, "1 bit"),
+            n => write!(f, "{} bits", n),
+        }
+    }
+}
+
+/// Implemented for `bool` and primitive integers.
+pub trait FromBits: Sized {
+    #[doc(hidden)]
+    fn __from_bits(bits: u128, n: u32) -> Self;
+}
+
+impl FromBits for bool {
+    fn __from_bits(bits: u128, n: u32) -> Self {
+        #![allow(unused_variables)]
+        bits != 0
+    }
+}
+
+impl<T: PrimInt> FromBits for T {
+    fn __from_bits(bits: u128, n: u32) -> Self {
+        match T::SIGNED && n < 128 && bits >> (n - 1) & 1 == 1 {
+            true => T::from_bits(bits | !0 << n),
+            false => T::from_bits(bits),
+        }
+    }
+}
+
+impl<'src, Ext, T> Pattern<'src, BitInput<'src>, Ext> for Bits<T>
+where
+    Ext: Extra<'src, BitInput<'src>>,
+    T: FromBits,
+{
+    type View<'tmp>
+        = T
+    where
+        'src: 'tmp;
+
+    #[inline]
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut BitInput<'src>,
+        start: (usize, u8),
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, (usize, u8)), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        match input.read(start, self.n) {
+            Some((bits, end)) => PResult::emit((T::__from_bits(bits, self.n), end)),
+            None => PResult::raise(Ext::Error::new(
+                BitInput::span(start..(input.bytes.len(), 0)),
+                ErrorKind::Expected(self),
+            )),
+        }
+    }
+
+    #[inline]
+    fn __check<'tmp>(
+        &self,
+        input: &'tmp mut BitInput<'src>,
+        start: (usize, u8),
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(usize, u8), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        Pattern::<BitInput<'src>, Ext>::__parse(self, input, start, state, ctx, private::Token)
+            .raise_or_map(|(_, end)| end)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub struct AlignToByte;
+
+impl<'src, Ext> Pattern<'src, BitInput<'src>, Ext> for AlignToByte
+where
+    Ext: Extra<'src, BitInput<'src>>,
+{
+    type View<'tmp>
+        = ()
+    where
+        'src: 'tmp;
+
+    #[inline]
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut BitInput<'src>,
+        start: (usize, u8),
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, (usize, u8)), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        Pattern::<BitInput<'src>, Ext>::__check(self, input, start, state, ctx, private::Token)
+            .raise_or_map(|end| ((), end))
+    }
+
+    #[inline]
+    fn __check<'tmp>(
+        &self,
+        _input: &'tmp mut BitInput<'src>,
+        start: (usize, u8),
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(usize, u8), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        PResult::emit(match start {
+            (byte, 0) => (byte, 0),
+            (byte, _) => (byte + 1, 0),
+        })
+    }
+}
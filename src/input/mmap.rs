@@ -0,0 +1,88 @@
+use crate::input::utf8::Utf8Input;
+use core::{ops::Deref, str};
+use memmap2::Mmap;
+use std::{fs::File, io, path::Path};
+
+/// Read-only memory-mapped file, whose contents are paged in by the OS on demand.
+///
+/// This is not an input by itself, but owns the mapping that inputs borrow from.
+/// [`as_bytes`](Self::as_bytes) and [`as_str`](Self::as_str) hand out plain `&[u8]` and `&str`,
+/// which are [`StaticInput`](crate::input::StaticInput)s, so views captured by patterns
+/// are borrowed from the mapping and live as long as it.
+///
+/// # Truncation
+///
+/// The mapping assumes that the file is neither modified nor truncated while mapped:
+/// modifications break the immutability of borrowed slices, and accessing pages beyond
+/// a truncated end raises `SIGBUS` on most platforms. This cannot be guaranteed within the process,
+/// therefore the constructors are `unsafe`. Common ways to uphold it are mapping files that
+/// the process owns exclusively, or holding a lock that all writers respect.
+#[derive(Debug)]
+pub struct MmapInput {
+    map: Option<Mmap>,
+}
+
+impl MmapInput {
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated until the returned value is dropped, see above.
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        unsafe { Self::map(&File::open(path)?) }
+    }
+
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated until the returned value is dropped, see above.
+    pub unsafe fn map(file: &File) -> io::Result<Self> {
+        // Mapping an empty file fails on some platforms.
+        if file.metadata()?.len() == 0 {
+            return Ok(Self { map: None });
+        }
+
+        let map = unsafe { Mmap::map(file)? };
+        #[cfg(unix)]
+        let _ = map.advise(memmap2::Advice::Sequential);
+        Ok(Self { map: Some(map) })
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or_default()
+    }
+
+    /// Validates the whole file at once, which touches every page.
+    pub fn as_str(&self) -> Result<&str, str::Utf8Error> {
+        str::from_utf8(self.as_bytes())
+    }
+
+    /// Text input validated lazily as it is parsed, see [`Utf8Input::strict`].
+    pub fn utf8(&self) -> Utf8Input<'_> {
+        Utf8Input::strict(self.as_bytes())
+    }
+
+    /// Text input substituting invalid sequences lazily, see [`Utf8Input::lossy`].
+    pub fn utf8_lossy(&self) -> Utf8Input<'_> {
+        Utf8Input::lossy(self.as_bytes())
+    }
+}
+
+impl Deref for MmapInput {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[u8]> for MmapInput {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{error::*, input::*};
+use core::{fmt, ops::Range};
+
+/// The items received so far from a stream that has not ended, such as the buffer of a socket read loop.
+///
+/// Slices are fetched with `eof == false`, so that parsers ask for more when they reach the end of
+/// what has been received, see [`InputSlice::fetch_more_slice`]. Such requests cannot be served and raise
+/// [`Incomplete`], after which [`PartialInput::is_incomplete`] tells the caller to read more and parse again.
+#[derive(Debug)]
+pub struct PartialInput<'src, S: ?Sized = [u8]> {
+    slice: &'src S,
+    incomplete: bool,
+}
+
+impl<'src, S: ?Sized + Slice<'src>> PartialInput<'src, S> {
+    pub fn new(slice: &'src S) -> Self {
+        Self {
+            slice,
+            incomplete: false,
+        }
+    }
+
+    pub fn slice(&self) -> &'src S {
+        self.slice
+    }
+
+    /// Whether a parser has asked for more than received.
+    pub fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+
+    fn raise<E: Error>(&mut self) -> E {
+        self.incomplete = true;
+        let len = self.slice.len();
+        E::new(len..len, ErrorKind::Other(&INCOMPLETE))
+    }
+}
+
+/// Raised by [`PartialInput`] when more items are needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Incomplete;
+
+static INCOMPLETE: Incomplete = Incomplete;
+
+impl fmt::Display for Incomplete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "incomplete input")
+    }
+}
+
+impl core::error::Error for Incomplete {}
+
+unsafe impl<'src, S: ?Sized + Slice<'src>> StaticInput for PartialInput<'src, S> {}
+
+impl<'src, S: ?Sized + Slice<'src>> Input<'src> for PartialInput<'src, S> {
+    type Token = S::Item;
+
+    type TokenMaybe<'tmp>
+        = S::ItemMaybe<'tmp>
+    where
+        'src: 'tmp;
+
+    type Cursor = usize;
+
+    #[inline]
+    fn begin(&self) -> Self::Cursor {
+        0
+    }
+
+    #[inline]
+    fn next_maybe_ref<'tmp, E: Error>(
+        &'tmp mut self,
+        cursor: &mut Self::Cursor,
+    ) -> Result<Option<Self::TokenMaybe<'tmp>>, E>
+    where
+        'src: 'tmp,
+    {
+        match self.slice.after(*cursor).first() {
+            Some(item) => {
+                *cursor += S::len_of(item.as_ref());
+                Ok(Some(item))
+            }
+            None => Err(self.raise()),
+        }
+    }
+
+    /// Never, as more may be received.
+    #[inline]
+    fn has_reached_end(&mut self, _: Self::Cursor) -> bool {
+        false
+    }
+
+    #[inline]
+    fn span(range: Range<Self::Cursor>) -> Range<usize> {
+        range
+    }
+
+    #[inline]
+    fn offset(cursor: Self::Cursor) -> usize {
+        cursor
+    }
+}
+
+impl<'src, S: ?Sized + Slice<'src>> InputSlice<'src> for PartialInput<'src, S> {
+    type Slice = S;
+
+    #[inline]
+    fn get_slice<'tmp>(&'tmp self, range: Range<Self::Cursor>) -> Option<&'tmp Self::Slice>
+    where
+        'src: 'tmp,
+    {
+        (self.slice.is_item_boundary(range.start) && self.slice.is_item_boundary(range.end))
+            .then(|| self.slice.subslice(range))
+    }
+
+    #[inline]
+    fn fetch_slice<'tmp, E: Error>(&'tmp mut self, start: Self::Cursor) -> Result<(&'tmp Self::Slice, bool), E>
+    where
+        'src: 'tmp,
+    {
+        Ok((self.slice.after(start), false))
+    }
+
+    #[inline]
+    fn fetch_more_slice<'tmp, E: Error>(&'tmp mut self, _: Self::Cursor) -> Result<(&'tmp Self::Slice, bool), E>
+    where
+        'src: 'tmp,
+    {
+        Err(self.raise())
+    }
+
+    #[inline]
+    fn release_slice<'tmp>(&'tmp mut self, range: Range<Self::Cursor>) -> &'tmp Self::Slice
+    where
+        'src: 'tmp,
+    {
+        self.slice.subslice(range)
+    }
+
+    #[inline]
+    fn bump_cursor(cursor: Self::Cursor, length: usize) -> Self::Cursor {
+        cursor + length
+    }
+}
+
+impl<'src, S> InputOwnableToken<'src> for PartialInput<'src, S>
+where
+    S: ?Sized + Slice<'src>,
+    S::Item: Clone,
+{
+    fn get_owned(&self, cursor: Self::Cursor) -> Option<Self::Token> {
+        self.slice.after(cursor).first().map(|item| item.cloned())
+    }
+
+    fn iter_owned(&self, range: Range<Self::Cursor>) -> impl Iterator<Item = Self::Token> {
+        self.slice.subslice(range).iter().map(|item| item.cloned())
+    }
+}
+
+impl<'src, T> InputBorrowableToken<'src> for PartialInput<'src, [T]> {
+    fn get_borrowed(&self, cursor: Self::Cursor) -> Option<&'src Self::Token> {
+        self.slice.get(cursor)
+    }
+
+    fn iter_borrowed(&self, range: Range<Self::Cursor>) -> impl Iterator<Item = &'src Self::Token> {
+        self.slice[range].iter()
+    }
+}
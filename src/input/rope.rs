@@ -0,0 +1,229 @@
+use crate::{error::*, input::*};
+use alloc::string::String;
+use core::ops::Range;
+
+/// In-memory text stored as a sequence of chunks, such as the rope of an editor buffer.
+///
+/// Slices within a single chunk are borrowed directly, only the slices straddling chunks
+/// are stitched into an owned buffer, which is reused.
+#[derive(Debug)]
+pub struct Rope<'src> {
+    chunks: &'src [&'src str],
+    buf: String,
+    window: Option<((usize, usize), usize)>,
+}
+
+impl<'src> Rope<'src> {
+    pub fn new(chunks: &'src [&'src str]) -> Self {
+        Self {
+            chunks,
+            buf: String::new(),
+            window: None,
+        }
+    }
+
+    pub fn chunks(&self) -> &'src [&'src str] {
+        self.chunks
+    }
+
+    fn stitch(&mut self, start: &RopeCursor<'src>, end_chunk: usize, end_offset: usize) -> &str {
+        self.buf.clear();
+        for (i, chunk) in self.chunks.iter().enumerate().take(end_chunk + 1).skip(start.chunk) {
+            let from = if i == start.chunk { start.offset } else { 0 };
+            let to = if i == end_chunk { end_offset } else { chunk.len() };
+            self.buf.push_str(&chunk[from..to]);
+        }
+        &self.buf
+    }
+}
+
+/// Points to the `offset`-th byte of the `chunk`-th chunk, and also knows the absolute offset.
+///
+/// Cursors at the end of a chunk are normalized to the beginning of the next non-empty chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RopeCursor<'src> {
+    chunks: &'src [&'src str],
+    chunk: usize,
+    offset: usize,
+    base: usize,
+}
+
+impl RopeCursor<'_> {
+    pub fn chunk(&self) -> usize {
+        self.chunk
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn bump(mut self, mut length: usize) -> Self {
+        loop {
+            let Some(chunk) = self.chunks.get(self.chunk) else {
+                break self;
+            };
+
+            let rest = chunk.len() - self.offset;
+            if length < rest || self.chunk + 1 == self.chunks.len() {
+                self.offset += length;
+                break self;
+            }
+
+            length -= rest;
+            self.base += chunk.len();
+            self.chunk += 1;
+            self.offset = 0;
+        }
+    }
+}
+
+impl<'src> Input<'src> for Rope<'src> {
+    type Token = char;
+
+    type TokenMaybe<'tmp>
+        = char
+    where
+        'src: 'tmp;
+
+    type Cursor = RopeCursor<'src>;
+
+    #[inline]
+    fn begin(&self) -> Self::Cursor {
+        RopeCursor {
+            chunks: self.chunks,
+            chunk: 0,
+            offset: 0,
+            base: 0,
+        }
+        .bump(0)
+    }
+
+    #[inline]
+    fn next_maybe_ref<'tmp, E: Error>(
+        &'tmp mut self,
+        cursor: &mut Self::Cursor,
+    ) -> Result<Option<Self::TokenMaybe<'tmp>>, E>
+    where
+        'src: 'tmp,
+    {
+        let ch = self
+            .chunks
+            .get(cursor.chunk)
+            .and_then(|chunk| chunk[cursor.offset..].chars().next());
+        if let Some(ch) = ch {
+            *cursor = cursor.clone().bump(ch.len_utf8());
+        }
+        Ok(ch)
+    }
+
+    #[inline]
+    fn has_reached_end(&mut self, cursor: Self::Cursor) -> bool {
+        self.chunks
+            .get(cursor.chunk)
+            .is_none_or(|chunk| cursor.chunk + 1 >= self.chunks.len() && cursor.offset == chunk.len())
+    }
+
+    #[inline]
+    fn span(range: Range<Self::Cursor>) -> Range<usize> {
+        Self::offset(range.start)..Self::offset(range.end)
+    }
+
+    #[inline]
+    fn offset(cursor: Self::Cursor) -> usize {
+        cursor.base + cursor.offset
+    }
+}
+
+impl<'src> InputSlice<'src> for Rope<'src> {
+    type Slice = str;
+
+    #[inline]
+    fn get_slice<'tmp>(&'tmp self, range: Range<Self::Cursor>) -> Option<&'tmp Self::Slice>
+    where
+        'src: 'tmp,
+    {
+        let (start, end) = (range.start, range.end);
+        if start.chunk == end.chunk {
+            return self.chunks.get(start.chunk)?.get(start.offset..end.offset);
+        }
+
+        // Only the latest stitched slice is available.
+        let len = Self::offset(end) - Self::offset(start.clone());
+        match self.window {
+            Some((key, _)) if key == (start.chunk, start.offset) => self.buf.get(..len),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn fetch_slice<'tmp, E: Error>(&'tmp mut self, start: Self::Cursor) -> Result<(&'tmp Self::Slice, bool), E>
+    where
+        'src: 'tmp,
+    {
+        let Some(last) = self.chunks.len().checked_sub(1) else {
+            return Ok(("", true));
+        };
+
+        let key = (start.chunk, start.offset);
+        let end_chunk = match self.window {
+            Some((k, end_chunk)) if k == key => (end_chunk + 1).min(last),
+            _ => start.chunk,
+        };
+        self.window = Some((key, end_chunk));
+
+        let eof = end_chunk == last;
+        match end_chunk == start.chunk {
+            true => Ok((&self.chunks[start.chunk][start.offset..], eof)),
+            false => Ok((self.stitch(&start, end_chunk, self.chunks[end_chunk].len()), eof)),
+        }
+    }
+
+    #[inline]
+    fn release_slice<'tmp>(&'tmp mut self, range: Range<Self::Cursor>) -> &'tmp Self::Slice
+    where
+        'src: 'tmp,
+    {
+        let (start, end) = (range.start, range.end);
+        if start.chunk == end.chunk {
+            return &self.chunks[start.chunk][start.offset..end.offset];
+        }
+
+        // A cursor normalized to the beginning of a chunk ends the slice at the end of the previous one.
+        if end.offset == 0 {
+            let end_chunk = end.chunk - 1;
+            if end_chunk == start.chunk {
+                return &self.chunks[start.chunk][start.offset..];
+            }
+            self.window = Some(((start.chunk, start.offset), end_chunk));
+            return self.stitch(&start, end_chunk, self.chunks[end_chunk].len());
+        }
+
+        self.window = Some(((start.chunk, start.offset), end.chunk));
+        self.stitch(&start, end.chunk, end.offset)
+    }
+
+    #[inline]
+    fn bump_cursor(cursor: Self::Cursor, length: usize) -> Self::Cursor {
+        cursor.bump(length)
+    }
+}
+
+impl<'src> InputOwnableToken<'src> for Rope<'src> {
+    fn get_owned(&self, cursor: Self::Cursor) -> Option<Self::Token> {
+        self.chunks.get(cursor.chunk)?[cursor.offset..].chars().next()
+    }
+
+    fn iter_owned(&self, range: Range<Self::Cursor>) -> impl Iterator<Item = Self::Token> {
+        let (start, end) = (range.start, range.end);
+        self.chunks
+            .iter()
+            .enumerate()
+            .take(end.chunk + 1)
+            .skip(start.chunk)
+            .flat_map(move |(i, chunk)| {
+                let from = if i == start.chunk { start.offset } else { 0 };
+                let to = if i == end.chunk { end.offset } else { chunk.len() };
+                chunk[from..to].chars()
+            })
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{error::*, input::*};
+use core::ops::Range;
+
+/// A slice whose offsets are shifted by `base`, such as a part of an outer input parsed on its own.
+#[derive(Debug)]
+pub struct SourceInput<'src, S: ?Sized = str> {
+    slice: &'src S,
+    base: usize,
+}
+
+impl<S: ?Sized> Clone for SourceInput<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: ?Sized> Copy for SourceInput<'_, S> {}
+
+impl<'src, S: ?Sized + Slice<'src>> SourceInput<'src, S> {
+    pub fn new(slice: &'src S, base: usize) -> Self {
+        Self { slice, base }
+    }
+
+    pub fn slice(&self) -> &'src S {
+        self.slice
+    }
+
+    pub fn base(&self) -> usize {
+        self.base
+    }
+}
+
+/// Local offset, and the base of its source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceCursor {
+    pos: usize,
+    base: usize,
+}
+
+impl SourceCursor {
+    /// Offset from the beginning of its source.
+    pub fn local(&self) -> usize {
+        self.pos
+    }
+}
+
+unsafe impl<'src, S: ?Sized + Slice<'src>> StaticInput for SourceInput<'src, S> {}
+
+impl<'src, S: ?Sized + Slice<'src>> Input<'src> for SourceInput<'src, S> {
+    type Token = S::Item;
+
+    type TokenMaybe<'tmp>
+        = S::ItemMaybe<'tmp>
+    where
+        'src: 'tmp;
+
+    type Cursor = SourceCursor;
+
+    #[inline]
+    fn begin(&self) -> Self::Cursor {
+        SourceCursor {
+            pos: 0,
+            base: self.base,
+        }
+    }
+
+    #[inline]
+    fn next_maybe_ref<'tmp, E: Error>(
+        &'tmp mut self,
+        cursor: &mut Self::Cursor,
+    ) -> Result<Option<Self::TokenMaybe<'tmp>>, E>
+    where
+        'src: 'tmp,
+    {
+        Ok(self
+            .slice
+            .after(cursor.pos)
+            .first()
+            .inspect(|item| cursor.pos += S::len_of(item.as_ref())))
+    }
+
+    #[inline]
+    fn has_reached_end(&mut self, cursor: Self::Cursor) -> bool {
+        cursor.pos == self.slice.len()
+    }
+
+    #[inline]
+    fn span(range: Range<Self::Cursor>) -> Range<usize> {
+        Self::offset(range.start)..Self::offset(range.end)
+    }
+
+    #[inline]
+    fn offset(cursor: Self::Cursor) -> usize {
+        cursor.base + cursor.pos
+    }
+}
+
+impl<'src, S: ?Sized + Slice<'src>> InputSlice<'src> for SourceInput<'src, S> {
+    type Slice = S;
+
+    #[inline]
+    fn get_slice<'tmp>(&'tmp self, range: Range<Self::Cursor>) -> Option<&'tmp Self::Slice>
+    where
+        'src: 'tmp,
+    {
+        let (start, end) = (range.start.pos, range.end.pos);
+        (self.slice.is_item_boundary(start) && self.slice.is_item_boundary(end))
+            .then(|| self.slice.subslice(start..end))
+    }
+
+    #[inline]
+    fn fetch_slice<'tmp, E: Error>(&'tmp mut self, start: Self::Cursor) -> Result<(&'tmp Self::Slice, bool), E>
+    where
+        'src: 'tmp,
+    {
+        Ok((self.slice.after(start.pos), true))
+    }
+
+    #[inline]
+    fn release_slice<'tmp>(&'tmp mut self, range: Range<Self::Cursor>) -> &'tmp Self::Slice
+    where
+        'src: 'tmp,
+    {
+        self.slice.subslice(range.start.pos..range.end.pos)
+    }
+
+    #[inline]
+    fn bump_cursor(mut cursor: Self::Cursor, length: usize) -> Self::Cursor {
+        cursor.pos += length;
+        cursor
+    }
+}
+
+impl<'src, S: ?Sized + Slice<'src>> InputTruncatable<'src> for SourceInput<'src, S> {
+    #[inline]
+    fn truncate(&self, end: Self::Cursor) -> Option<Self> {
+        (end.pos <= self.slice.len() && self.slice.is_item_boundary(end.pos)).then(|| Self {
+            slice: self.slice.before(end.pos),
+            base: self.base,
+        })
+    }
+}
+
+impl<'src, S> InputOwnableToken<'src> for SourceInput<'src, S>
+where
+    S: ?Sized + Slice<'src>,
+    S::Item: Clone,
+{
+    fn get_owned(&self, cursor: Self::Cursor) -> Option<Self::Token> {
+        self.slice.after(cursor.pos).first().map(|item| item.cloned())
+    }
+
+    fn iter_owned(&self, range: Range<Self::Cursor>) -> impl Iterator<Item = Self::Token> {
+        self.slice
+            .subslice(range.start.pos..range.end.pos)
+            .iter()
+            .map(|item| item.cloned())
+    }
+}
+
+impl<'src, T> InputBorrowableToken<'src> for SourceInput<'src, [T]> {
+    fn get_borrowed(&self, cursor: Self::Cursor) -> Option<&'src Self::Token> {
+        self.slice.get(cursor.pos)
+    }
+
+    fn iter_borrowed(&self, range: Range<Self::Cursor>) -> impl Iterator<Item = &'src Self::Token> {
+        self.slice[range.start.pos..range.end.pos].iter()
+    }
+}
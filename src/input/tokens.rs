@@ -0,0 +1,185 @@
+use crate::{error::*, input::*};
+use core::ops::Range;
+
+/// Tokens produced by an external lexer, paired with their spans in the source text.
+///
+/// Cursors index into tokens, but [`Input::span`] and [`Input::offset`] report byte ranges in the source,
+/// so that diagnostics point into the original text.
+#[derive(Debug)]
+pub struct SpannedTokens<'src, T> {
+    tokens: &'src [T],
+    spans: &'src [Range<usize>],
+    end: usize,
+}
+
+impl<'src, T> SpannedTokens<'src, T> {
+    /// # Panics
+    ///
+    /// Panics if `tokens` and `spans` have different lengths.
+    pub fn new(tokens: &'src [T], spans: &'src [Range<usize>]) -> Self {
+        assert_eq!(tokens.len(), spans.len(), "each token shall have a span");
+        Self {
+            tokens,
+            spans,
+            end: spans.last().map_or(0, |span| span.end),
+        }
+    }
+
+    /// Sets the offset that the end of input reports, which defaults to the end of the last token.
+    pub fn with_end(mut self, end: usize) -> Self {
+        self.end = end;
+        self
+    }
+
+    pub fn tokens(&self) -> &'src [T] {
+        self.tokens
+    }
+
+    pub fn spans(&self) -> &'src [Range<usize>] {
+        self.spans
+    }
+}
+
+impl<T> Clone for SpannedTokens<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SpannedTokens<'_, T> {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenCursor<'src> {
+    index: usize,
+    spans: &'src [Range<usize>],
+    end: usize,
+}
+
+impl TokenCursor<'_> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+unsafe impl<T> StaticInput for SpannedTokens<'_, T> {}
+
+impl<'src, T: 'src> Input<'src> for SpannedTokens<'src, T> {
+    type Token = T;
+
+    type TokenMaybe<'tmp>
+        = &'tmp T
+    where
+        'src: 'tmp;
+
+    type Cursor = TokenCursor<'src>;
+
+    #[inline]
+    fn begin(&self) -> Self::Cursor {
+        TokenCursor {
+            index: 0,
+            spans: self.spans,
+            end: self.end,
+        }
+    }
+
+    #[inline]
+    fn next_maybe_ref<'tmp, E: Error>(
+        &'tmp mut self,
+        cursor: &mut Self::Cursor,
+    ) -> Result<Option<Self::TokenMaybe<'tmp>>, E>
+    where
+        'src: 'tmp,
+    {
+        Ok(self.tokens.get(cursor.index).inspect(|_| cursor.index += 1))
+    }
+
+    #[inline]
+    fn has_reached_end(&mut self, cursor: Self::Cursor) -> bool {
+        cursor.index >= self.tokens.len()
+    }
+
+    #[inline]
+    fn span(range: Range<Self::Cursor>) -> Range<usize> {
+        match range.start.index < range.end.index {
+            true => {
+                let end = range
+                    .end
+                    .spans
+                    .get(range.end.index - 1)
+                    .map_or(range.end.end, |span| span.end);
+                Self::offset(range.start)..end
+            }
+            false => Self::offset_span(range.start),
+        }
+    }
+
+    #[inline]
+    fn offset(cursor: Self::Cursor) -> usize {
+        cursor.spans.get(cursor.index).map_or(cursor.end, |span| span.start)
+    }
+}
+
+impl<'src, T: 'src> InputSlice<'src> for SpannedTokens<'src, T> {
+    type Slice = [T];
+
+    #[inline]
+    fn get_slice<'tmp>(&'tmp self, range: Range<Self::Cursor>) -> Option<&'tmp Self::Slice>
+    where
+        'src: 'tmp,
+    {
+        self.tokens.get(range.start.index..range.end.index)
+    }
+
+    #[inline]
+    fn fetch_slice<'tmp, E: Error>(&'tmp mut self, start: Self::Cursor) -> Result<(&'tmp Self::Slice, bool), E>
+    where
+        'src: 'tmp,
+    {
+        Ok((&self.tokens[start.index..], true))
+    }
+
+    #[inline]
+    fn release_slice<'tmp>(&'tmp mut self, range: Range<Self::Cursor>) -> &'tmp Self::Slice
+    where
+        'src: 'tmp,
+    {
+        &self.tokens[range.start.index..range.end.index]
+    }
+
+    #[inline]
+    fn bump_cursor(mut cursor: Self::Cursor, length: usize) -> Self::Cursor {
+        cursor.index += length;
+        cursor
+    }
+}
+
+impl<'src, T: 'src> InputTruncatable<'src> for SpannedTokens<'src, T> {
+    #[inline]
+    fn truncate(&self, end: Self::Cursor) -> Option<Self> {
+        (end.index <= self.tokens.len()).then(|| Self {
+            tokens: &self.tokens[..end.index],
+            spans: &self.spans[..end.index],
+            end: Self::offset(end),
+        })
+    }
+}
+
+impl<'src, T: 'src + Clone> InputOwnableToken<'src> for SpannedTokens<'src, T> {
+    fn get_owned(&self, cursor: Self::Cursor) -> Option<Self::Token> {
+        self.tokens.get(cursor.index).cloned()
+    }
+
+    fn iter_owned(&self, range: Range<Self::Cursor>) -> impl Iterator<Item = Self::Token> {
+        self.tokens[range.start.index..range.end.index].iter().cloned()
+    }
+}
+
+impl<'src, T: 'src> InputBorrowableToken<'src> for SpannedTokens<'src, T> {
+    fn get_borrowed(&self, cursor: Self::Cursor) -> Option<&'src Self::Token> {
+        self.tokens.get(cursor.index)
+    }
+
+    fn iter_borrowed(&self, range: Range<Self::Cursor>) -> impl Iterator<Item = &'src Self::Token> {
+        self.tokens[range.start.index..range.end.index].iter()
+    }
+}
@@ -0,0 +1,353 @@
+use crate::{error::*, input::*};
+use core::{ops::Range, str};
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/// Bytes are validated lazily by chunks of this size.
+const CHUNK: usize = 4096;
+
+/// Text input over bytes that are supposed to be UTF-8.
+///
+/// Bytes are validated lazily as they are fetched, chunk by chunk.
+/// Offsets and spans are measured in bytes of the original input.
+///
+/// - In strict mode, invalid sequences are reported as [`ErrorKind::InvalidInput`] at their exact byte spans.
+/// - In lossy mode, each invalid sequence is substituted with U+FFFD REPLACEMENT CHARACTER,
+///   and only the slices containing them are decoded into an owned buffer.
+#[derive(Debug)]
+pub struct Utf8Input<'src> {
+    bytes: &'src [u8],
+    lossy: bool,
+    /// Known valid bytes, starting and ending at character boundaries.
+    valid: Range<usize>,
+    /// The last fetched window, and whether it was cut off by an invalid sequence.
+    window: Option<(usize, usize, bool)>,
+    #[cfg(feature = "alloc")]
+    buf: String,
+    #[cfg(feature = "alloc")]
+    buf_range: Range<usize>,
+}
+
+impl<'src> Utf8Input<'src> {
+    pub fn strict(bytes: &'src [u8]) -> Self {
+        Self {
+            bytes,
+            lossy: false,
+            valid: 0..0,
+            window: None,
+            #[cfg(feature = "alloc")]
+            buf: String::new(),
+            #[cfg(feature = "alloc")]
+            buf_range: 0..0,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn lossy(bytes: &'src [u8]) -> Self {
+        Self {
+            lossy: true,
+            ..Self::strict(bytes)
+        }
+    }
+
+    pub fn bytes(&self) -> &'src [u8] {
+        self.bytes
+    }
+
+    /// Validates `start..end`, returns the end of the valid prefix and the length of the invalid sequence after it.
+    fn validate(&mut self, start: usize, end: usize) -> (usize, Option<usize>) {
+        let from = match self.valid.contains(&start) || self.valid.end == start {
+            true => self.valid.end.max(start),
+            false => {
+                self.valid = start..start;
+                start
+            }
+        };
+
+        if from >= end {
+            return (end, None);
+        }
+
+        match str::from_utf8(&self.bytes[from..end]) {
+            Ok(_) => {
+                self.valid.end = end;
+                (end, None)
+            }
+            Err(e) => {
+                let up_to = from + e.valid_up_to();
+                self.valid.end = up_to;
+                match e.error_len() {
+                    Some(len) => (up_to, Some(len)),
+                    // Truncated at the end of input.
+                    None if end == self.bytes.len() => (up_to, Some(end - up_to)),
+                    // Truncated at the end of window, the remaining bytes will be validated next time.
+                    None => (up_to, None),
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn cursor(&self, pos: usize) -> Utf8Cursor<'src> {
+        Utf8Cursor {
+            bytes: self.bytes,
+            pos,
+            lossy: self.lossy,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn decode_lossy(&mut self, range: Range<usize>) -> &str {
+        if self.buf_range != range {
+            self.buf.clear();
+            for chunk in self.bytes[range.clone()].utf8_chunks() {
+                self.buf.push_str(chunk.valid());
+                if !chunk.invalid().is_empty() {
+                    self.buf.push(char::REPLACEMENT_CHARACTER);
+                }
+            }
+            self.buf_range = range;
+        }
+        &self.buf
+    }
+}
+
+/// Byte offset into the original input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utf8Cursor<'src> {
+    bytes: &'src [u8],
+    pos: usize,
+    lossy: bool,
+}
+
+impl Utf8Cursor<'_> {
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+/// Decodes the first character, or returns the length of the invalid sequence.
+#[inline]
+fn decode(bytes: &[u8]) -> Result<char, usize> {
+    let len = match bytes[0] {
+        0x00..=0x7f => 1,
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => return Err(1),
+    };
+
+    let bytes = &bytes[..len.min(bytes.len())];
+    match str::from_utf8(bytes) {
+        Ok(s) => Ok(s.chars().next().unwrap()),
+        Err(e) => Err(e.error_len().unwrap_or(bytes.len())),
+    }
+}
+
+impl<'src> Input<'src> for Utf8Input<'src> {
+    type Token = char;
+
+    type TokenMaybe<'tmp>
+        = char
+    where
+        'src: 'tmp;
+
+    type Cursor = Utf8Cursor<'src>;
+
+    #[inline]
+    fn begin(&self) -> Self::Cursor {
+        self.cursor(0)
+    }
+
+    #[inline]
+    fn next_maybe_ref<'tmp, E: Error>(
+        &'tmp mut self,
+        cursor: &mut Self::Cursor,
+    ) -> Result<Option<Self::TokenMaybe<'tmp>>, E>
+    where
+        'src: 'tmp,
+    {
+        let Some(rest) = self.bytes.get(cursor.pos..).filter(|rest| !rest.is_empty()) else {
+            return Ok(None);
+        };
+
+        match decode(rest) {
+            Ok(ch) => {
+                cursor.pos += ch.len_utf8();
+                Ok(Some(ch))
+            }
+            Err(len) if self.lossy => {
+                cursor.pos += len;
+                Ok(Some(char::REPLACEMENT_CHARACTER))
+            }
+            Err(len) => Err(E::new(cursor.pos..cursor.pos + len, ErrorKind::InvalidInput)),
+        }
+    }
+
+    #[inline]
+    fn has_reached_end(&mut self, cursor: Self::Cursor) -> bool {
+        cursor.pos >= self.bytes.len()
+    }
+
+    #[inline]
+    fn span(range: Range<Self::Cursor>) -> Range<usize> {
+        range.start.pos..range.end.pos
+    }
+
+    #[inline]
+    fn offset(cursor: Self::Cursor) -> usize {
+        cursor.pos
+    }
+}
+
+impl<'src> InputSlice<'src> for Utf8Input<'src> {
+    type Slice = str;
+
+    #[inline]
+    fn get_slice<'tmp>(&'tmp self, range: Range<Self::Cursor>) -> Option<&'tmp Self::Slice>
+    where
+        'src: 'tmp,
+    {
+        let range = range.start.pos..range.end.pos;
+        if self.valid.start <= range.start && range.end <= self.valid.end {
+            // SAFETY: Already validated.
+            return Some(unsafe { str::from_utf8_unchecked(&self.bytes[range]) });
+        }
+
+        #[cfg(feature = "alloc")]
+        if self.buf_range == range {
+            return Some(&self.buf);
+        }
+
+        str::from_utf8(self.bytes.get(range)?).ok()
+    }
+
+    fn fetch_slice<'tmp, E: Error>(&'tmp mut self, start: Self::Cursor) -> Result<(&'tmp Self::Slice, bool), E>
+    where
+        'src: 'tmp,
+    {
+        let start = start.pos;
+        let len = self.bytes.len();
+
+        #[cfg_attr(not(feature = "alloc"), allow(unused_variables))]
+        let (end, cut) = match self.window {
+            Some((s, end, cut)) if s == start => {
+                if cut && !self.lossy {
+                    let (up_to, invalid) = self.validate(start, len.min(end + CHUNK));
+                    let invalid = invalid.unwrap_or(0);
+                    return Err(E::new(up_to..up_to + invalid, ErrorKind::InvalidInput));
+                }
+                (len.min(end + CHUNK), cut)
+            }
+            _ => (len.min(start + CHUNK), false),
+        };
+
+        let (up_to, invalid) = self.validate(start, end);
+
+        if let Some(invalid) = invalid
+            && !self.lossy
+            && up_to == start
+        {
+            return Err(E::new(up_to..up_to + invalid, ErrorKind::InvalidInput));
+        }
+
+        #[cfg(feature = "alloc")]
+        if self.lossy && (cut || invalid.is_some()) {
+            // Do not split a character at the end of window.
+            let end = match up_to < end && invalid.is_none() {
+                true => up_to,
+                false => end,
+            };
+            self.window = Some((start, end, true));
+            return Ok((self.decode_lossy(start..end), end == len));
+        }
+
+        self.window = Some((start, up_to, invalid.is_some()));
+
+        // SAFETY: Validated above.
+        let slice = unsafe { str::from_utf8_unchecked(&self.bytes[start..up_to]) };
+        Ok((slice, invalid.is_none() && up_to == len))
+    }
+
+    #[inline]
+    fn release_slice<'tmp>(&'tmp mut self, range: Range<Self::Cursor>) -> &'tmp Self::Slice
+    where
+        'src: 'tmp,
+    {
+        let range = range.start.pos..range.end.pos;
+        if self.valid.start <= range.start && range.end <= self.valid.end {
+            // SAFETY: Already validated.
+            return unsafe { str::from_utf8_unchecked(&self.bytes[range]) };
+        }
+
+        #[cfg(feature = "alloc")]
+        if self.lossy {
+            return self.decode_lossy(range);
+        }
+
+        match self.validate(range.start, range.end) {
+            (up_to, None) if up_to == range.end => unsafe { str::from_utf8_unchecked(&self.bytes[range]) },
+            _ => panic!("contract violation"),
+        }
+    }
+
+    #[inline]
+    fn bump_cursor(mut cursor: Self::Cursor, length: usize) -> Self::Cursor {
+        let end = cursor.pos + length;
+        if !cursor.lossy
+            || cursor
+                .bytes
+                .get(cursor.pos..end)
+                .is_some_and(|b| str::from_utf8(b).is_ok())
+        {
+            cursor.pos = end;
+            return cursor;
+        }
+
+        // Each replacement character takes 3 bytes in the slice, but as many bytes as the invalid sequence in the input.
+        let mut length = length;
+        while length > 0 && cursor.pos < cursor.bytes.len() {
+            match decode(&cursor.bytes[cursor.pos..]) {
+                Ok(ch) => {
+                    cursor.pos += ch.len_utf8();
+                    length = length.saturating_sub(ch.len_utf8());
+                }
+                Err(len) => {
+                    cursor.pos += len;
+                    length = length.saturating_sub(char::REPLACEMENT_CHARACTER.len_utf8());
+                }
+            }
+        }
+        cursor
+    }
+}
+
+impl<'src> InputOwnableToken<'src> for Utf8Input<'src> {
+    fn get_owned(&self, cursor: Self::Cursor) -> Option<Self::Token> {
+        let rest = self.bytes.get(cursor.pos..).filter(|rest| !rest.is_empty())?;
+        match decode(rest) {
+            Ok(ch) => Some(ch),
+            Err(_) => self.lossy.then_some(char::REPLACEMENT_CHARACTER),
+        }
+    }
+
+    fn iter_owned(&self, range: Range<Self::Cursor>) -> impl Iterator<Item = Self::Token> {
+        let bytes = &self.bytes[range.start.pos..range.end.pos];
+        let lossy = self.lossy;
+        let mut pos = 0;
+        core::iter::from_fn(move || {
+            let rest = bytes.get(pos..).filter(|rest| !rest.is_empty())?;
+            match decode(rest) {
+                Ok(ch) => {
+                    pos += ch.len_utf8();
+                    Some(ch)
+                }
+                Err(len) => {
+                    pos += len;
+                    lossy.then_some(char::REPLACEMENT_CHARACTER)
+                }
+            }
+        })
+    }
+}
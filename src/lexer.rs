@@ -0,0 +1,154 @@
+use crate::{common::*, error::*, extra::*, input::*, parser::*, slice::*};
+use core::{marker::PhantomData, ops::Range};
+
+/// Drives a parser repeatedly over a slice to produce tokens with their spans.
+///
+/// - `token` is usually an [`alt`](crate::combinator::alt) of all token parsers.
+/// - `skip` matches whitespace and comments between tokens, its output is discarded.
+/// - `error` makes an error token from the error and the unrecognized text,
+///   so that the second stage can still run and report more diagnostics.
+///
+/// Consecutive unrecognized items are merged into one error token, and
+/// tokens matching nothing are regarded as unrecognized.
+pub fn lexer<'src, S, Ext, T, P, SP, SO, F>(token: P, skip: SP, error: F) -> Lexer<P, SP, F, SO>
+where
+    S: ?Sized + Slice<'src>,
+    Ext: Extra<'src, &'src S>,
+    P: Parser<'src, &'src S, T, Ext>,
+    SP: Parser<'src, &'src S, SO, Ext>,
+    F: Fn(Ext::Error, &'src S) -> T,
+{
+    Lexer {
+        token,
+        skip,
+        error,
+        phantom: PhantomData,
+    }
+}
+
+pub struct Lexer<P, SP, F, SO> {
+    token: P,
+    skip: SP,
+    error: F,
+    phantom: PhantomData<SO>,
+}
+
+impl<P, SP, F, SO> Lexer<P, SP, F, SO> {
+    /// Lazily yields tokens.
+    pub fn iter<'a, 'src, S, Ext, T>(&'a self, src: &'src S) -> Lex<'a, 'src, S, Ext, P, SP, F, SO>
+    where
+        S: ?Sized + Slice<'src>,
+        Ext: Extra<'src, &'src S>,
+        Ext::State: Default,
+        Ext::Context: Default,
+        P: Parser<'src, &'src S, T, Ext>,
+        SP: Parser<'src, &'src S, SO, Ext>,
+        F: Fn(Ext::Error, &'src S) -> T,
+    {
+        Lex {
+            lexer: self,
+            src,
+            pos: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Collects all tokens, which can be unzipped into [`SpannedTokens`](crate::input::tokens::SpannedTokens).
+    #[cfg(feature = "alloc")]
+    pub fn lex<'src, S, Ext, T>(&self, src: &'src S) -> alloc::vec::Vec<(T, Range<usize>)>
+    where
+        S: ?Sized + Slice<'src>,
+        Ext: Extra<'src, &'src S>,
+        Ext::State: Default,
+        Ext::Context: Default,
+        P: Parser<'src, &'src S, T, Ext>,
+        SP: Parser<'src, &'src S, SO, Ext>,
+        F: Fn(Ext::Error, &'src S) -> T,
+    {
+        self.iter::<S, Ext, T>(src).collect()
+    }
+}
+
+pub struct Lex<'a, 'src, S, Ext, P, SP, F, SO>
+where
+    S: ?Sized,
+{
+    lexer: &'a Lexer<P, SP, F, SO>,
+    src: &'src S,
+    pos: usize,
+    phantom: PhantomData<Ext>,
+}
+
+impl<'a, 'src, S, Ext, P, SP, F, SO> Lex<'a, 'src, S, Ext, P, SP, F, SO>
+where
+    S: ?Sized + Slice<'src>,
+    Ext: Extra<'src, &'src S>,
+    Ext::State: Default,
+    Ext::Context: Default,
+    SP: Parser<'src, &'src S, SO, Ext>,
+{
+    /// Offset of the next token, or the end of source.
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    fn skip_trivia(&self, mut pos: usize) -> usize {
+        let mut input = self.src;
+        while let Some((_, end)) = self.lexer.skip.parse(&mut input, pos).into_output() {
+            match end > pos {
+                true => pos = end,
+                false => break,
+            }
+        }
+        pos
+    }
+}
+
+impl<'a, 'src, S, Ext, T, P, SP, F, SO> Iterator for Lex<'a, 'src, S, Ext, P, SP, F, SO>
+where
+    S: ?Sized + Slice<'src>,
+    Ext: Extra<'src, &'src S>,
+    Ext::State: Default,
+    Ext::Context: Default,
+    P: Parser<'src, &'src S, T, Ext>,
+    SP: Parser<'src, &'src S, SO, Ext>,
+    F: Fn(Ext::Error, &'src S) -> T,
+{
+    type Item = (T, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut input = self.src;
+        let start = self.skip_trivia(self.pos);
+        if input.has_reached_end(start) {
+            self.pos = start;
+            return None;
+        }
+
+        let (out, err) = self.lexer.token.parse(&mut input, start).into_output_error();
+        let err = match (out, err) {
+            (Some((tok, end)), None) if end > start => {
+                self.pos = end;
+                return Some((tok, start..end));
+            }
+            (_, err) => err,
+        };
+
+        let bump = |pos: usize| pos + self.src.after(pos).first().map_or(0, |item| S::len_of(item.as_ref()));
+        let mut end = bump(start);
+        while !input.has_reached_end(end)
+            && self.skip_trivia(end) == end
+            && self
+                .lexer
+                .token
+                .check(&mut input, end)
+                .into_output()
+                .is_none_or(|next| next <= end)
+        {
+            end = bump(end);
+        }
+
+        self.pos = end;
+        let err = err.unwrap_or_else(|| Ext::Error::new(start..start, ErrorKind::InvalidInput));
+        Some(((self.lexer.error)(err, self.src.subslice(start..end)), start..end))
+    }
+}
@@ -1,6 +1,6 @@
 #![no_std]
 
-#[cfg(test)]
+#[cfg(any(test, feature = "mmap"))]
 #[cfg_attr(test, macro_use)]
 extern crate std;
 
@@ -11,13 +11,20 @@ mod private;
 
 #[macro_use]
 pub mod common;
+pub mod binary;
 pub mod combinator;
 pub mod converter;
 pub mod error;
 pub mod extra;
+pub mod grammars;
 pub mod input;
+pub mod lexer;
+pub mod literal;
+pub mod location;
+pub mod number;
 pub mod parser;
 pub mod pattern;
 pub mod predicate;
 pub mod primitive;
 pub mod slice;
+pub mod source;
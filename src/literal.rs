@@ -0,0 +1,205 @@
+use crate::{common::*, error::*, extra::*, input::*, pattern::*, private};
+use core::{cmp::Ordering, fmt};
+
+/// Matches the longest literal of a set, and yields the value associated with it.
+///
+/// The table is sorted at construction time, which turns it into a flattened trie:
+/// each input byte narrows the range of candidates down by binary search,
+/// so the cost does not grow linearly with the number of literals.
+///
+/// This is a `const fn`, therefore the set can be built once into a `const` item, even under `no_std`.
+///
+/// # Panics
+///
+/// Panics if the same literal appears twice.
+pub const fn one_of_literals<'a, V, const N: usize>(table: [(&'a str, V); N]) -> OneOfLiterals<'a, V, N> {
+    OneOfLiterals::new(table, false)
+}
+
+/// Same as [`one_of_literals`], but compares ASCII letters case-insensitively.
+pub const fn one_of_literals_ignore_ascii_case<'a, V, const N: usize>(
+    table: [(&'a str, V); N],
+) -> OneOfLiterals<'a, V, N> {
+    OneOfLiterals::new(table, true)
+}
+
+//------------------------------------------------------------------------------
+
+pub struct OneOfLiterals<'a, V, const N: usize> {
+    entries: [(&'a str, V); N],
+    ignore_case: bool,
+}
+
+impl<'a, V, const N: usize> OneOfLiterals<'a, V, N> {
+    const fn new(mut entries: [(&'a str, V); N], ignore_case: bool) -> Self {
+        let mut i = 1;
+        while i < N {
+            let mut j = i;
+            while j > 0 {
+                match compare(entries[j - 1].0.as_bytes(), entries[j].0.as_bytes(), ignore_case) {
+                    Ordering::Less => break,
+                    Ordering::Equal => panic!("duplicate literal"),
+                    Ordering::Greater => entries.swap(j - 1, j),
+                }
+                j -= 1;
+            }
+            i += 1;
+        }
+
+        Self { entries, ignore_case }
+    }
+
+    pub fn literals(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.entries.iter().map(|(lit, _)| *lit)
+    }
+
+    /// Returns the index of the longest literal prefixing `bytes`,
+    /// or `None` if more bytes are needed to make the decision.
+    #[inline]
+    fn dispatch(&self, bytes: &[u8], eof: bool) -> Option<Option<usize>> {
+        let (mut lo, mut hi) = (0, N);
+        let mut depth = 0;
+        let mut best = None;
+
+        while lo < hi {
+            // Prefixes are sorted before their extensions.
+            if self.entries[lo].0.len() == depth {
+                best = Some(lo);
+                lo += 1;
+                if lo == hi {
+                    break;
+                }
+            }
+
+            let Some(&byte) = bytes.get(depth) else {
+                return eof.then_some(best);
+            };
+
+            let byte = fold(byte, self.ignore_case);
+            let range = &self.entries[lo..hi];
+            let start = lo + range.partition_point(|(lit, _)| fold(lit.as_bytes()[depth], self.ignore_case) < byte);
+            let end = lo + range.partition_point(|(lit, _)| fold(lit.as_bytes()[depth], self.ignore_case) <= byte);
+
+            (lo, hi) = (start, end);
+            depth += 1;
+        }
+
+        Some(best)
+    }
+}
+
+impl<'a, V, const N: usize> Describe for OneOfLiterals<'a, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "one of ")?;
+        for (i, lit) in self.literals().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:?}", lit)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'src, 'a, I, Ext, V, const N: usize> Pattern<'src, I, Ext> for OneOfLiterals<'a, V, N>
+where
+    I: InputByteSlice<'src>,
+    Ext: Extra<'src, I>,
+    V: Clone,
+{
+    type View<'tmp>
+        = V
+    where
+        'src: 'tmp;
+
+    #[inline]
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        drop((state, ctx));
+        PResult::from(__dispatch(self, input, start.clone())).map(|idx| {
+            let (lit, val) = &self.entries[idx];
+            let end = I::bump_cursor(start.clone(), lit.len());
+            input.release_slice(start..end.clone());
+            (val.clone(), end)
+        })
+    }
+
+    #[inline]
+    fn __check<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        drop((state, ctx));
+        PResult::from(__dispatch(self, input, start.clone()))
+            .map(|idx| I::bump_cursor(start, self.entries[idx].0.len()))
+    }
+}
+
+#[inline]
+fn __dispatch<'src, I, E, V, const N: usize>(
+    lits: &OneOfLiterals<'_, V, N>,
+    input: &mut I,
+    start: I::Cursor,
+) -> Result<usize, E>
+where
+    I: InputByteSlice<'src>,
+    E: Error,
+{
+    let (bytes, eof) = input.fetch_byte_slice(start.clone())?;
+    if let Some(best) = lits.dispatch(bytes, eof) {
+        return best.ok_or_else(|| E::new(I::span(start.clone()..start), ErrorKind::Expected(lits)));
+    }
+    loop {
+        let (bytes, eof) = input.fetch_more_byte_slice(start.clone())?;
+        if let Some(best) = lits.dispatch(bytes, eof) {
+            break best.ok_or_else(|| E::new(I::span(start.clone()..start), ErrorKind::Expected(lits)));
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[inline]
+const fn fold(byte: u8, ignore_case: bool) -> u8 {
+    match ignore_case {
+        true => byte.to_ascii_lowercase(),
+        false => byte,
+    }
+}
+
+const fn compare(a: &[u8], b: &[u8], ignore_case: bool) -> Ordering {
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        let (x, y) = (fold(a[i], ignore_case), fold(b[i], ignore_case));
+        if x < y {
+            return Ordering::Less;
+        } else if x > y {
+            return Ordering::Greater;
+        }
+        i += 1;
+    }
+
+    if a.len() < b.len() {
+        Ordering::Less
+    } else if a.len() > b.len() {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
@@ -0,0 +1,201 @@
+use core::{fmt, num::NonZeroUsize, ops::Range};
+use memchr::{memchr_iter, memrchr};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// What a column counts from the beginning of its line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnUnit {
+    Bytes,
+    Chars,
+    /// As LSP does by default.
+    Utf16,
+    /// Extended grapheme clusters, as users perceive.
+    Graphemes,
+}
+
+impl ColumnUnit {
+    fn count(self, text: &str) -> usize {
+        match self {
+            ColumnUnit::Bytes => text.len(),
+            ColumnUnit::Chars => text.chars().count(),
+            ColumnUnit::Utf16 => text.chars().map(char::len_utf16).sum(),
+            ColumnUnit::Graphemes => unicode_segmentation::UnicodeSegmentation::graphemes(text, true).count(),
+        }
+    }
+}
+
+/// One-based line and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    pub line: NonZeroUsize,
+    pub column: NonZeroUsize,
+}
+
+impl LineCol {
+    fn new(line: usize, column: usize) -> Self {
+        Self {
+            line: NonZeroUsize::MIN.saturating_add(line),
+            column: NonZeroUsize::MIN.saturating_add(column),
+        }
+    }
+}
+
+impl fmt::Display for LineCol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Byte range together with the lines and columns of both ends.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub range: Range<usize>,
+    pub start: LineCol,
+    pub end: LineCol,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// Locates the offset by scanning from the beginning.
+///
+/// Returns `None` if the offset is out of bounds or not at a character boundary.
+/// Build a [`LineIndex`] instead, if many offsets are going to be located.
+pub fn line_col(text: &str, offset: usize, unit: ColumnUnit) -> Option<LineCol> {
+    let before = text.get(..offset)?;
+    let line = memchr_iter(b'\n', before.as_bytes()).count();
+    let line_start = memrchr(b'\n', before.as_bytes()).map_or(0, |off| off + 1);
+    Some(LineCol::new(line, unit.count(&before[line_start..])))
+}
+
+pub fn line_col_span(text: &str, range: Range<usize>, unit: ColumnUnit) -> Option<Span> {
+    Some(Span {
+        start: line_col(text, range.start, unit)?,
+        end: line_col(text, range.end, unit)?,
+        range,
+    })
+}
+
+/// Side table of line starts, built once with `memchr`, then offsets are located by binary search.
+///
+/// Usually put into [`Extra::Context`](crate::extra::Extra::Context),
+/// so that [`ProvideExtra::located_span`](crate::extra::ProvideExtra::located_span) is available.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct LineIndex<'src> {
+    text: &'src str,
+    starts: Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'src> LineIndex<'src> {
+    pub fn new(text: &'src str) -> Self {
+        let starts = core::iter::once(0)
+            .chain(memchr_iter(b'\n', text.as_bytes()).map(|off| off + 1))
+            .collect();
+        Self { text, starts }
+    }
+
+    pub fn text(&self) -> &'src str {
+        self.text
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Zero-based line number containing the offset.
+    pub fn line_of(&self, offset: usize) -> Option<usize> {
+        (offset <= self.text.len()).then(|| self.starts.partition_point(|&start| start <= offset) - 1)
+    }
+
+    /// Byte range of the zero-based line, excluding the line terminator.
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.starts.get(line)?;
+        let end = self.starts.get(line + 1).map_or(self.text.len(), |next| next - 1);
+        let end = match self.text[..end].ends_with('\r') {
+            true => end - 1,
+            false => end,
+        };
+        Some(start..end)
+    }
+
+    pub fn line(&self, line: usize) -> Option<&'src str> {
+        self.line_range(line).map(|range| &self.text[range])
+    }
+
+    /// Returns `None` if the offset is out of bounds or not at a character boundary.
+    pub fn line_col(&self, offset: usize, unit: ColumnUnit) -> Option<LineCol> {
+        let line = self.line_of(offset)?;
+        let prefix = self.text.get(self.starts[line]..offset)?;
+        Some(LineCol::new(line, unit.count(prefix)))
+    }
+
+    pub fn span(&self, range: Range<usize>, unit: ColumnUnit) -> Option<Span> {
+        Some(Span {
+            start: self.line_col(range.start, unit)?,
+            end: self.line_col(range.end, unit)?,
+            range,
+        })
+    }
+
+    /// Inverse of [`line_col`](Self::line_col).
+    ///
+    /// Returns `None` if the column is beyond the end of the line, where the line terminator is not counted,
+    /// or does not fall at a boundary of its unit.
+    pub fn offset(&self, pos: LineCol, unit: ColumnUnit) -> Option<usize> {
+        let range = self.line_range(pos.line.get() - 1)?;
+        let line = &self.text[range.clone()];
+        let column = pos.column.get() - 1;
+        let end = core::iter::once(line.len());
+        let offset = match unit {
+            ColumnUnit::Bytes => nth_boundary(
+                line.char_indices()
+                    .map(|(off, _)| (off, off))
+                    .chain(end.map(|off| (off, off))),
+                column,
+            ),
+            ColumnUnit::Chars => nth_boundary(line.char_indices().map(|(off, _)| off).chain(end).enumerate(), column),
+            ColumnUnit::Utf16 => nth_boundary(
+                line.char_indices()
+                    .map(|(off, ch)| (off, ch.len_utf16()))
+                    .chain(end.map(|off| (off, 0)))
+                    .scan(0, |units, (off, len)| {
+                        let col = *units;
+                        *units += len;
+                        Some((col, off))
+                    }),
+                column,
+            ),
+            ColumnUnit::Graphemes => nth_boundary(
+                unicode_segmentation::UnicodeSegmentation::grapheme_indices(line, true)
+                    .map(|(off, _)| off)
+                    .chain(end)
+                    .enumerate(),
+                column,
+            ),
+        };
+        Some(range.start + offset?)
+    }
+}
+
+/// Byte offset of the boundary at the column, from `(column, offset)` pairs in ascending order.
+#[cfg(feature = "alloc")]
+fn nth_boundary(mut boundaries: impl Iterator<Item = (usize, usize)>, column: usize) -> Option<usize> {
+    boundaries
+        .find(|&(col, _)| col >= column)
+        .filter(|&(col, _)| col == column)
+        .map(|(_, off)| off)
+}
+
+#[cfg(feature = "alloc")]
+impl<'src> AsRef<LineIndex<'src>> for LineIndex<'src> {
+    fn as_ref(&self) -> &LineIndex<'src> {
+        self
+    }
+}
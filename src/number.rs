@@ -0,0 +1,495 @@
+use crate::{common::*, error::*, extra::*, input::*, pattern::*, private};
+use core::{fmt, marker::PhantomData};
+
+/// An integer in the given `radix`, optionally signed.
+///
+/// A leading `+` is always accepted, a leading `-` only if `T` is signed.
+/// Overflow is reported as [`ErrorKind::Overflow`] at the span of the whole literal.
+///
+/// # Panics
+///
+/// Panics if `radix` is not in the range `2..=36`.
+pub const fn int<T: PrimInt>(radix: u32) -> Int<T> {
+    assert!(2 <= radix && radix <= 36, "radix must be in 2..=36");
+    Int {
+        radix,
+        signed: true,
+        separator: None,
+        phantom: PhantomData,
+    }
+}
+
+/// A decimal floating point number, such as `-12.5e-3`.
+///
+/// Both the integral part and the fractional part (if the point presents) require at least one digit.
+/// Finite literals too large for `T` are reported as [`ErrorKind::Overflow`].
+/// Without `alloc`, literals with separators longer than [`FLOAT_BUFFER_LEN`] are reported as [`TooLong`].
+pub const fn float<T: PrimFloat>() -> Float<T> {
+    Float {
+        signed: true,
+        separator: None,
+        inf_nan: false,
+        phantom: PhantomData,
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub struct Int<T> {
+    radix: u32,
+    signed: bool,
+    separator: Option<u8>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: PrimInt> Int<T> {
+    /// Rejects the leading `+` or `-`.
+    pub const fn unsigned(mut self) -> Self {
+        self.signed = false;
+        self
+    }
+
+    /// Allows `sep` (usually `_`) to appear between two digits.
+    pub const fn separator(mut self, sep: u8) -> Self {
+        self.separator = Some(sep);
+        self
+    }
+
+    fn scan(&self, bytes: &[u8]) -> (usize, bool) {
+        let mut scanner = Scanner::new(bytes, self.separator);
+        if self.signed {
+            scanner.sign(T::SIGNED);
+        }
+        match scanner.digits(self.radix) {
+            0 => (0, scanner.hit_end),
+            _ => (scanner.pos, scanner.hit_end),
+        }
+    }
+
+    fn convert(&self, lit: &[u8]) -> Option<T> {
+        let negative = lit[0] == b'-';
+        lit.iter()
+            .filter_map(|b| (*b as char).to_digit(self.radix))
+            .try_fold(T::ZERO, |acc, digit| acc.push_digit(self.radix, digit, negative))
+    }
+}
+
+impl<T> Describe for Int<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.radix {
+            2 => write!(f, "a binary integer"),
+            8 => write!(f, "an octal integer"),
+            10 => write!(f, "a decimal integer"),
+            16 => write!(f, "a hexadecimal integer"),
+            radix => write!(f, "an integer in radix {}", radix),
+        }
+    }
+}
+
+impl<'src, I, Ext, T> Pattern<'src, I, Ext> for Int<T>
+where
+    I: InputByteSlice<'src>,
+    Ext: Extra<'src, I>,
+    T: PrimInt,
+{
+    type View<'tmp>
+        = T
+    where
+        'src: 'tmp;
+
+    #[inline]
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        __number(
+            self,
+            input,
+            start.clone(),
+            |bytes| self.scan(bytes),
+            |lit| self.convert(lit).ok_or(ErrorKind::Overflow),
+        )
+        .raise_or_map(|(val, end)| {
+            input.release_slice(start..end.clone());
+            (val, end)
+        })
+    }
+
+    #[inline]
+    fn __check<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        __number(
+            self,
+            input,
+            start,
+            |bytes| self.scan(bytes),
+            |lit| self.convert(lit).ok_or(ErrorKind::Overflow),
+        )
+        .raise_or_map(|(_, end)| end)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub struct Float<T> {
+    signed: bool,
+    separator: Option<u8>,
+    inf_nan: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T: PrimFloat> Float<T> {
+    /// Rejects the leading `+` or `-`.
+    pub const fn unsigned(mut self) -> Self {
+        self.signed = false;
+        self
+    }
+
+    /// Allows `sep` (usually `_`) to appear between two digits.
+    pub const fn separator(mut self, sep: u8) -> Self {
+        self.separator = Some(sep);
+        self
+    }
+
+    /// Also accepts `inf`, `infinity` and `nan`, case-insensitively.
+    pub const fn inf_nan(mut self) -> Self {
+        self.inf_nan = true;
+        self
+    }
+
+    fn scan(&self, bytes: &[u8]) -> (usize, bool) {
+        let mut scanner = Scanner::new(bytes, self.separator);
+        if self.signed {
+            scanner.sign(true);
+        }
+
+        if self.inf_nan {
+            // `inf` is a prefix of `infinity`.
+            let maybe_infinity = match scanner.word("infinity") {
+                Some(true) => return (scanner.pos, false),
+                Some(false) => true,
+                None => false,
+            };
+            for word in ["inf", "nan"] {
+                match scanner.word(word) {
+                    Some(true) => return (scanner.pos, maybe_infinity),
+                    Some(false) => return (0, true),
+                    None => (),
+                }
+            }
+        }
+
+        if scanner.digits(10) == 0 {
+            return (0, scanner.hit_end);
+        }
+
+        let mut len = scanner.pos;
+        if scanner.byte(|b| b == b'.') && scanner.digits(10) > 0 {
+            len = scanner.pos;
+        }
+
+        scanner.pos = len;
+        if scanner.byte(|b| b == b'e' || b == b'E') {
+            scanner.byte(|b| b == b'+' || b == b'-');
+            if scanner.digits(10) > 0 {
+                len = scanner.pos;
+            }
+        }
+
+        (len, scanner.hit_end)
+    }
+
+    fn convert(&self, lit: &[u8]) -> Result<T, ErrorKind<'static>> {
+        let val = match self.separator {
+            #[cfg(feature = "alloc")]
+            Some(sep) if lit.len() > FLOAT_BUFFER_LEN && lit.contains(&sep) => T::from_ascii(
+                &lit.iter()
+                    .copied()
+                    .filter(|b| *b != sep)
+                    .collect::<alloc::vec::Vec<_>>(),
+            ),
+            Some(sep) if lit.contains(&sep) => {
+                let mut buf = [0u8; FLOAT_BUFFER_LEN];
+                let mut len = 0;
+                for &b in lit.iter().filter(|b| **b != sep) {
+                    *buf.get_mut(len).ok_or(ErrorKind::Other(&TooLong))? = b;
+                    len += 1;
+                }
+                T::from_ascii(&buf[..len])
+            }
+            _ => T::from_ascii(lit),
+        }
+        .ok_or(ErrorKind::InvalidInput)?;
+
+        // Only `inf` and `infinity` contain the letter `i`.
+        match !val.is_infinite() || lit.iter().any(|b| b.eq_ignore_ascii_case(&b'i')) {
+            true => Ok(val),
+            false => Err(ErrorKind::Overflow),
+        }
+    }
+}
+
+impl<T> Describe for Float<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a floating point number")
+    }
+}
+
+impl<'src, I, Ext, T> Pattern<'src, I, Ext> for Float<T>
+where
+    I: InputByteSlice<'src>,
+    Ext: Extra<'src, I>,
+    T: PrimFloat,
+{
+    type View<'tmp>
+        = T
+    where
+        'src: 'tmp;
+
+    #[inline]
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        __number(
+            self,
+            input,
+            start.clone(),
+            |bytes| self.scan(bytes),
+            |lit| self.convert(lit),
+        )
+        .raise_or_map(|(val, end)| {
+            input.release_slice(start..end.clone());
+            (val, end)
+        })
+    }
+
+    #[inline]
+    fn __check<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        __number(self, input, start, |bytes| self.scan(bytes), |lit| self.convert(lit)).raise_or_map(|(_, end)| end)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[inline]
+fn __number<'src, I, E, T, S, C>(
+    desc: &dyn Describe,
+    input: &mut I,
+    start: I::Cursor,
+    scan: S,
+    convert: C,
+) -> PResult<(T, I::Cursor), E>
+where
+    I: InputByteSlice<'src>,
+    E: Error,
+    S: Fn(&[u8]) -> (usize, bool),
+    C: Fn(&[u8]) -> Result<T, ErrorKind<'static>>,
+{
+    let mut more = false;
+    loop {
+        let (bytes, eof) = match more {
+            false => trip!(input.fetch_byte_slice(start.clone())),
+            true => trip!(input.fetch_more_byte_slice(start.clone())),
+        };
+        let (len, hit_end) = scan(bytes);
+
+        if hit_end && !eof {
+            more = true;
+            continue;
+        }
+
+        let end = I::bump_cursor(start.clone(), len);
+
+        if len == 0 {
+            return PResult::raise(E::new(I::span(start..end), ErrorKind::Expected(desc)));
+        }
+
+        return match convert(&bytes[..len]) {
+            Ok(val) => PResult::emit((val, end)),
+            Err(kind) => PResult::raise(E::new(I::span(start..end), kind)),
+        };
+    }
+}
+
+/// Size of the buffer for removing separators from float literals without `alloc`.
+pub const FLOAT_BUFFER_LEN: usize = 128;
+
+/// Raised without `alloc`, when a float literal with separators does not fit into [`FLOAT_BUFFER_LEN`] bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooLong;
+
+impl fmt::Display for TooLong {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "literal with separators too long to convert without `alloc`")
+    }
+}
+
+impl core::error::Error for TooLong {}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    separator: Option<u8>,
+    pos: usize,
+    hit_end: bool,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(bytes: &'a [u8], separator: Option<u8>) -> Self {
+        Self {
+            bytes,
+            separator,
+            pos: 0,
+            hit_end: false,
+        }
+    }
+
+    fn peek(&mut self, off: usize) -> Option<u8> {
+        let b = self.bytes.get(self.pos + off).copied();
+        self.hit_end |= b.is_none();
+        b
+    }
+
+    fn byte(&mut self, f: impl Fn(u8) -> bool) -> bool {
+        let ok = self.peek(0).is_some_and(f);
+        self.pos += ok as usize;
+        ok
+    }
+
+    fn sign(&mut self, minus: bool) -> bool {
+        self.byte(|b| b == b'+' || minus && b == b'-')
+    }
+
+    /// Returns the number of digits, separators are allowed only between two digits.
+    fn digits(&mut self, radix: u32) -> usize {
+        let is_digit = |b: u8| (b as char).is_digit(radix);
+        let mut count = 0;
+        loop {
+            if self.byte(is_digit) {
+                count += 1;
+            } else if count > 0
+                && self.separator.is_some_and(|sep| self.peek(0) == Some(sep))
+                && self.peek(1).is_some_and(is_digit)
+            {
+                self.pos += 2;
+                count += 1;
+            } else {
+                break count;
+            }
+        }
+    }
+
+    /// `Some(true)` if matched, `Some(false)` if matched partially before the end of bytes.
+    fn word(&mut self, word: &str) -> Option<bool> {
+        let rest = &self.bytes[self.pos..];
+        let len = rest.len().min(word.len());
+        if !rest[..len].eq_ignore_ascii_case(&word.as_bytes()[..len]) {
+            None
+        } else if len == word.len() {
+            self.pos += len;
+            Some(true)
+        } else {
+            Some(false)
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub trait PrimInt: Copy {
+    const SIGNED: bool;
+    const BITS: u32;
+    const ZERO: Self;
+
+    /// `self * radix + digit`, or `self * radix - digit` if `negative`.
+    fn push_digit(self, radix: u32, digit: u32, negative: bool) -> Option<Self>;
+
+    /// Truncates to the lowest `Self::BITS` bits.
+    fn from_bits(bits: u128) -> Self;
+}
+
+pub trait PrimFloat: Copy {
+    fn from_ascii(lit: &[u8]) -> Option<Self>;
+
+    fn is_infinite(self) -> bool;
+}
+
+macro_rules! impl_prim_int {
+    ( $($signed:literal: $($ty:ty),+ ;)+ ) => { $($(
+        impl PrimInt for $ty {
+            const SIGNED: bool = $signed;
+            const BITS: u32 = <$ty>::BITS;
+            const ZERO: Self = 0;
+
+            #[inline]
+            fn push_digit(self, radix: u32, digit: u32, negative: bool) -> Option<Self> {
+                let acc = self.checked_mul(radix as Self)?;
+                match negative {
+                    true => acc.checked_sub(digit as Self),
+                    false => acc.checked_add(digit as Self),
+                }
+            }
+
+            #[inline]
+            fn from_bits(bits: u128) -> Self {
+                bits as Self
+            }
+        }
+    )+)+ };
+}
+
+impl_prim_int! {
+    true: i8, i16, i32, i64, i128, isize;
+    false: u8, u16, u32, u64, u128, usize;
+}
+
+macro_rules! impl_prim_float {
+    ( $($ty:ty),+ ) => { $(
+        impl PrimFloat for $ty {
+            #[inline]
+            fn from_ascii(lit: &[u8]) -> Option<Self> {
+                core::str::from_utf8(lit).ok()?.parse().ok()
+            }
+
+            #[inline]
+            fn is_infinite(self) -> bool {
+                <$ty>::is_infinite(self)
+            }
+        }
+    )+ };
+}
+
+impl_prim_float!(f32, f64);
@@ -1,4 +1,5 @@
-use crate::{common::*, error::EmptyErr, extra::*, input::*, private};
+use crate::{combinator, common::*, error::EmptyErr, extra::*, input::*, private};
+use core::marker::PhantomData;
 
 pub trait Parser<'src, I, O, Ext = EmptyErr>
 where
@@ -53,6 +54,32 @@ where
         )
     }
 
+    fn check(&self, input: &mut I, start: I::Cursor) -> PResult<I::Cursor, Ext::Error>
+    where
+        Ext::State: Default,
+        Ext::Context: Default,
+    {
+        self.check_with_state(input, start, &mut Ext::State::default())
+    }
+
+    fn check_with_state(
+        &self,
+        input: &mut I,
+        start: I::Cursor,
+        state: &mut Ext::State,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        Ext::Context: Default,
+    {
+        self.__check(
+            input,
+            start,
+            state.into(),
+            Ext::Context::default().into(),
+            private::Token,
+        )
+    }
+
     fn fullmatch(&self, input: I) -> PResult<O, Ext::Error>
     where
         Ext::State: Default,
@@ -105,4 +132,18 @@ where
     }
 
     //------------------------------------------------------------------------------
+
+    /// Feeds the output to `f`, and continues with the parser it returns.
+    fn then_with<F, P, O2>(self, f: F) -> impl Parser<'src, I, O2, Ext>
+    where
+        Self: Sized,
+        F: Fn(O) -> P,
+        P: Parser<'src, I, O2, Ext>,
+    {
+        combinator::ThenWith {
+            parser: self,
+            f,
+            phantom: PhantomData,
+        }
+    }
 }
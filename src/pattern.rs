@@ -1,4 +1,4 @@
-use crate::{common::*, converter, extra::*, input::*, parser::*, private};
+use crate::{combinator, common::*, converter, extra::*, input::*, parser::*, private};
 use core::marker::PhantomData;
 
 pub trait Pattern<'src, I, Ext>
@@ -44,6 +44,23 @@ where
         converter::Captured { pattern: self }
     }
 
+    /// Runs `inner` over the slice matched by the pattern, which shall be consumed entirely.
+    ///
+    /// The inner parser sees the input truncated at the end of the slice, and starts where the pattern started,
+    /// so that spans of inner errors point into the original input.
+    fn and_parse_inner<P, O>(self, inner: P) -> impl Parser<'src, I, O, Ext>
+    where
+        Self: Sized,
+        I: InputTruncatable<'src>,
+        P: Parser<'src, I, O, Ext>,
+    {
+        combinator::AndParseInner {
+            pattern: self,
+            inner,
+            phantom: PhantomData,
+        }
+    }
+
     fn lift<F, O>(self, mapper: F) -> impl Parser<'src, I, O, Ext>
     where
         Self: Sized,
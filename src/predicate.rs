@@ -12,6 +12,29 @@ pub struct Just<Token: PartialEq + Debug>(pub Token);
 
 pub struct Except<Pred>(Pred);
 
+/// Matches tokens by their enum variants, usually constructed by [`kind!`](crate::kind).
+pub struct Kind<F> {
+    name: &'static str,
+    pred: F,
+}
+
+impl<F> Kind<F> {
+    pub const fn new<Token>(name: &'static str, pred: F) -> Self
+    where
+        F: Fn(&Token) -> bool,
+    {
+        Self { name, pred }
+    }
+}
+
+/// Matches tokens against patterns, ignoring their payloads, such as `kind!(Tok::Ident(_) | Tok::Keyword(_))`.
+#[macro_export]
+macro_rules! kind {
+    ( $($pat:pat_param)|+ ) => {
+        $crate::predicate::Kind::new(::core::stringify!($($pat)|+), |token| ::core::matches!(token, $($pat)|+))
+    };
+}
+
 pub fn except<Token, Pred>(pred: Pred) -> impl Predicate<Token>
 where
     Pred: Predicate<Token>,
@@ -96,6 +119,15 @@ impl<Token, Pred: Predicate<Token>> Predicate<Token> for Except<Pred> {
     }
 }
 
+impl<Token, F: Fn(&Token) -> bool> Predicate<Token> for Kind<F> {
+    fn predicate(&self, item: &Token) -> bool {
+        (self.pred)(item)
+    }
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
 impl<Token, F: Fn(&Token) -> bool> Predicate<Token> for F {
     fn predicate(&self, item: &Token) -> bool {
         self(item)
@@ -123,6 +155,95 @@ impl<Token: PartialOrd + Debug> Predicate<Token> for RangeInclusive<Token> {
     }
 }
 
+/// Set of bytes, tested by a single lookup, which can be built in `const` items.
+///
+/// As a predicate on `char`s, code points up to U+00FF are taken as bytes.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ByteSet {
+    bits: [u128; 2],
+}
+
+impl ByteSet {
+    pub const EMPTY: Self = Self { bits: [0; 2] };
+
+    pub const fn new(bytes: &[u8]) -> Self {
+        let mut set = Self::EMPTY;
+        let mut i = 0;
+        while i < bytes.len() {
+            set = set.with(bytes[i]);
+            i += 1;
+        }
+        set
+    }
+
+    pub const fn range(lo: u8, hi: u8) -> Self {
+        let mut set = Self::EMPTY;
+        let mut b = lo;
+        while b <= hi {
+            set = set.with(b);
+            if b == u8::MAX {
+                break;
+            }
+            b += 1;
+        }
+        set
+    }
+
+    pub const fn with(mut self, byte: u8) -> Self {
+        self.bits[(byte >> 7) as usize] |= 1 << (byte & 0x7f);
+        self
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self {
+            bits: [self.bits[0] | other.bits[0], self.bits[1] | other.bits[1]],
+        }
+    }
+
+    pub const fn without(self, other: Self) -> Self {
+        Self {
+            bits: [self.bits[0] & !other.bits[0], self.bits[1] & !other.bits[1]],
+        }
+    }
+
+    #[inline]
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.bits[(byte >> 7) as usize] & (1 << (byte & 0x7f)) != 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=u8::MAX).filter(|&b| self.contains(b))
+    }
+}
+
+impl Debug for ByteSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Predicate::<u8>::describe(self, f)
+    }
+}
+
+impl Predicate<u8> for ByteSet {
+    fn predicate(&self, item: &u8) -> bool {
+        self.contains(*item)
+    }
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+        for b in self.iter() {
+            write!(f, "{}", b.escape_ascii())?;
+        }
+        f.write_str("]")
+    }
+}
+
+impl Predicate<char> for ByteSet {
+    fn predicate(&self, item: &char) -> bool {
+        u8::try_from(*item).is_ok_and(|b| self.contains(b))
+    }
+    fn describe(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Predicate::<u8>::describe(self, f)
+    }
+}
+
 macro_rules! impl_predicate_for_primitives {
     ( $($ty:ty),+ $(,)? ) => { $(
         impl Predicate<$ty> for $ty {
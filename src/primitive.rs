@@ -54,15 +54,15 @@ where
     {
         drop((state, ctx));
         let mut end = start.clone();
-        PResult::from(input.next_maybe_ref::<Ext::Error>(&mut end))
-            .flatten()
-            .raise_or_and_then(|token| match token.verify_by(&self[0]) {
+        PResult::from(input.next_maybe_ref::<Ext::Error>(&mut end)).raise_or_and_then(|token| {
+            match token.is_some_and(|token| token.verify_by(&self[0])) {
                 true => Ok(end),
                 false => Err(Ext::Error::new(
                     I::span(start..end),
                     ErrorKind::Expected(&Single(&self[0], PhantomData)),
                 )),
-            })
+            }
+        })
     }
 }
 
@@ -111,15 +111,15 @@ where
     {
         drop((state, ctx));
         let mut end = start.clone();
-        PResult::from(input.next_maybe_ref::<Ext::Error>(&mut end))
-            .flatten()
-            .raise_or_and_then(|token| match token.verify_by(&self.0) {
+        PResult::from(input.next_maybe_ref::<Ext::Error>(&mut end)).raise_or_and_then(|token| {
+            match token.is_some_and(|token| token.verify_by(&self.0)) {
                 true => Ok(end),
                 false => Err(Ext::Error::new(
                     I::span(start..end),
                     ErrorKind::Expected(&Single(&self.0, PhantomData)),
                 )),
-            })
+            }
+        })
     }
 }
 
@@ -204,8 +204,13 @@ where
 {
     let mut times = 0;
     let mut offset = 0;
+    let mut more = false;
     let end = loop {
-        let (slice, eof) = trip!(input.fetch_slice(start.clone()));
+        let (slice, eof) = match more {
+            false => trip!(input.fetch_slice(start.clone())),
+            true => trip!(input.fetch_more_slice(start.clone())),
+        };
+        more = true;
 
         if I::Slice::ITEM_HAS_FIXED_LENGTH_1 && slice.len() < range.lower_bound() {
             match eof {
@@ -225,9 +230,11 @@ where
             offset += off + I::Slice::len_of(item.as_ref());
         }
 
+        // A token failing the predicate decides the match without fetching more.
+        let decided = offset < slice.len();
         let end = I::bump_cursor(start.clone(), offset);
 
-        if !eof && range.unfulfilled(times) {
+        if !eof && !decided && range.unfulfilled(times) {
             continue;
         } else if range.contains(times) {
             return PResult::emit(end);
@@ -284,7 +291,7 @@ where
             let mut out = MaybeUninit::<[I::Token; N]>::uninit();
             for (i, item) in input.iter_owned(start.clone()..end.clone()).enumerate() {
                 unsafe { (&raw mut (*out.as_mut_ptr())[i]).write(item) }
-                len = i;
+                len = i + 1;
             }
 
             if len != N {
@@ -358,7 +365,7 @@ where
             let mut out = MaybeUninit::<[&'tmp I::Token; N]>::uninit();
             for (i, item) in input.iter_borrowed(start.clone()..end.clone()).enumerate() {
                 unsafe { (&raw mut (*out.as_mut_ptr())[i]).write(item) }
-                len = i;
+                len = i + 1;
             }
 
             if len != N {
@@ -386,3 +393,96 @@ where
         __check_take(self, &self.pred, &N, input, start)
     }
 }
+
+//------------------------------------------------------------------------------
+
+/// Extracts a borrowed payload from a single token, usually constructed by [`payload!`](crate::payload).
+pub struct Payload<Token, F> {
+    name: &'static str,
+    extract: F,
+    phantom: PhantomData<Token>,
+}
+
+impl<Token, F, P> Payload<Token, F>
+where
+    F: for<'all> Fn(&'all Token) -> Option<&'all P>,
+    P: ?Sized,
+{
+    pub const fn new(name: &'static str, extract: F) -> Self {
+        Self {
+            name,
+            extract,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Matches a single token against a pattern, and yields a reference to what it binds,
+/// such as `payload!(Tok::Ident(name) => name.as_str())`.
+#[macro_export]
+macro_rules! payload {
+    ( $pat:pat => $expr:expr ) => {
+        $crate::primitive::Payload::new(::core::stringify!($pat), |token| match token {
+            $pat => ::core::option::Option::Some($expr),
+            #[allow(unreachable_patterns)]
+            _ => ::core::option::Option::None,
+        })
+    };
+}
+
+impl<Token, F> Describe for Payload<Token, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a single token matches {}", self.name)
+    }
+}
+
+impl<'src, I, Ext, F, P> Pattern<'src, I, Ext> for Payload<I::Token, F>
+where
+    I: InputBorrowableToken<'src>,
+    Ext: Extra<'src, I>,
+    F: for<'all> Fn(&'all I::Token) -> Option<&'all P>,
+    P: ?Sized + 'src,
+{
+    type View<'tmp>
+        = &'tmp P
+    where
+        'src: 'tmp;
+
+    #[inline]
+    fn __parse<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        _state: MaybeMut<Ext::State>,
+        _ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<(Self::View<'tmp>, I::Cursor), Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        let payload = input
+            .get_borrowed(start.clone())
+            .and_then(|token| (self.extract)(token));
+        let mut end = start.clone();
+        trip!(input.next_maybe_ref::<Ext::Error>(&mut end));
+        match payload {
+            Some(payload) => PResult::emit((payload, end)),
+            None => PResult::raise(Ext::Error::new(I::span(start..end), ErrorKind::Expected(self))),
+        }
+    }
+
+    #[inline]
+    fn __check<'tmp>(
+        &self,
+        input: &'tmp mut I,
+        start: I::Cursor,
+        state: MaybeMut<Ext::State>,
+        ctx: MaybeRef<Ext::Context>,
+        _: private::Token,
+    ) -> PResult<I::Cursor, Ext::Error>
+    where
+        'src: 'tmp,
+    {
+        Pattern::<I, Ext>::__parse(self, input, start, state, ctx, private::Token).raise_or_map(|(_, end)| end)
+    }
+}
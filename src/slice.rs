@@ -1,6 +1,9 @@
 use crate::common::*;
 use core::ops::Range;
 
+pub mod latin1;
+pub mod utf16;
+
 pub trait Slice<'src>: 'src {
     type Item: 'src;
     type ItemMaybe<'tmp>: RefVal<'tmp, Self::Item>
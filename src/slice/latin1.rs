@@ -0,0 +1,93 @@
+use crate::slice::*;
+use core::{fmt, ops::Range};
+
+/// ISO-8859-1 text, where each byte is exactly the code point of a character.
+#[derive(PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Latin1([u8]);
+
+impl Latin1 {
+    pub const fn new(bytes: &[u8]) -> &Self {
+        // SAFETY: `repr(transparent)`, and every byte sequence is valid.
+        unsafe { &*(bytes as *const [u8] as *const Self) }
+    }
+
+    pub const fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Code units are bytes, so byte-oriented patterns work too.
+impl AsRef<[u8]> for Latin1 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Latin1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"")?;
+        for ch in self.iter() {
+            write!(f, "{}", ch.escape_debug())?;
+        }
+        write!(f, "\"")
+    }
+}
+
+impl<'src> Slice<'src> for Latin1 {
+    type Item = char;
+    type ItemMaybe<'tmp>
+        = char
+    where
+        'src: 'tmp;
+
+    const ITEM_HAS_FIXED_LENGTH_1: bool = true;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+    #[inline]
+    fn len_of(item: &Self::Item) -> usize {
+        #![allow(unused_variables)]
+        1
+    }
+
+    #[inline]
+    fn subslice(&self, range: Range<usize>) -> &Self {
+        Self::new(&self.0[range])
+    }
+    #[inline]
+    fn split_at(&self, mid: usize) -> (&Self, &Self) {
+        let (a, b) = self.0.split_at(mid);
+        (Self::new(a), Self::new(b))
+    }
+
+    #[inline]
+    fn iter<'tmp>(&'tmp self) -> impl Iterator<Item = Self::ItemMaybe<'tmp>>
+    where
+        'src: 'tmp,
+    {
+        self.0.iter().map(|&b| b as char)
+    }
+    #[inline]
+    fn iter_indices<'tmp>(&'tmp self) -> impl Iterator<Item = (usize, Self::ItemMaybe<'tmp>)>
+    where
+        'src: 'tmp,
+    {
+        self.iter().enumerate()
+    }
+
+    #[inline]
+    fn is_item_boundary(&self, idx: usize) -> bool {
+        idx <= self.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'src> BoxableSlice<'src> for Latin1 {
+    fn to_boxed(&self) -> Box<Self> {
+        // SAFETY: `repr(transparent)`.
+        unsafe { Box::from_raw(Box::into_raw(Box::<[u8]>::from(&self.0)) as *mut Self) }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::slice::*;
+use core::{char, fmt, ops::Range};
+
+macro_rules! gen_utf16_slices {
+    ( $( $(#[$attr:meta])* $Name:ident => $from:ident; )* ) => { $(
+        $(#[$attr])*
+        ///
+        /// Lengths and offsets are measured in code units, lone surrogates are decoded as U+FFFD REPLACEMENT CHARACTER.
+        #[derive(PartialEq, Eq, Hash)]
+        #[repr(transparent)]
+        pub struct $Name([u16]);
+
+        impl $Name {
+            pub const fn new(units: &[u16]) -> &Self {
+                // SAFETY: `repr(transparent)`, and lone surrogates are tolerated.
+                unsafe { &*(units as *const [u16] as *const Self) }
+            }
+
+            /// Reinterprets the bytes in place, fails if they are misaligned or of odd length.
+            pub fn from_bytes(bytes: &[u8]) -> Option<&Self> {
+                if bytes.as_ptr().align_offset(align_of::<u16>()) != 0 || bytes.len() % 2 != 0 {
+                    return None;
+                }
+                // SAFETY: The pointer is aligned and the length is even, checked above,
+                // and any bit pattern is a valid `u16`.
+                let units = unsafe { core::slice::from_raw_parts(bytes.as_ptr().cast::<u16>(), bytes.len() / 2) };
+                Some(Self::new(units))
+            }
+
+            /// Code units as they are stored, i.e. not converted to the native byte order.
+            pub const fn as_units(&self) -> &[u16] {
+                &self.0
+            }
+
+            #[inline]
+            fn unit(&self, idx: usize) -> Option<u16> {
+                self.0.get(idx).map(|&u| u16::$from(u))
+            }
+
+            #[inline]
+            fn chars(&self) -> impl Iterator<Item = char> + '_ {
+                char::decode_utf16(self.0.iter().map(|&u| u16::$from(u)))
+                    .map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+            }
+        }
+
+        impl fmt::Debug for $Name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "\"")?;
+                for ch in self.chars() {
+                    write!(f, "{}", ch.escape_debug())?;
+                }
+                write!(f, "\"")
+            }
+        }
+
+        impl<'src> Slice<'src> for $Name {
+            type Item = char;
+            type ItemMaybe<'tmp>
+                = char
+            where
+                'src: 'tmp;
+
+            const ITEM_HAS_FIXED_LENGTH_1: bool = false;
+
+            #[inline]
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+            #[inline]
+            fn len_of(item: &Self::Item) -> usize {
+                item.len_utf16()
+            }
+
+            #[inline]
+            fn subslice(&self, range: Range<usize>) -> &Self {
+                Self::new(&self.0[range])
+            }
+            #[inline]
+            fn split_at(&self, mid: usize) -> (&Self, &Self) {
+                let (a, b) = self.0.split_at(mid);
+                (Self::new(a), Self::new(b))
+            }
+
+            #[inline]
+            fn iter<'tmp>(&'tmp self) -> impl Iterator<Item = Self::ItemMaybe<'tmp>>
+            where
+                'src: 'tmp,
+            {
+                self.chars()
+            }
+            #[inline]
+            fn iter_indices<'tmp>(&'tmp self) -> impl Iterator<Item = (usize, Self::ItemMaybe<'tmp>)>
+            where
+                'src: 'tmp,
+            {
+                self.chars().scan(0, |idx, ch| {
+                    let item = (*idx, ch);
+                    *idx += ch.len_utf16();
+                    Some(item)
+                })
+            }
+
+            /// Rejects the offsets between a high surrogate and its paired low surrogate.
+            #[inline]
+            fn is_item_boundary(&self, idx: usize) -> bool {
+                let is_low = |u: u16| (0xdc00..0xe000).contains(&u);
+                let is_high = |u: u16| (0xd800..0xdc00).contains(&u);
+                match idx.checked_sub(1).and_then(|prev| self.unit(prev)) {
+                    _ if idx > self.len() => false,
+                    Some(prev) => !(is_high(prev) && self.unit(idx).is_some_and(is_low)),
+                    None => true,
+                }
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl<'src> BoxableSlice<'src> for $Name {
+            fn to_boxed(&self) -> Box<Self> {
+                // SAFETY: `repr(transparent)`.
+                unsafe { Box::from_raw(Box::into_raw(Box::<[u16]>::from(&self.0)) as *mut Self) }
+            }
+        }
+    )* };
+}
+
+gen_utf16_slices! {
+    /// UTF-16 text in little-endian code units.
+    Utf16Le => from_le;
+    /// UTF-16 text in big-endian code units.
+    Utf16Be => from_be;
+}
@@ -0,0 +1,187 @@
+use core::{fmt, ops::Range};
+
+#[cfg(feature = "alloc")]
+use crate::{common::PResult, extra::Extra, location::*, parser::Parser};
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, vec::Vec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceId(pub u32);
+
+impl fmt::Display for SourceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Local byte range in a known source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceSpan {
+    pub source: SourceId,
+    pub range: Range<usize>,
+}
+
+impl SourceSpan {
+    pub fn new(source: SourceId, range: Range<usize>) -> Self {
+        Self { source, range }
+    }
+}
+
+/// An error raised in a known source, whose spans are local to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceError<E> {
+    pub source: SourceId,
+    pub error: E,
+}
+
+impl AsRef<SourceId> for SourceId {
+    fn as_ref(&self) -> &SourceId {
+        self
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+struct SourceFile<'src> {
+    name: Cow<'src, str>,
+    text: Cow<'src, str>,
+}
+
+/// Registered sources, each kept on its own and told apart by [`SourceId`]s.
+///
+/// Each source is parsed by itself through [`input`](Self::input) or [`fullmatch`](Self::fullmatch),
+/// so the plain `Range<usize>` spans carried by errors are local to it, and grammars are written against
+/// plain `&str` as usual. The id is attached to the errors by [`fullmatch`](Self::fullmatch),
+/// or is put into the context for [`ProvideExtra::source_span`](crate::extra::ProvideExtra::source_span).
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap<'src> {
+    files: Vec<SourceFile<'src>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'src> SourceMap<'src> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Borrowed text is not copied.
+    pub fn add(&mut self, name: impl Into<Cow<'src, str>>, text: impl Into<Cow<'src, str>>) -> SourceId {
+        let id = SourceId(self.files.len().try_into().expect("too many sources"));
+        self.files.push(SourceFile {
+            name: name.into(),
+            text: text.into(),
+        });
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = SourceId> + use<'src> {
+        (0..self.files.len() as u32).map(SourceId)
+    }
+
+    fn file(&self, id: SourceId) -> &SourceFile<'src> {
+        &self.files[id.0 as usize]
+    }
+
+    pub fn name(&self, id: SourceId) -> &str {
+        &self.file(id).name
+    }
+
+    pub fn text(&self, id: SourceId) -> &str {
+        &self.file(id).text
+    }
+
+    /// Exactly the text of the source, to be parsed from its beginning.
+    pub fn input(&self, id: SourceId) -> &str {
+        self.text(id)
+    }
+
+    /// Parses the whole source, which shall be consumed entirely, and tags the error with its id.
+    pub fn fullmatch<'a, P, O, Ext>(&'a self, id: SourceId, parser: &P) -> PResult<O, SourceError<Ext::Error>>
+    where
+        P: Parser<'a, &'a str, O, Ext>,
+        Ext: Extra<'a, &'a str>,
+        Ext::State: Default,
+        Ext::Context: Default,
+    {
+        parser
+            .fullmatch(self.input(id))
+            .map_err(|error| SourceError { source: id, error })
+    }
+
+    pub fn locate(&self, span: &SourceSpan, unit: ColumnUnit) -> Option<Span> {
+        line_col_span(self.text(span.source), span.range.clone(), unit)
+    }
+
+    /// Renders the message with the source name, location and the offending line underlined.
+    pub fn report<'a, M: fmt::Display>(&'a self, span: &SourceSpan, message: M) -> Report<'a, M> {
+        Report {
+            map: self,
+            span: span.clone(),
+            message,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'src> AsRef<SourceMap<'src>> for SourceMap<'src> {
+    fn as_ref(&self) -> &SourceMap<'src> {
+        self
+    }
+}
+
+/// See [`SourceMap::report`].
+#[cfg(feature = "alloc")]
+pub struct Report<'a, M> {
+    map: &'a SourceMap<'a>,
+    span: SourceSpan,
+    message: M,
+}
+
+#[cfg(feature = "alloc")]
+impl<M: fmt::Display> fmt::Display for Report<'_, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.map.name(self.span.source);
+        let text = self.map.text(self.span.source);
+        writeln!(f, "error: {}", self.message)?;
+
+        let Some(span) = self.map.locate(&self.span, ColumnUnit::Chars) else {
+            return writeln!(f, " --> {}", name);
+        };
+
+        let line = span.start.line.get();
+        let line_start = text[..self.span.range.start].rfind('\n').map_or(0, |off| off + 1);
+        let line_text = text[line_start..].split('\n').next().unwrap_or_default();
+        let line_text = line_text.strip_suffix('\r').unwrap_or(line_text);
+
+        // Underline until the end of the first line, at least one column.
+        let width = match span.end.line == span.start.line {
+            true => span.end.column.get() - span.start.column.get(),
+            false => line_text.chars().count() + 1 - span.start.column.get(),
+        };
+
+        let gutter = line.ilog10() as usize + 1;
+        writeln!(f, "{:gutter$}--> {}:{}", "", name, span.start)?;
+        writeln!(f, "{:gutter$} |", "")?;
+        writeln!(f, "{} | {}", line, line_text)?;
+        writeln!(
+            f,
+            "{:gutter$} | {:pad$}{:^<width$}",
+            "",
+            "",
+            "",
+            pad = span.start.column.get() - 1,
+            width = width.max(1),
+        )
+    }
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use common::*;
+use kaparser::{binary::*, input::partial::PartialInput, parser::*, pattern::*};
+
+fn full<'a, P, O>(pattern: P, bytes: &'a [u8]) -> Result<O, Failed>
+where
+    P: Pattern<'a, &'a [u8], Ext, View<'a> = O>,
+{
+    pattern.captured().fullmatch(bytes).into_result()
+}
+
+fn parse_at<'a, P, O>(pattern: P, bytes: &'a [u8], start: usize) -> Result<(O, usize), Failed>
+where
+    P: Pattern<'a, &'a [u8], Ext, View<'a> = O>,
+{
+    let mut input = bytes;
+    pattern.captured().parse(&mut input, start).into_result()
+}
+
+/// Decodes what has been received so far, `None` if incomplete.
+fn partial<'a, P, O>(pattern: P, bytes: &'a [u8]) -> Option<Result<(O, usize), Failed>>
+where
+    P: Pattern<'a, PartialInput<'a>, Ext, View<'a> = O>,
+{
+    let mut input = PartialInput::new(bytes);
+    let res = pattern.captured().parse(&mut input, 0).into_result();
+    (!input.is_incomplete()).then_some(res)
+}
+
+#[test]
+fn endianness() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    assert_eq!(full(be_u32(), &bytes).unwrap(), 0x1234_5678);
+    assert_eq!(full(le_u32(), &bytes).unwrap(), 0x7856_3412);
+    assert_eq!(full(ne_u32(), &bytes).unwrap(), u32::from_ne_bytes(bytes));
+    assert_eq!(full(be_i32(), &[0xff, 0xff, 0xff, 0xfe]).unwrap(), -2);
+    assert_eq!(full(le_i16(), &[0x00, 0x80]).unwrap(), i16::MIN);
+
+    assert_eq!(full(le_u8(), &[0xff]).unwrap(), 0xff);
+    assert_eq!(full(be_i8(), &[0xff]).unwrap(), -1);
+    assert_eq!(full(le_u128(), &u128::MAX.to_le_bytes()).unwrap(), u128::MAX);
+    assert_eq!(full(be_i64(), &(-5i64).to_be_bytes()).unwrap(), -5);
+
+    assert_eq!(full(be_f64(), &1.5f64.to_be_bytes()).unwrap(), 1.5);
+    assert_eq!(full(le_f32(), &(-0.25f32).to_le_bytes()).unwrap(), -0.25);
+    assert!(full(ne_f64(), &f64::NAN.to_ne_bytes()).unwrap().is_nan());
+}
+
+#[test]
+fn records() {
+    // A record of `u8 tag, be u16 length, le u32 value`.
+    let bytes = [0x07, 0x00, 0x04, 0xef, 0xbe, 0xad, 0xde, 0xff];
+    let (tag, cur) = parse_at(le_u8(), &bytes, 0).unwrap();
+    let (len, cur) = parse_at(be_u16(), &bytes, cur).unwrap();
+    let (value, cur) = parse_at(le_u32(), &bytes, cur).unwrap();
+    assert_eq!((tag, len, value, cur), (7, 4, 0xdead_beef, 7));
+
+    let err = parse_at(be_u16(), &bytes, cur).unwrap_err();
+    assert_eq!((err.0, &*err.1), (7..8, "expected 2 tokens matches ANY"));
+
+    let err = full(le_u16(), &[1, 2, 3]).unwrap_err();
+    assert_eq!((err.0, &*err.1), (2..2, "expected end of input"));
+}
+
+#[test]
+fn streaming() {
+    let bytes = 0x0102_0304_0506_0708u64.to_be_bytes();
+    for received in 0..bytes.len() {
+        assert!(partial(be_u64(), &bytes[..received]).is_none(), "{received}");
+    }
+    let (value, end) = partial(be_u64(), &bytes).unwrap().unwrap();
+    assert_eq!((value, end), (0x0102_0304_0506_0708, 8));
+}
+
+#[test]
+fn leb128() {
+    assert_eq!(full(uleb128::<u32>(), &[0xe5, 0x8e, 0x26]).unwrap(), 624_485);
+    assert_eq!(full(uleb128::<u8>(), &[0x7f]).unwrap(), 127);
+    assert_eq!(
+        full(
+            uleb128::<u64>(),
+            &[0xff; 9].iter().chain(&[0x01]).copied().collect::<Vec<_>>()
+        )
+        .unwrap(),
+        u64::MAX
+    );
+    assert_eq!(full(sleb128::<i32>(), &[0xc0, 0xbb, 0x78]).unwrap(), -123_456);
+    assert_eq!(full(sleb128::<i8>(), &[0x7f]).unwrap(), -1);
+    assert_eq!(full(sleb128::<i8>(), &[0x3f]).unwrap(), 63);
+    assert_eq!(full(sleb128::<i16>(), &[0xc0, 0x00]).unwrap(), 64);
+    assert_eq!(full(sleb128::<i8>(), &[0x80, 0x7f]).unwrap(), i8::MIN);
+
+    // Padded encodings are accepted unless canonical.
+    assert_eq!(full(uleb128::<u32>(), &[0x81, 0x80, 0x00]).unwrap(), 1);
+    assert_eq!(full(sleb128::<i32>(), &[0xff, 0x7f]).unwrap(), -1);
+
+    for (pattern, bytes, span, message) in [
+        (
+            uleb128::<u8>(),
+            &[0x80, 0x02][..],
+            0..2,
+            "number too large to fit in target type",
+        ),
+        (uleb128::<u8>(), &[0x80, 0x80, 0x00], 0..3, "overlong encoding"),
+        (uleb128::<u8>().canonical(), &[0x81, 0x00], 0..2, "overlong encoding"),
+        (uleb128::<u8>(), &[0x80], 0..1, "expected an unsigned LEB128 integer"),
+        (uleb128::<u8>(), &[], 0..0, "expected an unsigned LEB128 integer"),
+    ] {
+        let err = full(pattern, bytes).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{bytes:x?}");
+    }
+
+    for (bytes, span, message) in [
+        (&[0x80, 0x01][..], 0..2, "number too large to fit in target type"),
+        (&[0xff, 0x7e], 0..2, "number too large to fit in target type"),
+        (&[0xff, 0x7f], 0..2, "overlong encoding"),
+    ] {
+        let err = full(sleb128::<i8>().canonical(), bytes).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{bytes:x?}");
+    }
+}
+
+#[test]
+fn protobuf() {
+    assert_eq!(full(varint::<u64>(), &[0x96, 0x01]).unwrap(), 150);
+    assert_eq!(full(varint::<u32>(), &[0x00]).unwrap(), 0);
+
+    // Negative `int32` values are sign extended to ten bytes.
+    let minus_one = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    assert_eq!(full(varint::<i32>(), &minus_one).unwrap(), -1);
+    assert_eq!(full(varint::<i64>(), &minus_one).unwrap(), -1);
+    assert_eq!(full(varint::<u64>(), &[0xff; 10]).unwrap_err().0, 0..10);
+
+    for (bytes, value) in [
+        (&[0x00][..], 0),
+        (&[0x01], -1),
+        (&[0x02], 1),
+        (&[0x03], -2),
+        (&[0xfe, 0xff, 0xff, 0xff, 0x0f], i32::MAX),
+        (&[0xff, 0xff, 0xff, 0xff, 0x0f], i32::MIN),
+    ] {
+        assert_eq!(full(varint_zigzag::<i32>(), bytes).unwrap(), value, "{bytes:x?}");
+    }
+}
+
+#[test]
+fn quic() {
+    // Examples from RFC 9000, Appendix A.1.
+    let bytes = [0xc2, 0x19, 0x7c, 0x5e, 0xff, 0x14, 0xe8, 0x8c];
+    assert_eq!(full(quic_varint(), &bytes).unwrap(), 151_288_809_941_952_652);
+    assert_eq!(full(quic_varint(), &[0x9d, 0x7f, 0x3e, 0x7d]).unwrap(), 494_878_333);
+    assert_eq!(full(quic_varint(), &[0x7b, 0xbd]).unwrap(), 15_293);
+    assert_eq!(full(quic_varint(), &[0x25]).unwrap(), 37);
+    assert_eq!(full(quic_varint(), &[0x40, 0x25]).unwrap(), 37);
+
+    let err = full(quic_varint().canonical(), &[0x40, 0x25]).unwrap_err();
+    assert_eq!((err.0, &*err.1), (0..2, "overlong encoding"));
+    let err = full(quic_varint(), &[0x80, 0x00, 0x01]).unwrap_err();
+    assert_eq!((err.0, &*err.1), (0..3, "expected a QUIC varint"));
+
+    for received in 0..bytes.len() {
+        assert!(partial(quic_varint(), &bytes[..received]).is_none(), "{received}");
+    }
+    assert_eq!(partial(quic_varint(), &bytes).unwrap().unwrap().1, 8);
+    assert!(partial(uleb128::<u32>(), &[0xe5, 0x8e]).is_none());
+    assert_eq!(
+        partial(uleb128::<u32>(), &[0xe5, 0x8e, 0x26, 0xff]).unwrap().unwrap(),
+        (624_485, 3)
+    );
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::*;
+use kaparser::{
+    input::{Input, bits::*},
+    parser::*,
+    pattern::*,
+    predicate::*,
+};
+
+fn parse_at<'a, P, O>(pattern: P, input: BitInput<'a>, start: (usize, u8)) -> Result<(O, (usize, u8)), Failed>
+where
+    P: Pattern<'a, BitInput<'a>, Ext, View<'a> = O>,
+{
+    let mut input = input;
+    pattern.captured().parse(&mut input, start).into_result()
+}
+
+#[test]
+fn msb_first() {
+    let input = BitInput::msb_first(&[0b1010_0110, 0b1111_0001]);
+    let (a, cur) = parse_at(bits::<u8>(3), input, (0, 0)).unwrap();
+    let (b, cur) = parse_at(bool_bit(), input, cur).unwrap();
+    let (c, cur) = parse_at(bits::<i8>(4), input, cur).unwrap();
+    let (d, cur) = parse_at(bits::<i8>(4), input, cur).unwrap();
+    assert_eq!((a, b, c, d, cur), (0b101, false, 6, -1, (1, 4)));
+    assert_eq!(BitInput::offset(cur), 12);
+
+    // Fields straddling a byte boundary are big-endian.
+    assert_eq!(
+        parse_at(bits::<u16>(10), input, (0, 3)).unwrap(),
+        (0b00_1101_1110, (1, 5))
+    );
+    assert_eq!(parse_at(bits::<u16>(16), input, (0, 0)).unwrap().0, 0xa6f1);
+}
+
+#[test]
+fn lsb_first() {
+    // A DEFLATE block header, `BFINAL = 1` and `BTYPE = 01`, followed by a 5-bit code.
+    let input = BitInput::lsb_first(&[0b1011_0011]);
+    let (last, cur) = parse_at(bool_bit(), input, (0, 0)).unwrap();
+    let (kind, cur) = parse_at(bits::<u8>(2), input, cur).unwrap();
+    let (code, cur) = parse_at(bits::<u8>(5), input, cur).unwrap();
+    assert_eq!((last, kind, code, cur), (true, 1, 0b10110, (1, 0)));
+
+    let input = BitInput::lsb_first(&[0x34, 0x12]);
+    assert_eq!(parse_at(bits::<u16>(16), input, (0, 0)).unwrap().0, 0x1234);
+    assert_eq!(parse_at(bits::<i16>(12), input, (0, 4)).unwrap().0, 0x123);
+    assert_eq!(parse_at(bits::<i16>(4), input, (0, 4)).unwrap().0, 3);
+}
+
+#[test]
+fn alignment() {
+    let input = BitInput::msb_first(&[0xff, 0x80]);
+    assert_eq!(parse_at(align_to_byte(), input, (0, 0)).unwrap(), ((), (0, 0)));
+    assert_eq!(parse_at(align_to_byte(), input, (0, 3)).unwrap(), ((), (1, 0)));
+    assert_eq!(parse_at(align_to_byte(), input, (1, 7)).unwrap(), ((), (2, 0)));
+
+    let (flag, cur) = parse_at(bool_bit(), input, (0, 5)).unwrap();
+    let (_, cur) = parse_at(align_to_byte(), input, cur).unwrap();
+    assert_eq!(parse_at(bool_bit(), input, cur).unwrap(), (true, (1, 1)));
+    assert!(flag);
+}
+
+#[test]
+fn single_bits() {
+    let input = BitInput::msb_first(&[0b0100_0000]);
+    assert_eq!(parse_at([Just(false)], input, (0, 0)).unwrap(), (false, (0, 1)));
+    assert_eq!(parse_at([Just(true)], input, (0, 1)).unwrap(), (true, (0, 2)));
+    assert_eq!(parse_at([ANY], input, (0, 7)).unwrap(), (false, (1, 0)));
+
+    let err = parse_at([Just(true)], input, (0, 0)).unwrap_err();
+    assert_eq!(err.0, 0..1);
+}
+
+#[test]
+fn errors() {
+    let input = BitInput::msb_first(&[0xab]);
+    let err = parse_at(bits::<u16>(12), input, (0, 2)).unwrap_err();
+    assert_eq!((err.0, &*err.1), (2..8, "expected 12 bits"));
+    let err = parse_at(bool_bit(), input, (1, 0)).unwrap_err();
+    assert_eq!((err.0, &*err.1), (8..8, "expected 1 bit"));
+
+    // The end of input is at the last byte boundary.
+    let err = Parser::<_, _, Ext>::fullmatch(&bits::<u8>(4).captured(), input)
+        .into_result()
+        .unwrap_err();
+    assert_eq!((err.0, &*err.1), (4..4, "expected end of input"));
+    assert_eq!(
+        Parser::<_, _, Ext>::fullmatch(&bits::<u8>(8).captured(), input)
+            .into_result()
+            .unwrap(),
+        0xab
+    );
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::*;
+use kaparser::{binary::*, combinator::*, common::PResult, number::*, parser::*, pattern::*, predicate::*};
+
+fn parse<'a, P, O>(parser: &P, bytes: &'a [u8]) -> PResult<(O, usize), Failed>
+where
+    P: Parser<'a, &'a [u8], O, Ext>,
+{
+    let mut input = bytes;
+    parser.parse(&mut input, 0)
+}
+
+#[test]
+fn then_with() {
+    // A shift applied to the value after it.
+    let value = le_u8()
+        .captured()
+        .then_with(|shift: u8| be_u16().lift(move |v| u32::from(v) << shift));
+    assert_eq!(
+        parse(&value, &[4, 0x01, 0x02, 0xff]).into_result().unwrap(),
+        (0x1020, 3)
+    );
+
+    let err = parse(&value, &[]).into_result().unwrap_err();
+    assert_eq!((err.0, &*err.1), (0..0, "expected 1 tokens matches ANY"));
+    let err = parse(&value, &[1, 0]).into_result().unwrap_err();
+    assert_eq!((err.0, &*err.1), (1..2, "expected 2 tokens matches ANY"));
+}
+
+#[test]
+fn length_prefixed_bodies() {
+    let record = length_prefixed(le_u8().lift(usize::from), be_u16().captured());
+    assert_eq!(
+        parse(&record, &[2, 0x12, 0x34, 0xff]).into_result().unwrap(),
+        (0x1234, 3)
+    );
+
+    // The body sees the end of input at the declared length.
+    let err = parse(&record, &[1, 0x12, 0x34]).into_result().unwrap_err();
+    assert_eq!((err.0, &*err.1), (1..2, "expected 2 tokens matches ANY"));
+
+    // The declared length runs past the end of input.
+    let res = parse(&record, &[4, 0x12, 0x34]);
+    assert!(!res.has_output());
+    let err = res.into_error().unwrap();
+    assert_eq!((err.0, &*err.1), (1..5, "expected 4 more tokens"));
+
+    // Nested records.
+    let outer = length_prefixed(
+        le_u8().lift(usize::from),
+        length_prefixed(le_u8().lift(usize::from), le_u8().captured()),
+    );
+    assert_eq!(parse(&outer, &[2, 1, 9, 0]).into_result().unwrap(), (9, 3));
+}
+
+#[test]
+fn recovered_errors() {
+    // Leftover bytes in the body are reported, but the output and the end of the record are kept.
+    let record = length_prefixed(le_u8().lift(usize::from), le_u8().captured());
+    let res = parse(&record, &[3, 7, 8, 9, 4]);
+    assert_eq!(res.output(), Some(&(7, 4)));
+    assert_eq!(res.error().map(|e| e.0.clone()), Some(2..2));
+
+    // Parsing goes on after a recovered error, which is kept along.
+    let pair = record.then_with(|first| le_u8().lift(move |second| (first, second)));
+    let res = parse(&pair, &[3, 7, 8, 9, 4]);
+    assert_eq!(res.output(), Some(&((7, 4), 5)));
+    assert_eq!(res.error().map(|e| e.0.clone()), Some(2..2));
+
+    let res = parse(&pair, &[3, 7, 8, 9]);
+    assert!(!res.has_output());
+    assert_eq!(res.error().map(|e| e.0.clone()), Some(2..2));
+}
+
+#[test]
+fn backtracking() {
+    // Failures of the length, of the truncation and of the body all let `alt` try the next one.
+    let either = alt((
+        length_prefixed(be_u16().lift(usize::from), le_u32().captured()),
+        le_u8()
+            .captured()
+            .then_with(|n: u8| le_u16().lift(move |v| u32::from(v) + u32::from(n))),
+    ));
+    assert_eq!(parse(&either, &[0, 4, 1, 0, 0, 0]).into_result().unwrap(), (1, 6));
+    assert_eq!(parse(&either, &[0]).into_result().unwrap_err().0, 0..1);
+    assert_eq!(parse(&either, &[9, 1, 0]).into_result().unwrap(), (10, 3));
+    assert_eq!(parse(&either, &[0, 3, 1, 0, 0, 0]).into_result().unwrap(), (0x0103, 3));
+}
+
+#[test]
+fn inner_parsers() {
+    // A grammar written against plain `&str` parses the matched word.
+    let word = || ('0'..='9', 'a'..='z').take1more();
+    let number = word().and_parse_inner(int::<u32>(10).captured());
+    let parse_str = |text: &'static str| {
+        let mut input = text;
+        Parser::<_, _, Ext>::parse(&number, &mut input, 2).into_result()
+    };
+    assert_eq!(parse_str("x=124;").unwrap(), (124, 5));
+
+    // It sees the end of input at the end of the word, and its errors point into the whole text.
+    let err = parse_str("x=12a4;").unwrap_err();
+    assert_eq!((err.0, &*err.1), (4..4, "expected end of input"));
+    let err = parse_str("x=ab;").unwrap_err();
+    assert_eq!((err.0, &*err.1), (2..2, "expected a decimal integer"));
+    let err = parse_str("x=;").unwrap_err();
+    assert_eq!(err.0, 2..2);
+
+    let digits = word().and_parse_inner(('0'..='9').take1more().lift(|_| ()));
+    let mut input = "12 34";
+    let res = Parser::<_, _, Ext>::check(&digits, &mut input, 3).into_result();
+    assert_eq!(res.unwrap(), 5);
+}
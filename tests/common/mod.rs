@@ -0,0 +1,28 @@
+//! Shared by the integration tests.
+
+#![allow(dead_code)]
+
+use kaparser::{common::Describe, error::*, extra::Full};
+use std::ops::Range;
+
+/// Keeps the span and the description of errors, for comparisons.
+#[derive(Debug)]
+pub struct Failed(pub Range<usize>, pub String);
+
+impl Error for Failed {
+    type Label = ();
+
+    fn new(span: Range<usize>, kind: ErrorKind) -> Self {
+        Failed(span, format!("{}", &kind as &dyn Describe))
+    }
+
+    fn merge(self, _: Self) -> Self {
+        self
+    }
+
+    fn label(self, _: ()) -> Self {
+        self
+    }
+}
+
+pub type Ext = Full<Failed, (), ()>;
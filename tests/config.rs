@@ -0,0 +1,140 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+use kaparser::{grammars::config::*, parser::*};
+use std::{borrow::Cow, ops::Range};
+
+fn parse(config: Config, text: &'static str) -> Document<'static> {
+    Parser::<&str, Document, Ext>::fullmatch(&config, text)
+        .into_result()
+        .unwrap()
+}
+
+fn fails(config: Config, cases: &[(&'static str, Range<usize>, &str)]) {
+    for (text, span, message) in cases {
+        let err = Parser::<&str, Document, Ext>::fullmatch(&config, *text)
+            .into_result()
+            .unwrap_err();
+        assert_eq!((err.0, &*err.1), (span.clone(), *message), "{text}");
+    }
+}
+
+#[test]
+fn ini_sections() {
+    let text = "top = 1\n; comment\n[ server ]  # trailing\r\nhost=example.com ; inline\nport: 80\n\n[client]\nhost = \"quoted ; not a comment\"\n[server]\nport = 8080\n";
+    let doc = parse(ini(), text);
+    assert_eq!(doc.get(None, "top"), Some("1"));
+    assert_eq!(doc.get(Some("server"), "host"), Some("example.com"));
+    assert_eq!(doc.get(Some("server"), "port"), Some("8080"));
+    assert_eq!(doc.get(Some("client"), "host"), Some("quoted ; not a comment"));
+    assert_eq!(doc.get(Some("client"), "port"), None);
+    assert_eq!(doc.sections().collect::<Vec<_>>(), ["server", "client"]);
+
+    let entry = doc.entry(Some("server"), "host").unwrap();
+    assert_eq!(&text[entry.key_span.clone()], "host");
+    assert_eq!(&text[entry.value_span.clone()], "example.com");
+    let entry = doc.entry(Some("client"), "host").unwrap();
+    assert_eq!(&text[entry.value_span.clone()], "\"quoted ; not a comment\"");
+
+    fails(
+        ini(),
+        &[
+            ("[server\nhost = a\n", 7..7, "expected `]`"),
+            ("[server] x\n", 9..9, "expected a line break"),
+            ("key\n", 3..3, "expected `=` or `:`"),
+            ("key = \"open\nx\n", 6..7, "expected closing quote on the same line"),
+            ("key = 'a' b\n", 10..10, "expected a line break"),
+            ("key = \"a\\qb\"\n", 8..10, "invalid input"),
+        ],
+    );
+}
+
+#[test]
+fn ini_values() {
+    let text = "a = one \\\n    two \\\n three\nb = \"tab\\tquote\\\" ${x}\"\nc = 'raw \\t ${y}'\nd = \\${e} ${f:-g}\n";
+    let doc = parse(ini(), text);
+    let a = doc.entry(None, "a").unwrap();
+    assert!(matches!(&a.value, Cow::Owned(v) if v == "one two three"));
+    assert_eq!(&text[a.value_span.clone()], "one \\\n    two \\\n three");
+    assert_eq!(doc.get(None, "b"), Some("tab\tquote\" ${x}"));
+    assert_eq!(doc.get(None, "c"), Some("raw \\t ${y}"));
+    assert!(matches!(doc.entry(None, "c").unwrap().value, Cow::Borrowed(_)));
+
+    let names = |key| {
+        let entry = doc.entry(None, key).unwrap();
+        entry
+            .interpolations
+            .iter()
+            .map(|i| (i.name, &text[i.span.clone()]))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names("b"), [("x", "${x}")]);
+    assert_eq!(names("c"), []);
+    assert_eq!(names("d"), [("f:-g", "${f:-g}")]);
+
+    fails(ini(), &[("a = ${x\n", 7..7, "expected `}` of the interpolation")]);
+}
+
+#[test]
+fn properties_continuations() {
+    let text = "# comment\n! also\nfruits = apple, banana, \\\n    pear\\\r\n  , plum\nkey\\ with\\:seps:value\nempty\nspaced    value here\nuni=\\u00e9t\\u00E9\\n\nodd = a\\\\\nnext = b\n";
+    let doc = parse(properties(), text);
+    assert_eq!(doc.get(None, "fruits"), Some("apple, banana, pear, plum"));
+    assert_eq!(doc.get(None, "key with:seps"), Some("value"));
+    assert_eq!(doc.get(None, "empty"), Some(""));
+    assert_eq!(doc.get(None, "spaced"), Some("value here"));
+    assert_eq!(doc.get(None, "uni"), Some("été\n"));
+    // An even number of backslashes does not continue.
+    assert_eq!(doc.get(None, "odd"), Some("a\\"));
+    assert_eq!(doc.get(None, "next"), Some("b"));
+    assert_eq!(doc.sections().count(), 0);
+
+    let entry = doc.entry(None, "fruits").unwrap();
+    assert_eq!(
+        &text[entry.value_span.clone()],
+        "apple, banana, \\\n    pear\\\r\n  , plum"
+    );
+    let entry = doc.entry(None, "key with:seps").unwrap();
+    assert_eq!(&text[entry.key_span.clone()], "key\\ with\\:seps");
+
+    fails(
+        properties(),
+        &[(
+            "a = \\u00g1\n",
+            6..8,
+            "expected 4 tokens matches [0123456789ABCDEFabcdef]",
+        )],
+    );
+}
+
+#[test]
+fn dotenv_quoting() {
+    let text = "# comment\nexport A=1\nexport\tB = \"two words\" # comment\nC='single ${NOT}'\nD=\"multi\nline \\\"q\\\" ${A}\"\nE=plain # comment\nexport=kept\nF=\"joined \\\nline\"\n";
+    let doc = parse(dotenv(), text);
+    assert_eq!(doc.get(None, "A"), Some("1"));
+    assert_eq!(doc.get(None, "B"), Some("two words"));
+    assert_eq!(doc.get(None, "C"), Some("single ${NOT}"));
+    assert_eq!(doc.get(None, "D"), Some("multi\nline \"q\" ${A}"));
+    assert_eq!(doc.get(None, "E"), Some("plain"));
+    assert_eq!(doc.get(None, "export"), Some("kept"));
+    assert_eq!(doc.get(None, "F"), Some("joined line"));
+
+    let a = doc.entry(None, "A").unwrap();
+    assert_eq!(&text[a.key_span.clone()], "A");
+    let d = doc.entry(None, "D").unwrap();
+    assert_eq!(&text[d.value_span.clone()], "\"multi\nline \\\"q\\\" ${A}\"");
+    assert_eq!(d.interpolations.iter().map(|i| i.name).collect::<Vec<_>>(), ["A"]);
+    assert!(doc.entry(None, "C").unwrap().interpolations.is_empty());
+
+    fails(
+        dotenv(),
+        &[
+            ("=1\n", 0..0, "expected a variable name"),
+            ("A 1\n", 2..2, "expected `=`"),
+            ("A=\"open\n", 8..8, "expected closing quote"),
+            ("A='x' y\n", 6..6, "expected a line break"),
+        ],
+    );
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+use kaparser::{grammars::csv::*, input::rope::Rope};
+use std::borrow::Cow;
+
+fn read(config: Csv, text: &'static str) -> Vec<Record<'static>> {
+    config.records::<_, Failed>(text).collect::<Result<_, _>>().unwrap()
+}
+
+fn fields(records: &[Record]) -> Vec<Vec<String>> {
+    records
+        .iter()
+        .map(|record| record.iter().map(String::from).collect())
+        .collect()
+}
+
+#[test]
+fn quoted_fields() {
+    let records = read(csv(), "a,\"b,c\",\"\"\n\"x\",y,z\n");
+    assert_eq!(fields(&records), [["a", "b,c", ""], ["x", "y", "z"]]);
+
+    // Quoted fields without escapes are borrowed as well.
+    assert!(matches!(records[0].fields()[1], Cow::Borrowed("b,c")));
+    assert_eq!((records[0].line(), records[0].span()), (1, 0..10));
+    assert_eq!((records[1].line(), records[1].span()), (2, 11..18));
+
+    // Quotes are literal when quoting is disabled.
+    let records = read(csv().quote(None), "\"a,b\"\n");
+    assert_eq!(fields(&records), [["\"a", "b\""]]);
+}
+
+#[test]
+fn escapes() {
+    let records = read(csv(), "\"say \"\"hi\"\"\",\"\"\"\"\n");
+    assert_eq!(fields(&records), [["say \"hi\"", "\""]]);
+    assert!(matches!(records[0].fields()[0], Cow::Owned(_)));
+
+    let records = read(csv().escape(Some(b'\\')), "\"a\\\"b\",\"c\\\\d\",\"e\"\"f\"\n");
+    assert_eq!(fields(&records), [["a\"b", "c\\d", "e\"f"]]);
+}
+
+#[test]
+fn line_breaks() {
+    let text = "a,\"multi\r\nline\"\r\n\r\nb,c\r\n\n\"\n\",d";
+    let records = read(csv(), text);
+    assert_eq!(fields(&records), [["a", "multi\r\nline"], ["b", "c"], ["\n", "d"]]);
+    assert_eq!(records.iter().map(Record::line).collect::<Vec<_>>(), [1, 4, 6]);
+    assert_eq!(records[0].span(), 0..15);
+    assert_eq!(&text[records[2].span()], "\"\n\",d");
+
+    // A lone `\r` is part of the field.
+    assert_eq!(fields(&read(csv(), "a\rb\n")), [["a\rb"]]);
+}
+
+#[test]
+fn ragged_rows() {
+    let text = "h1,h2,h3\n1\n1,2,3,4\n,\n";
+    let mut records = csv().has_headers(true).records::<_, Failed>(text);
+    let headers = records.headers().unwrap().unwrap();
+    assert_eq!(headers.position("h3"), Some(2));
+    assert_eq!(headers.position("h4"), None);
+
+    let records = records.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(records.iter().map(Record::len).collect::<Vec<_>>(), [1, 4, 2]);
+    assert_eq!(records[0].get(1), None);
+    assert_eq!(&records[1][3], "4");
+    assert_eq!(fields(&records[2..]), [["", ""]]);
+}
+
+#[test]
+fn tsv_and_owned() {
+    let chunks: &'static [&'static str] = &["name\tq", "ty\n\"a\tb", "\"\t1\n", "c\t", "2"];
+    let records = tsv()
+        .has_headers(true)
+        .records_owned::<_, Failed>(Rope::new(chunks))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(fields(&records), [["a\tb", "1"], ["c", "2"]]);
+    assert!(records[0].fields().iter().all(|field| matches!(field, Cow::Owned(_))));
+    assert_eq!(records[1].span(), 17..20);
+}
+
+#[test]
+fn errors() {
+    for (text, span, message) in [
+        ("a\n\"b\nc", 2..3, "expected closing `\"` of the field quoted at line 2"),
+        (
+            "\"a\"b,c\n",
+            3..3,
+            "expected a delimiter or a line break after the quoted field",
+        ),
+    ] {
+        let mut records = csv().records::<_, Failed>(text);
+        let err = records.find_map(Result::err).unwrap();
+        assert_eq!((err.0, &*err.1), (span, message), "{text}");
+        assert!(records.next().is_none());
+    }
+
+    let mut records = csv().records::<_, Failed>(&b"a,\xff\n"[..]);
+    let err = records.next().unwrap().unwrap_err();
+    assert_eq!((err.0, &*err.1), (2..3, "invalid input"));
+}
@@ -0,0 +1,235 @@
+mod common;
+
+use common::*;
+use kaparser::{grammars::datetime::*, parser::*, pattern::*};
+use std::ops::Range;
+
+fn full<'a, P, O>(pattern: P, text: &'a str) -> Result<O, Failed>
+where
+    P: Pattern<'a, &'a str, Ext, View<'a> = O>,
+{
+    pattern.captured().fullmatch(text).into_result()
+}
+
+fn fails<'a, P, O>(pattern: P, cases: &[(&'a str, Range<usize>, &str)])
+where
+    P: Pattern<'a, &'a str, Ext, View<'a> = O> + Copy,
+    O: std::fmt::Debug,
+{
+    for (text, span, message) in cases {
+        let err = full(pattern, text).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span.clone(), *message), "{text}");
+    }
+}
+
+#[test]
+fn rfc3339_examples() {
+    // RFC 3339, section 5.8
+    let dt = full(rfc3339(), "1985-04-12T23:20:50.52Z").unwrap();
+    assert_eq!(dt.date, Date::new(1985, 4, 12).unwrap());
+    assert_eq!(dt.time.nanosecond, 520_000_000);
+    assert_eq!(dt.offset, Some(Offset::UTC));
+    assert_eq!(dt.unix_timestamp(), Some(482196050));
+    assert_eq!(dt.to_string(), "1985-04-12T23:20:50.52Z");
+
+    let dt = full(rfc3339(), "1996-12-19T16:39:57-08:00").unwrap();
+    assert_eq!(dt.offset, Some(Offset { minutes: -480 }));
+    assert_eq!(dt.unix_timestamp(), Some(851042397));
+
+    let dt = full(rfc3339(), "1990-12-31T23:59:60Z").unwrap();
+    assert_eq!(dt.time.second, 60);
+    let dt = full(rfc3339(), "1990-12-31T15:59:60-08:00").unwrap();
+    assert_eq!(dt.unix_timestamp(), Some(662688000));
+
+    let dt = full(rfc3339(), "1937-01-01T12:00:27.87+00:20").unwrap();
+    assert_eq!(dt.offset, Some(Offset { minutes: 20 }));
+
+    assert!(full(rfc3339(), "2024-03-01 12:00:00z").is_ok());
+    assert!(full(rfc3339(), "2024-03-01T12:00:00").is_err());
+    let dt = full(rfc3339().local(), "2024-03-01T12:00:00").unwrap();
+    assert_eq!((dt.offset, dt.unix_timestamp()), (None, None));
+
+    fails(
+        rfc3339(),
+        &[
+            ("2024-13-01T00:00:00Z", 5..7, "error: month shall be 01 to 12"),
+            (
+                "2023-02-29T00:00:00Z",
+                8..10,
+                "error: day exceeds the days in the month",
+            ),
+            (
+                "2024-02-30T00:00:00Z",
+                8..10,
+                "error: day exceeds the days in the month",
+            ),
+            ("2024-03-01T24:00:00Z", 11..13, "error: hour shall be 00 to 23"),
+            ("2024-03-01T12:60:00Z", 14..16, "error: minute shall be 00 to 59"),
+            ("2024-03-01T12:00:61Z", 17..19, "error: second shall be 00 to 60"),
+            (
+                "2024-03-01T12:59:60Z",
+                17..19,
+                "error: leap second shall be in the last minute of a UTC day",
+            ),
+            ("2024-03-01T12:00Z", 16..16, "expected `:`"),
+            ("2024-03-01T12:00:00+0800", 22..22, "expected `:`"),
+            (
+                "2024-03-01T12:00:00+24:00",
+                20..22,
+                "error: offset hour shall be 00 to 23",
+            ),
+            ("2024-03-01T12:00:00,5Z", 19..19, "expected `Z` or an offset"),
+            ("24-03-01T12:00:00Z", 2..2, "expected a year of four digits"),
+            ("2024-3-01T12:00:00Z", 6..6, "expected a month of two digits"),
+            ("2024-03-01X12:00:00Z", 10..10, "expected `T`"),
+        ],
+    );
+    assert!(full(date(), "2024-02-29").is_ok());
+    assert!(full(date(), "2000-02-29").is_ok());
+    assert!(full(date(), "1900-02-29").is_err());
+}
+
+#[test]
+fn iso_dates() {
+    let date = full(iso_date(), "2024-W09-5").unwrap();
+    assert_eq!(
+        date,
+        IsoDate::Week(WeekDate {
+            year: 2024,
+            week: 9,
+            weekday: 5
+        })
+    );
+    assert_eq!(date.to_date(), Date::new(2024, 3, 1).unwrap());
+
+    let date = full(iso_date(), "2024-061").unwrap();
+    assert_eq!(date, IsoDate::Ordinal(OrdinalDate { year: 2024, day: 61 }));
+    assert_eq!(date.to_date(), Date::new(2024, 3, 1).unwrap());
+
+    let date = full(iso_date(), "2024-03-01").unwrap();
+    assert_eq!(date.to_date().weekday(), 5);
+    assert_eq!(date.to_string(), "2024-03-01");
+
+    // Week years differ from calendar years around new year.
+    let date = Date::new(2008, 12, 29).unwrap();
+    assert_eq!(
+        date.week_date(),
+        WeekDate {
+            year: 2009,
+            week: 1,
+            weekday: 1
+        }
+    );
+    let date = Date::new(2010, 1, 3).unwrap();
+    assert_eq!(date.week_date().to_string(), "2009-W53-7");
+    assert_eq!(full(week_date(), "2009-W53-7").unwrap().to_date(), date);
+    assert_eq!(
+        full(ordinal_date(), "2024-366").unwrap().to_date(),
+        Date::new(2024, 12, 31).unwrap()
+    );
+
+    fails(
+        week_date(),
+        &[
+            ("2024-W53-1", 6..8, "error: week exceeds the weeks in the year"),
+            ("2024-W00-1", 6..8, "error: week exceeds the weeks in the year"),
+            ("2024-W09-8", 9..10, "error: weekday shall be 1 to 7"),
+            ("2024-09-1", 5..5, "expected `W`"),
+        ],
+    );
+    fails(
+        ordinal_date(),
+        &[
+            ("2023-366", 5..8, "error: day exceeds the days in the year"),
+            ("2023-000", 5..8, "error: day exceeds the days in the year"),
+        ],
+    );
+}
+
+#[test]
+fn times_and_offsets() {
+    let t = full(time(), "23:20").unwrap();
+    assert_eq!((t.hour, t.minute, t.second), (23, 20, 0));
+    let t = full(time(), "23:20:50,123456789999").unwrap();
+    assert_eq!(t.nanosecond, 123_456_789);
+    assert_eq!(t.to_string(), "23:20:50.123456789");
+
+    // The trailing point of a sentence is left alone.
+    let mut input = "at 12:00:00.";
+    let pattern = Pattern::<&str, Ext>::captured(time());
+    let (_, end) = Parser::<&str, Time, Ext>::parse(&pattern, &mut input, 3)
+        .into_result()
+        .unwrap();
+    assert_eq!(end, 11);
+
+    for (text, minutes) in [("Z", 0), ("+08:00", 480), ("+0530", 330), ("-03", -180), ("-00:00", 0)] {
+        assert_eq!(full(offset(), text).unwrap().minutes, minutes, "{text}");
+    }
+    assert_eq!(Offset { minutes: -570 }.to_string(), "-09:30");
+    fails(
+        offset(),
+        &[
+            ("08:00", 0..0, "expected `Z` or an offset"),
+            ("+08:60", 4..6, "error: minute shall be 00 to 59"),
+            ("+8", 2..2, "expected an offset hour of two digits"),
+        ],
+    );
+}
+
+#[test]
+fn durations() {
+    let d = full(duration(), "P1Y2M10DT2H30M").unwrap();
+    assert_eq!((d.years, d.months, d.days, d.hours, d.minutes), (1, 2, 10, 2, 30));
+    assert_eq!(d.to_std(), None);
+    assert_eq!(d.to_string(), "P1Y2M10DT2H30M");
+
+    let d = full(duration(), "P1DT2H").unwrap();
+    assert_eq!(d.to_std(), Some(std::time::Duration::from_secs(93600)));
+
+    let d = full(duration(), "PT0.5S").unwrap();
+    assert_eq!(d.to_std(), Some(std::time::Duration::from_millis(500)));
+    assert_eq!(d.to_string(), "PT0.5S");
+
+    let d = full(duration(), "P2W").unwrap();
+    assert_eq!(d.weeks, 2);
+    assert_eq!(full(duration(), "PT36H").unwrap().hours, 36);
+    assert_eq!(Duration::default().to_string(), "PT0S");
+
+    fails(
+        duration(),
+        &[
+            ("P", 1..1, "expected a duration component"),
+            ("PT", 2..2, "expected an hour, minute or second component"),
+            ("P1D2Y", 4..5, "error: designator out of order or repeated"),
+            ("P1D1D", 4..5, "error: designator out of order or repeated"),
+            ("PT1.5M", 3..5, "error: fraction only allowed for seconds"),
+            ("P1H", 2..2, "expected a designator `Y`, `M`, `W` or `D`"),
+            ("PT1D", 3..3, "expected a designator `H`, `M` or `S`"),
+            ("PT99999999999S", 2..13, "number too large to fit in target type"),
+            ("1D", 0..0, "expected `P`"),
+        ],
+    );
+}
+
+#[test]
+fn bytes() {
+    let pattern = Pattern::<&[u8], Ext>::captured(rfc3339());
+    let dt = Parser::<&[u8], DateTime, Ext>::fullmatch(&pattern, b"2024-03-01T12:00:00+08:00".as_slice());
+    assert_eq!(dt.into_result().unwrap().unix_timestamp(), Some(1709265600));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono() {
+    let dt = full(rfc3339(), "1990-12-31T23:59:60.5Z").unwrap();
+    let converted = dt.to_chrono().unwrap();
+    assert_eq!(converted.timestamp_subsec_nanos(), 1_500_000_000);
+    assert_eq!(converted.timestamp(), 662687999);
+
+    let dt = full(rfc3339(), "1996-12-19T16:39:57-08:00").unwrap();
+    assert_eq!(dt.to_chrono().unwrap().timestamp(), dt.unix_timestamp().unwrap());
+    assert_eq!(
+        full(duration(), "PT1M").unwrap().to_chrono(),
+        Some(chrono::TimeDelta::minutes(1))
+    );
+}
@@ -0,0 +1,214 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+use kaparser::{grammars::http1::*, input::partial::PartialInput, parser::*};
+
+fn parse<'a, P, O>(parser: &P, bytes: &'a [u8]) -> Result<(O, usize), Failed>
+where
+    P: Parser<'a, &'a [u8], O, Ext>,
+{
+    parse_at(parser, bytes, 0)
+}
+
+fn parse_at<'a, P, O>(parser: &P, bytes: &'a [u8], start: usize) -> Result<(O, usize), Failed>
+where
+    P: Parser<'a, &'a [u8], O, Ext>,
+{
+    let mut input = bytes;
+    parser.parse(&mut input, start).into_result()
+}
+
+/// Parses what has been received so far, `None` if incomplete.
+fn partial<'a, P, O>(parser: &P, bytes: &'a [u8], start: usize) -> Option<Result<(O, usize), Failed>>
+where
+    P: Parser<'a, PartialInput<'a>, O, Ext>,
+{
+    let mut input = PartialInput::new(bytes);
+    let res = parser.parse(&mut input, start).into_result();
+    (!input.is_incomplete()).then_some(res)
+}
+
+#[test]
+fn requests() {
+    let text = b"\r\nGET /index.html?q=1 HTTP/1.1\r\nHost: example.com\r\nAccept:  */* \r\nX-Empty:\r\n\r\nbody";
+    let (req, end) = parse(&request(), text).unwrap();
+    assert_eq!(&text[end..], b"body");
+    assert_eq!(req.method, "GET");
+    assert_eq!(req.target, "/index.html?q=1");
+    assert_eq!(req.version, Version::HTTP_11);
+    assert_eq!(req.headers.len(), 3);
+    assert_eq!(req.header("host"), Some(&b"example.com"[..]));
+    assert_eq!(req.header("ACCEPT"), Some(&b"*/*"[..]));
+    assert_eq!(req.header("x-empty"), Some(&b""[..]));
+    assert_eq!(req.headers[0].span, 32..51);
+    assert_eq!(req.framing(), Some(Framing::Empty));
+
+    // Bare LF line endings.
+    let (req, _) = parse(&request(), b"POST * HTTP/1.0\nContent-Length: 5\n\n").unwrap();
+    assert_eq!(req.version.to_string(), "HTTP/1.0");
+    assert_eq!(req.framing(), Some(Framing::Length(5)));
+
+    for (text, span, message) in [
+        (&b"GET  / HTTP/1.1\r\n\r\n"[..], 4..4, "expected a request target"),
+        (
+            b"GET / HTTP/1.1\r\nHost : x\r\n\r\n",
+            20..21,
+            "error: whitespace between field name and colon",
+        ),
+        (b"GET / HTTP/1.1\r\nHost\r\n\r\n", 20..20, "expected `:`"),
+        (b"GET / HTTP/1.1\rHost: x\r\n\r\n", 14..15, "error: bare CR"),
+        (
+            b"GET / HTTP/1.1\r\nA: \x01\r\n\r\n",
+            19..19,
+            "expected a field value character",
+        ),
+        (b"GET / HTTP/2\r\n\r\n", 12..12, "expected `.`"),
+        (b"G(T / HTTP/1.1\r\n\r\n", 1..1, "expected a space"),
+        (
+            b"GET / HTTP/1.1\r\nA: 1\r\n  2\r\n\r\n",
+            20..23,
+            "error: obsolete line folding",
+        ),
+    ] {
+        let err = parse(&request(), text).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{}", text.escape_ascii());
+    }
+}
+
+#[test]
+fn responses() {
+    let text = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nTransfer-Encoding: gzip, chunked\r\n\r\n";
+    let (res, end) = parse(&response(), text).unwrap();
+    assert_eq!(end, text.len());
+    assert_eq!((res.status, res.reason), (200, &b"OK"[..]));
+    assert_eq!(res.headers[0].value_str(), Some("text/plain"));
+    assert_eq!(res.framing(), Some(Framing::Chunked));
+
+    let (res, _) = parse(&response(), b"HTTP/1.1 404\r\n\r\n").unwrap();
+    assert_eq!((res.status, res.reason), (404, &b""[..]));
+    assert_eq!(res.framing(), Some(Framing::UntilClose));
+
+    let (res, _) = parse(&response(), b"HTTP/1.1 304 Not Modified\r\nContent-Length: 10\r\n\r\n").unwrap();
+    assert_eq!(res.framing(), Some(Framing::Empty));
+
+    let err = parse(&response(), b"HTTP/1.1 20 OK\r\n\r\n").unwrap_err();
+    assert_eq!((err.0, &*err.1), (11..11, "expected a status code of three digits"));
+}
+
+#[test]
+fn framing() {
+    let framing = |fields: &'static [u8]| {
+        let text = [b"PUT / HTTP/1.1\r\n", fields, b"\r\n"].concat().leak();
+        parse(&request(), text).unwrap().0.framing()
+    };
+    assert_eq!(framing(b"Content-Length: 42\r\n"), Some(Framing::Length(42)));
+    assert_eq!(framing(b"Content-Length: 42, 42\r\n"), Some(Framing::Length(42)));
+    assert_eq!(framing(b"Content-Length: 42\r\nContent-Length: 7\r\n"), None);
+    assert_eq!(framing(b"Content-Length: +42\r\n"), None);
+    assert_eq!(framing(b"Content-Length: 99999999999999999999\r\n"), None);
+    assert_eq!(
+        framing(b"Transfer-Encoding: chunked\r\nContent-Length: 42\r\n"),
+        Some(Framing::Chunked)
+    );
+    assert_eq!(framing(b"Transfer-Encoding: chunked, gzip\r\n"), None);
+}
+
+#[test]
+fn obs_fold() {
+    let text = b"GET / HTTP/1.1\r\nX-Long: a\r\n\tb\r\nHost: h\r\n\r\n";
+    let (req, _) = parse(&request().allow_obs_fold(), text).unwrap();
+    assert_eq!(req.header("x-long"), Some(&b"a  \tb"[..]));
+    assert_eq!(req.headers[0].span, 16..31);
+    assert_eq!(req.header("host"), Some(&b"h"[..]));
+}
+
+#[test]
+fn limits() {
+    let text = b"GET / HTTP/1.1\r\nA: 1\r\nB: 2\r\nC: 3\r\n\r\n";
+    assert!(parse(&request().max_headers(3), text).is_ok());
+    let err = parse(&request().max_headers(2), text).unwrap_err();
+    assert_eq!((err.0, &*err.1), (28..28, "error: too many header fields"));
+
+    assert!(parse(&request().max_head_len(text.len()), text).is_ok());
+    let err = parse(&request().max_head_len(text.len() - 1), text).unwrap_err();
+    assert_eq!((err.0, &*err.1), (35..35, "error: message head too large"));
+
+    // Checked before asking for more.
+    let endless = [b"GET / HTTP/1.1\r\nA: ".as_slice(), &[b'a'; 100]].concat();
+    let err = partial(&request().max_head_len(64), &endless, 0).unwrap().unwrap_err();
+    assert_eq!((err.0, &*err.1), (64..64, "error: message head too large"));
+}
+
+#[test]
+fn chunked() {
+    let body = b"5;name=value\r\nhello\r\n1A\r\nabcdefghijklmnopqrstuvwxyz\r\n0\r\nExpires: never\r\n\r\nnext";
+    let (first, end) = parse(&chunk(), body).unwrap();
+    assert_eq!(
+        first,
+        Chunk::Data {
+            data: b"hello",
+            ext: b";name=value"
+        }
+    );
+
+    let (second, end) = parse_at(&chunk(), body, end).unwrap();
+    let Chunk::Data { data, .. } = second else { panic!() };
+    assert_eq!(data.len(), 26);
+
+    let (last, end) = parse_at(&chunk(), body, end).unwrap();
+    let Chunk::Last { ext, trailers } = last else { panic!() };
+    assert_eq!(ext, b"");
+    assert_eq!(header(&trailers, "expires"), Some(&b"never"[..]));
+    assert_eq!(trailers[0].span, 56..72);
+    assert_eq!(&body[end..], b"next");
+
+    for (text, span, message) in [
+        (&b"5\r\nhelloX"[..], 8..8, "expected a line ending after the chunk data"),
+        (b"x\r\n", 0..0, "expected a chunk size"),
+        (b"5 x\r\nhello\r\n", 1..1, "expected `;` or a line ending"),
+        (
+            b"10000000000000000\r\n",
+            0..17,
+            "number too large to fit in target type",
+        ),
+        (b"5\r\nhel", 6..6, "expected chunk data"),
+    ] {
+        let err = parse(&chunk(), text).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{}", text.escape_ascii());
+    }
+}
+
+#[test]
+fn incremental() {
+    let text = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n0\r\n\r\n";
+    let head = 47;
+
+    // A socket read loop, receiving one more byte at a time.
+    for received in 0..head {
+        assert!(partial(&response(), &text[..received], 0).is_none(), "{received}");
+    }
+    let (res, end) = partial(&response(), &text[..head], 0).unwrap().unwrap();
+    assert_eq!((res.framing(), end), (Some(Framing::Chunked), head));
+
+    for received in head..head + 9 {
+        assert!(partial(&chunk(), &text[..received], head).is_none(), "{received}");
+    }
+    let (data, end) = partial(&chunk(), &text[..head + 9], head).unwrap().unwrap();
+    assert_eq!(
+        data,
+        Chunk::Data {
+            data: b"Wiki",
+            ext: b""
+        }
+    );
+
+    assert!(partial(&chunk(), &text[..text.len() - 1], end).is_none());
+    let (last, _) = partial(&chunk(), text, end).unwrap().unwrap();
+    assert!(matches!(last, Chunk::Last { trailers, .. } if trailers.is_empty()));
+
+    // Errors are reported without waiting for more.
+    let err = partial(&request(), b"GET /\x00", 0).unwrap().unwrap_err();
+    assert_eq!((err.0, &*err.1), (5..5, "expected a space"));
+}
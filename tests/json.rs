@@ -0,0 +1,174 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+use kaparser::{grammars::json::*, parser::*};
+use std::{borrow::Cow, ops::Range};
+
+fn decode(text: &'static str) -> Result<Value<'static>, Failed> {
+    Parser::<&str, Value, Ext>::fullmatch(&json(), text).into_result()
+}
+
+fn fails(parser: Json, cases: &[(&'static str, Range<usize>, &str)]) {
+    for (text, span, message) in cases {
+        let err = Parser::<&str, Value, Ext>::fullmatch(&parser, *text)
+            .into_result()
+            .unwrap_err();
+        assert_eq!((err.0, &*err.1), (span.clone(), *message), "{text}");
+    }
+}
+
+#[test]
+fn values() {
+    let value = decode(" {\"a\": [1, true, null, \"x\"], \"b\": {}, \"a\": []}\n").unwrap();
+    let members = value.as_object().unwrap();
+    assert_eq!(members.len(), 3);
+    assert_eq!(value.get("a").and_then(Value::as_array), Some(&[][..]));
+    assert_eq!(value.get("b").and_then(Value::as_object), Some(&[][..]));
+    let items = members[0].1.as_array().unwrap();
+    assert_eq!(items[0].as_number().unwrap().as_u64(), Some(1));
+    assert_eq!(items[1].as_bool(), Some(true));
+    assert!(items[2].is_null());
+    assert_eq!(items[3].as_str(), Some("x"));
+
+    assert_eq!(decode("[ ]").unwrap(), Value::Array(vec![]));
+    assert_eq!(decode("\"\"").unwrap(), Value::String(Cow::Borrowed("")));
+}
+
+#[test]
+fn escapes() {
+    // Strings without escapes are borrowed.
+    assert!(matches!(
+        decode("\"plain é\"").unwrap(),
+        Value::String(Cow::Borrowed("plain é"))
+    ));
+    assert!(matches!(
+        decode(r#""a\"b\\c\/d\b\f\n\r\te""#).unwrap(),
+        Value::String(Cow::Owned(s)) if s == "a\"b\\c/d\x08\x0c\n\r\te"
+    ));
+    assert_eq!(decode(r#""é中""#).unwrap().as_str(), Some("é中"));
+    assert_eq!(decode(r#""\u0000""#).unwrap().as_str(), Some("\0"));
+
+    fails(
+        json(),
+        &[
+            (r#""\x""#, 1..3, "invalid input"),
+            (r#""\"#, 2..2, "expected an escape sequence"),
+            ("\"a\tb\"", 2..3, "invalid input"),
+            ("\"ab", 3..3, "expected `\"`"),
+            (r#""\u12""#, 3..5, "expected 4 tokens matches [0123456789ABCDEFabcdef]"),
+        ],
+    );
+}
+
+#[test]
+fn surrogate_pairs() {
+    assert_eq!(decode(r#""😀""#).unwrap().as_str(), Some("😀"));
+    assert_eq!(decode(r#""𝄞!""#).unwrap().as_str(), Some("𝄞!"));
+
+    fails(
+        json(),
+        &[
+            // Lone low surrogate.
+            (r#""\ude00""#, 1..7, "invalid input"),
+            // High surrogate not followed by a low one.
+            (r#""\ud83d""#, 7..7, "expected a low surrogate escape"),
+            (r#""\ud83dx""#, 7..7, "expected a low surrogate escape"),
+            (r#""\ud83d\u0041""#, 7..13, "invalid input"),
+        ],
+    );
+}
+
+#[test]
+fn numbers() {
+    for text in [
+        "0",
+        "-0",
+        "12",
+        "-12",
+        "0.5",
+        "1e10",
+        "1E+2",
+        "-1.25e-3",
+        "123456789012345678901234567890",
+    ] {
+        assert_eq!(decode(text).unwrap().as_number().unwrap().as_str(), text);
+    }
+
+    let n = decode("-1.5e3").unwrap().as_number().unwrap();
+    assert!(!n.is_integer());
+    assert_eq!(n.as_f64(), -1500.0);
+    assert_eq!(n.as_i64(), None);
+    assert_eq!(decode("-42").unwrap().as_number().unwrap().as_i64(), Some(-42));
+    assert_eq!(
+        decode("18446744073709551616").unwrap().as_number().unwrap().as_u64(),
+        None
+    );
+
+    fails(
+        json(),
+        &[
+            ("-", 1..1, "expected a digit"),
+            ("1.", 2..2, "expected a digit"),
+            ("1.e5", 2..2, "expected a digit"),
+            ("1e", 2..2, "expected a digit"),
+            ("1e+", 3..3, "expected a digit"),
+            ("01", 1..1, "expected end of input"),
+            ("+1", 0..0, "expected a JSON value"),
+            (".5", 0..0, "expected a JSON value"),
+            ("[1,]", 3..3, "expected a JSON value"),
+        ],
+    );
+}
+
+#[test]
+fn structure() {
+    fails(
+        json(),
+        &[
+            ("", 0..0, "expected a JSON value"),
+            ("[1 2]", 3..3, "expected `,` or `]`"),
+            ("{\"a\" 1}", 5..5, "expected `:`"),
+            ("{\"a\": 1,}", 8..8, "expected a member name"),
+            ("{1: 2}", 1..1, "expected a member name"),
+            ("{\"a\": 1 \"b\": 2}", 8..8, "expected `,` or `}`"),
+            ("nul", 0..0, "expected a JSON value"),
+            ("[] []", 3..3, "expected end of input"),
+        ],
+    );
+}
+
+#[test]
+fn depth_limit() {
+    let deep = |n: usize| -> &'static str { Box::leak(format!("{}{}", "[".repeat(n), "]".repeat(n)).into_boxed_str()) };
+
+    assert!(decode(deep(DEFAULT_MAX_DEPTH)).is_ok());
+    fails(
+        json(),
+        &[(deep(DEFAULT_MAX_DEPTH + 1), 128..129, "error: nesting too deep")],
+    );
+
+    fails(
+        json().max_depth(2),
+        &[("[{\"a\": [1]}]", 7..8, "error: nesting too deep")],
+    );
+    fails(json().max_depth(0), &[("{}", 0..1, "error: nesting too deep")]);
+    let res = Parser::<&str, Value, Ext>::fullmatch(&json().max_depth(0), "1");
+    assert!(res.into_result().is_ok());
+}
+
+#[test]
+fn bytes() {
+    let value = Parser::<&[u8], Value, Ext>::fullmatch(&json(), "[\"é\", 1]".as_bytes())
+        .into_result()
+        .unwrap();
+    assert_eq!(value.as_array().unwrap()[0].as_str(), Some("é"));
+
+    for (bytes, span) in [(&b"\"a\xffb\""[..], 2..3), (b"\"\xe2\x82\"", 1..3)] {
+        let err = Parser::<&[u8], Value, Ext>::fullmatch(&json(), bytes)
+            .into_result()
+            .unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, "invalid input"), "{}", bytes.escape_ascii());
+    }
+}
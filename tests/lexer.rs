@@ -0,0 +1,134 @@
+mod common;
+
+use common::*;
+use kaparser::{combinator::*, lexer::*, literal::*, number::*, parser::*, pattern::*, predicate::*};
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    AddAssign,
+    Sub,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug, PartialEq)]
+enum Tok {
+    Num(i64),
+    Op(Op),
+    Ident,
+    Error(String, String),
+}
+
+const OPS: OneOfLiterals<'static, Op, 5> = one_of_literals([
+    ("+", Op::Add),
+    ("+=", Op::AddAssign),
+    ("-", Op::Sub),
+    ("(", Op::LParen),
+    (")", Op::RParen),
+]);
+
+fn lex(src: &'static str) -> Vec<(Tok, Range<usize>)> {
+    let token = alt((
+        int::<i64>(10).unsigned().lift(Tok::Num),
+        OPS.lift(Tok::Op),
+        ('a'..='z').take1more().lift(|_| Tok::Ident),
+    ));
+    let comment = ['#'].captured().then_with(|_| except('\n').take0more().lift(|_| ()));
+    let skip = alt(((' ', '\t', '\n').take1more().lift(|_| ()), comment));
+    let lexer = lexer::<str, Ext, _, _, _, _, _>(token, skip, |err: Failed, text: &str| {
+        Tok::Error(err.1, text.to_owned())
+    });
+
+    let mut iter = lexer.iter::<str, Ext, _>(src);
+    let tokens = iter.by_ref().collect();
+    assert_eq!(iter.offset(), src.len());
+    tokens
+}
+
+#[test]
+fn ordered_choice() {
+    // The first succeeded alternative wins, rather than the longest one.
+    let plus = alt((
+        one_of_literals([("+", 1)]).captured(),
+        one_of_literals([("+=", 2)]).captured(),
+    ));
+    let mut input = "+=";
+    let res = Parser::<_, _, Ext>::parse(&plus, &mut input, 0).into_result();
+    assert_eq!(res.unwrap(), (1, 1));
+
+    let number_or_op = alt((int::<i64>(10).lift(Tok::Num), OPS.lift(Tok::Op)));
+    let full = |text| Parser::<_, _, Ext>::fullmatch(&number_or_op, text).into_result();
+    assert_eq!(full("-5").unwrap(), Tok::Num(-5));
+    assert_eq!(full("+=").unwrap(), Tok::Op(Op::AddAssign));
+
+    // Errors of all alternatives are merged, and this one keeps the first.
+    let err = full("?").unwrap_err();
+    assert_eq!((err.0, &*err.1), (0..0, "expected a decimal integer"));
+    let err = full("1+").unwrap_err();
+    assert_eq!((err.0, &*err.1), (1..1, "expected end of input"));
+
+    // Single tokens fail rather than panic at the end of input.
+    let hash = alt((['#'].captured(), ['!'].captured()));
+    let err = Parser::<_, _, Ext>::fullmatch(&hash, "").into_result().unwrap_err();
+    assert_eq!((err.0, &*err.1), (0..0, "expected a single token matches #"));
+}
+
+#[test]
+fn tokens() {
+    assert_eq!(
+        lex("x += 12 - (yz)"),
+        [
+            (Tok::Ident, 0..1),
+            (Tok::Op(Op::AddAssign), 2..4),
+            (Tok::Num(12), 5..7),
+            (Tok::Op(Op::Sub), 8..9),
+            (Tok::Op(Op::LParen), 10..11),
+            (Tok::Ident, 11..13),
+            (Tok::Op(Op::RParen), 13..14),
+        ]
+    );
+
+    // Longest literals, and no trivia needed between tokens.
+    assert_eq!(
+        lex("+=+12ab"),
+        [
+            (Tok::Op(Op::AddAssign), 0..2),
+            (Tok::Op(Op::Add), 2..3),
+            (Tok::Num(12), 3..5),
+            (Tok::Ident, 5..7),
+        ]
+    );
+
+    assert_eq!(
+        lex("1 # one\n\t2 #\n  # two"),
+        [(Tok::Num(1), 0..1), (Tok::Num(2), 9..10)]
+    );
+    assert_eq!(lex(""), []);
+    assert_eq!(lex("  \n"), []);
+}
+
+#[test]
+fn recovery() {
+    // Consecutive unrecognized characters make a single error token.
+    assert_eq!(
+        lex("a $? b"),
+        [
+            (Tok::Ident, 0..1),
+            (Tok::Error("expected a decimal integer".into(), "$?".into()), 2..4),
+            (Tok::Ident, 5..6),
+        ]
+    );
+
+    // An error token stops before trivia and before anything recognized.
+    assert_eq!(
+        lex("€€1€ $"),
+        [
+            (Tok::Error("expected a decimal integer".into(), "€€".into()), 0..6),
+            (Tok::Num(1), 6..7),
+            (Tok::Error("expected a decimal integer".into(), "€".into()), 7..10),
+            (Tok::Error("expected a decimal integer".into(), "$".into()), 11..12),
+        ]
+    );
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::*;
+use kaparser::{combinator::alt, input::partial::PartialInput, literal::*, parser::*, pattern::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Lt,
+    Le,
+    Shl,
+    ShlEq,
+    Eq,
+}
+
+const OPS: OneOfLiterals<'static, Op, 5> = one_of_literals([
+    ("<<=", Op::ShlEq),
+    ("<", Op::Lt),
+    ("=", Op::Eq),
+    ("<<", Op::Shl),
+    ("<=", Op::Le),
+]);
+
+fn parse(text: &'static str) -> Result<(Op, usize), Failed> {
+    let mut input = text;
+    Parser::<&str, Op, Ext>::parse(&OPS.captured(), &mut input, 0).into_result()
+}
+
+/// Decodes what has been received so far, `None` if incomplete.
+fn partial(bytes: &[u8]) -> Option<Result<(Op, usize), Failed>> {
+    let mut input = PartialInput::new(bytes);
+    let res = Parser::<PartialInput, Op, Ext>::parse(&OPS.captured(), &mut input, 0).into_result();
+    (!input.is_incomplete()).then_some(res)
+}
+
+#[test]
+fn longest_match() {
+    assert_eq!(parse("<").unwrap(), (Op::Lt, 1));
+    assert_eq!(parse("<=").unwrap(), (Op::Le, 2));
+    assert_eq!(parse("<<").unwrap(), (Op::Shl, 2));
+    assert_eq!(parse("<<=").unwrap(), (Op::ShlEq, 3));
+    assert_eq!(parse("=").unwrap(), (Op::Eq, 1));
+
+    // Stops at the longest literal, whatever follows.
+    assert_eq!(parse("<<<").unwrap(), (Op::Shl, 2));
+    assert_eq!(parse("<=<").unwrap(), (Op::Le, 2));
+    assert_eq!(parse("<<==").unwrap(), (Op::ShlEq, 3));
+    assert_eq!(parse("<a").unwrap(), (Op::Lt, 1));
+
+    let err = parse(">").unwrap_err();
+    assert_eq!(
+        (err.0, &*err.1),
+        (0..0, r#"expected one of "<", "<<", "<<=", "<=", "=""#)
+    );
+    assert_eq!(parse("").unwrap_err().0, 0..0);
+    assert_eq!(OPS.literals().collect::<Vec<_>>(), ["<", "<<", "<<=", "<=", "="]);
+}
+
+#[test]
+fn shared_prefixes() {
+    const WORDS: OneOfLiterals<'static, u8, 5> =
+        one_of_literals([("in", 0), ("int", 1), ("into", 2), ("interface", 3), ("i", 4)]);
+    let parse = |text: &'static str| {
+        let mut input = text;
+        Parser::<&str, u8, Ext>::parse(&WORDS.captured(), &mut input, 0).into_result()
+    };
+    assert_eq!(parse("i").unwrap(), (4, 1));
+    assert_eq!(parse("in").unwrap(), (0, 2));
+    assert_eq!(parse("inte").unwrap(), (1, 3));
+    assert_eq!(parse("into").unwrap(), (2, 4));
+    assert_eq!(parse("interface").unwrap(), (3, 9));
+    assert_eq!(parse("interfac").unwrap(), (1, 3));
+    assert!(parse("x").is_err());
+
+    const KEYWORDS: OneOfLiterals<'static, u8, 2> = one_of_literals_ignore_ascii_case([("SELECT", 0), ("SET", 1)]);
+    let mut input = "select";
+    assert_eq!(
+        Parser::<&str, u8, Ext>::parse(&KEYWORDS.captured(), &mut input, 0)
+            .into_result()
+            .unwrap(),
+        (0, 6)
+    );
+    let mut input = "Set x";
+    assert_eq!(
+        Parser::<&str, u8, Ext>::parse(&KEYWORDS.captured(), &mut input, 0)
+            .into_result()
+            .unwrap(),
+        (1, 3)
+    );
+}
+
+#[test]
+fn incomplete_input() {
+    // A longer literal may follow, so more bytes are needed.
+    assert!(partial(b"").is_none());
+    assert!(partial(b"<").is_none());
+    assert!(partial(b"<<").is_none());
+
+    // Decided without looking further.
+    assert_eq!(partial(b"<<=").unwrap().unwrap(), (Op::ShlEq, 3));
+    assert_eq!(partial(b"<=").unwrap().unwrap(), (Op::Le, 2));
+    assert_eq!(partial(b"<<x").unwrap().unwrap(), (Op::Shl, 2));
+    assert_eq!(partial(b"=").unwrap().unwrap(), (Op::Eq, 1));
+    assert!(partial(b">").unwrap().is_err());
+}
+
+#[test]
+fn alternatives_over_partial_input() {
+    // A failed branch does not leave the next one taken as asking for more.
+    let method = alt((
+        one_of_literals([("GET", 1)]).captured(),
+        one_of_literals([("POST", 2)]).captured(),
+    ));
+    let mut input = PartialInput::new(&b"POST / HTTP/1.1\r\n"[..]);
+    let res = Parser::<PartialInput, i32, Ext>::parse(&method, &mut input, 0).into_result();
+    assert_eq!(res.unwrap(), (2, 4));
+    assert!(!input.is_incomplete());
+
+    let mut input = PartialInput::new(&b"PO"[..]);
+    assert!(
+        Parser::<PartialInput, i32, Ext>::parse(&method, &mut input, 0)
+            .into_result()
+            .is_err()
+    );
+    assert!(input.is_incomplete());
+}
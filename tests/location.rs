@@ -0,0 +1,120 @@
+use kaparser::location::*;
+use std::num::NonZeroUsize;
+
+fn pos(line: usize, column: usize) -> LineCol {
+    LineCol {
+        line: NonZeroUsize::new(line).unwrap(),
+        column: NonZeroUsize::new(column).unwrap(),
+    }
+}
+
+const TEXT: &str = "fn main() {\r\n    let é = \"😀\";\n\n}";
+
+#[test]
+fn scanning() {
+    assert_eq!(line_col(TEXT, 0, ColumnUnit::Bytes), Some(pos(1, 1)));
+    assert_eq!(line_col(TEXT, 11, ColumnUnit::Bytes), Some(pos(1, 12)));
+    assert_eq!(line_col(TEXT, 13, ColumnUnit::Bytes), Some(pos(2, 1)));
+    assert_eq!(line_col(TEXT, TEXT.len(), ColumnUnit::Chars), Some(pos(4, 2)));
+    assert_eq!(line_col(TEXT, TEXT.len() + 1, ColumnUnit::Bytes), None);
+
+    // Inside `é`.
+    assert_eq!(line_col(TEXT, 22, ColumnUnit::Bytes), None);
+
+    let span = line_col_span(TEXT, 21..23, ColumnUnit::Chars).unwrap();
+    assert_eq!((span.start, span.end), (pos(2, 9), pos(2, 10)));
+    assert_eq!(span.to_string(), "2:9-2:10");
+}
+
+#[test]
+fn columns() {
+    // `😀` is one char, two UTF-16 units and four bytes.
+    let smiley = TEXT.find('😀').unwrap();
+    let after = smiley + 4;
+    for (unit, column) in [
+        (ColumnUnit::Bytes, 19),
+        (ColumnUnit::Chars, 15),
+        (ColumnUnit::Utf16, 16),
+        (ColumnUnit::Graphemes, 15),
+    ] {
+        assert_eq!(line_col(TEXT, after, unit), Some(pos(2, column)), "{unit:?}");
+    }
+
+    // A family emoji is a single grapheme of five chars.
+    let text = "a👨‍👩‍👧b";
+    let b = text.find('b').unwrap();
+    assert_eq!(line_col(text, b, ColumnUnit::Graphemes), Some(pos(1, 3)));
+    assert_eq!(line_col(text, b, ColumnUnit::Chars), Some(pos(1, 7)));
+    assert_eq!(line_col(text, b, ColumnUnit::Utf16), Some(pos(1, 10)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn line_index() {
+    let index = LineIndex::new(TEXT);
+    assert_eq!(index.line_count(), 4);
+    assert_eq!(index.line_of(0), Some(0));
+    assert_eq!(index.line_of(12), Some(0));
+    assert_eq!(index.line_of(13), Some(1));
+    assert_eq!(index.line_of(TEXT.len()), Some(3));
+    assert_eq!(index.line_of(TEXT.len() + 1), None);
+
+    // Line terminators, CRLF included, are not part of lines.
+    assert_eq!(index.line_range(0), Some(0..11));
+    assert_eq!(index.line(0), Some("fn main() {"));
+    assert_eq!(index.line(2), Some(""));
+    assert_eq!(index.line(3), Some("}"));
+    assert_eq!(index.line(4), None);
+
+    for offset in 0..=TEXT.len() {
+        for unit in [
+            ColumnUnit::Bytes,
+            ColumnUnit::Chars,
+            ColumnUnit::Utf16,
+            ColumnUnit::Graphemes,
+        ] {
+            assert_eq!(
+                index.line_col(offset, unit),
+                line_col(TEXT, offset, unit),
+                "{offset} {unit:?}"
+            );
+        }
+    }
+
+    let span = index.span(13..TEXT.len(), ColumnUnit::Utf16).unwrap();
+    assert_eq!(span.to_string(), "2:1-4:2");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn offsets() {
+    let index = LineIndex::new(TEXT);
+    // Every offset but the one of `\r` round-trips.
+    for offset in (0..=TEXT.len()).filter(|&off| off != 12 && TEXT.is_char_boundary(off)) {
+        for unit in [
+            ColumnUnit::Bytes,
+            ColumnUnit::Chars,
+            ColumnUnit::Utf16,
+            ColumnUnit::Graphemes,
+        ] {
+            let pos = index.line_col(offset, unit).unwrap();
+            assert_eq!(index.offset(pos, unit), Some(offset), "{offset} {unit:?}");
+        }
+    }
+
+    // The end of a CRLF line is before the `\r`, and columns past it do not exist.
+    assert_eq!(index.offset(pos(1, 12), ColumnUnit::Bytes), Some(11));
+    assert_eq!(index.offset(pos(1, 13), ColumnUnit::Bytes), None);
+    assert_eq!(index.offset(pos(1, 13), ColumnUnit::Chars), None);
+    assert_eq!(index.offset(pos(3, 1), ColumnUnit::Chars), Some(TEXT.len() - 2));
+    assert_eq!(index.offset(pos(3, 2), ColumnUnit::Chars), None);
+    assert_eq!(index.offset(pos(4, 2), ColumnUnit::Graphemes), Some(TEXT.len()));
+    assert_eq!(index.offset(pos(5, 1), ColumnUnit::Bytes), None);
+
+    // Columns inside a character or between the surrogates of a pair.
+    assert_eq!(index.offset(pos(2, 10), ColumnUnit::Bytes), None);
+    assert_eq!(index.offset(pos(2, 14), ColumnUnit::Utf16), Some(27));
+    assert_eq!(index.offset(pos(2, 15), ColumnUnit::Utf16), None);
+    assert_eq!(index.offset(pos(2, 18), ColumnUnit::Utf16), Some(TEXT.len() - 3));
+    assert_eq!(index.offset(pos(2, 19), ColumnUnit::Utf16), None);
+}
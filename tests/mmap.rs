@@ -0,0 +1,96 @@
+#![cfg(feature = "mmap")]
+
+mod common;
+
+use common::*;
+use kaparser::{
+    input::{Input, mmap::*, utf8::Utf8Input},
+    number::*,
+    parser::*,
+    pattern::*,
+    predicate::*,
+};
+use std::{fs, path::PathBuf};
+
+/// Maps a fresh file, which is not modified until the end of the test.
+///
+/// The mapping is leaked, since `lift` needs `'static` input.
+fn map(name: &str, contents: &[u8]) -> (&'static MmapInput, PathBuf) {
+    let path = std::env::temp_dir().join(format!("kaparser-{}-{name}", std::process::id()));
+    fs::write(&path, contents).unwrap();
+    // SAFETY: The file is private to this test.
+    let input = unsafe { MmapInput::open(&path) }.unwrap();
+    (Box::leak(Box::new(input)), path)
+}
+
+fn parse<P>(parser: P, mut input: Utf8Input<'static>) -> Result<usize, Failed>
+where
+    P: Parser<'static, Utf8Input<'static>, (), Ext>,
+{
+    let start = input.begin();
+    parser.parse(&mut input, start).into_result().map(|((), cur)| cur.pos())
+}
+
+#[test]
+fn contents() {
+    let (input, path) = map("contents", b"42 apples\n");
+    assert_eq!((input.len(), input.is_empty()), (10, false));
+    assert_eq!(&input[..2], b"42");
+    assert_eq!(input.as_ref(), b"42 apples\n");
+    assert_eq!(input.as_str(), Ok("42 apples\n"));
+
+    let text = input.as_str().unwrap();
+    let mut slice = text;
+    let res = Parser::<_, _, Ext>::parse(&int::<u32>(10).captured(), &mut slice, 0);
+    assert_eq!(res.into_result().unwrap(), (42, 2));
+    let word = ('a'..='z').take1more().lift(|_| ());
+    assert_eq!(
+        Parser::<_, _, Ext>::parse(&word, &mut slice, 3)
+            .into_result()
+            .unwrap()
+            .1,
+        9
+    );
+
+    let bytes = input.as_bytes();
+    let all = Predicate::<u8>::take0more(ANY).lift(|_| ());
+    assert!(Parser::<_, _, Ext>::fullmatch(&all, bytes).into_result().is_ok());
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn empty() {
+    let (input, path) = map("empty", b"");
+    assert_eq!((input.len(), input.is_empty()), (0, true));
+    assert_eq!(input.as_str(), Ok(""));
+    let mut utf8 = input.utf8();
+    let begin = utf8.begin();
+    assert!(utf8.has_reached_end(begin));
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn utf8() {
+    let (input, path) = map("utf8", b"caf\xc3\xa9 \xff!");
+    assert!(input.as_str().is_err());
+
+    // Validated lazily, so the text before the invalid byte is parsed.
+    let word = ('a'..='z', 'é').take1more().lift(|_| ());
+    assert_eq!(parse(word, input.utf8()).unwrap(), 5);
+    let err = parse(Predicate::<char>::take0more(ANY).lift(|_| ()), input.utf8()).unwrap_err();
+    assert_eq!((err.0, &*err.1), (6..7, "invalid input"));
+    let end = parse(Predicate::<char>::take0more(ANY).lift(|_| ()), input.utf8_lossy());
+    assert_eq!(end.unwrap(), 8);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn missing_file() {
+    let path = std::env::temp_dir().join(format!("kaparser-{}-missing", std::process::id()));
+    // SAFETY: Nothing is mapped.
+    let err = unsafe { MmapInput::open(path) }.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use common::*;
+use kaparser::{grammars::net::*, parser::*, pattern::*};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+fn full<'a, P, O>(pattern: P, text: &'a str) -> Result<O, Failed>
+where
+    P: Pattern<'a, &'a str, Ext, View<'a> = O>,
+{
+    pattern.captured().fullmatch(text).into_result()
+}
+
+fn v6(text: &str) -> Ipv6Addr {
+    text.parse().unwrap()
+}
+
+#[test]
+fn ipv4_addresses() {
+    assert_eq!(full(ipv4(), "192.0.2.1").unwrap(), Ipv4Addr::new(192, 0, 2, 1));
+    assert_eq!(full(ipv4(), "0.0.0.0").unwrap(), Ipv4Addr::UNSPECIFIED);
+    assert_eq!(full(ipv4(), "255.255.255.255").unwrap(), Ipv4Addr::BROADCAST);
+
+    for (text, span, message) in [
+        ("192.0.2.01", 8..10, "error: leading zeros in a decimal number"),
+        ("010.0.0.1", 0..3, "error: leading zeros in a decimal number"),
+        ("256.0.0.1", 0..3, "number too large to fit in target type"),
+        ("1.2.3.1000", 6..10, "number too large to fit in target type"),
+        ("1.2.3", 5..5, "expected `.`"),
+        ("1.2..3", 4..4, "expected a decimal octet"),
+        ("1.2.3.4.5", 7..7, "expected end of input"),
+    ] {
+        let err = full(ipv4(), text).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{text}");
+    }
+}
+
+#[test]
+fn ipv6_addresses() {
+    // RFC 4291, section 2.2
+    for text in [
+        "2001:DB8:0:0:8:800:200C:417A",
+        "2001:db8::8:800:200c:417a",
+        "FF01::101",
+        "::1",
+        "::",
+        "0:0:0:0:0:0:13.1.68.3",
+        "::13.1.68.3",
+        "::FFFF:129.144.52.38",
+        "1::",
+        "1:2:3:4:5:6:7::",
+        "::2:3:4:5:6:7:8",
+        "2001:0db8:0000:0000:0000:0000:0000:0001",
+    ] {
+        assert_eq!(full(ipv6(), text).unwrap(), v6(text), "{text}");
+    }
+
+    for (text, span, message) in [
+        (
+            "2001:db8::00001",
+            10..15,
+            "error: more than four hexadecimal digits in a group",
+        ),
+        ("1::2::3", 4..6, "error: `::` shall appear only once"),
+        (
+            "1:2:3:4::5:6:7:8",
+            7..9,
+            "error: `::` shall stand for at least one group",
+        ),
+        ("1:2:3:4:5:6:7", 13..13, "expected `:`"),
+        ("::ffff:192.0.2.01", 15..17, "error: leading zeros in a decimal number"),
+        (
+            "1:2:3:4:5:6:7:1.2.3.4",
+            14..21,
+            "error: embedded IPv4 address shall take the last 32 bits",
+        ),
+        (":1", 0..0, "expected a hexadecimal group"),
+        ("1:2:3:4:5:6:7:8:9", 15..15, "expected end of input"),
+    ] {
+        let err = full(ipv6(), text).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{text}");
+    }
+}
+
+#[test]
+fn networks() {
+    let net = full(cidr(), "192.0.2.1/24").unwrap();
+    assert_eq!(net.addr, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)));
+    assert_eq!(net.prefix_len, 24);
+    assert_eq!(net.network(), IpAddr::V4(Ipv4Addr::new(192, 0, 2, 0)));
+    assert!(net.contains("192.0.2.200".parse().unwrap()));
+    assert!(!net.contains("192.0.3.1".parse().unwrap()));
+    assert_eq!(net.to_string(), "192.0.2.1/24");
+
+    let net = full(cidr().strict(), "2001:db8::/32").unwrap();
+    assert_eq!(net.addr, IpAddr::V6(v6("2001:db8::")));
+    assert!(net.contains("2001:db8:ffff::1".parse().unwrap()));
+    assert!(!net.contains("192.0.2.1".parse().unwrap()));
+
+    assert_eq!(
+        full(cidr(), "0.0.0.0/0").unwrap().network(),
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    );
+    assert_eq!(full(cidr(), "::1/128").unwrap().prefix_len, 128);
+
+    for (text, span, message) in [
+        ("192.0.2.0/33", 10..12, "error: prefix length exceeds the address"),
+        ("192.0.2.0/08", 10..12, "error: leading zeros in a decimal number"),
+        ("192.0.2.0", 9..9, "expected `/`"),
+        ("192.0.2.0/", 10..10, "expected a prefix length"),
+    ] {
+        let err = full(cidr(), text).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{text}");
+    }
+    let err = full(cidr().strict(), "192.0.2.1/24").unwrap_err();
+    assert_eq!((err.0, &*err.1), (0..12, "error: host bits set after the prefix"));
+}
+
+#[test]
+fn mac_addresses() {
+    let expected = MacAddr([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+    for text in ["00:1a:2b:3c:4d:5e", "00-1A-2B-3C-4D-5E", "001a.2b3c.4d5e"] {
+        assert_eq!(full(mac(), text).unwrap(), expected, "{text}");
+    }
+    assert_eq!(expected.to_string(), "00:1a:2b:3c:4d:5e");
+
+    for (text, span, message) in [
+        ("00:1a:2b:3c:4d", 14..14, "expected `:`"),
+        ("00:1a:2b-3c:4d:5e", 8..8, "expected `:`"),
+        ("00:1a:2b:3c:4d:5e0", 15..18, "expected two hexadecimal digits"),
+        ("001a.2b3.c4d5e", 5..8, "expected four hexadecimal digits"),
+    ] {
+        let err = full(mac(), text).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{text}");
+    }
+}
+
+#[test]
+fn composed() {
+    // An address followed by a port, as in a log line.
+    let text = "10.0.0.1:8080";
+    let mut input = text;
+    let addr = Pattern::<&str, Ext>::captured(ip());
+    let (addr, end) = Parser::<&str, IpAddr, Ext>::parse(&addr, &mut input, 0)
+        .into_result()
+        .unwrap();
+    assert_eq!((addr, end), (IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 8));
+
+    let addr = Pattern::<&[u8], Ext>::captured(ipv6());
+    let addr = Parser::<&[u8], Ipv6Addr, Ext>::fullmatch(&addr, b"fe80::1".as_slice());
+    assert_eq!(addr.into_result().unwrap(), v6("fe80::1"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn streaming() {
+    use kaparser::input::rope::Rope;
+
+    let chunks: &[&str] = &["2001:d", "b8::19", "2.0.", "2.1/1", "28"];
+    let net = Pattern::<Rope, Ext>::lift(cidr(), |net| net);
+    let net = Parser::<Rope, IpNet, Ext>::fullmatch(&net, Rope::new(chunks))
+        .into_result()
+        .unwrap();
+    assert_eq!(net.addr, IpAddr::V6(v6("2001:db8::192.0.2.1")));
+    assert_eq!(net.prefix_len, 128);
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use common::*;
+use kaparser::{number::*, parser::*, pattern::*};
+use std::ops::Range;
+
+fn full<'a, P, O>(pattern: P, text: &'a str) -> Result<O, Failed>
+where
+    P: Pattern<'a, &'a str, Ext, View<'a> = O>,
+{
+    pattern.captured().fullmatch(text).into_result()
+}
+
+fn prefix<'a, P, O>(pattern: P, text: &'a str) -> Result<(O, usize), Failed>
+where
+    P: Pattern<'a, &'a str, Ext, View<'a> = O>,
+{
+    let mut input = text;
+    pattern.captured().parse(&mut input, 0).into_result()
+}
+
+fn fails<'a, P, O>(pattern: P, text: &'a str) -> (Range<usize>, String)
+where
+    P: Pattern<'a, &'a str, Ext, View<'a> = O>,
+    O: std::fmt::Debug,
+{
+    let err = full(pattern, text).unwrap_err();
+    (err.0, err.1)
+}
+
+#[test]
+fn integers() {
+    assert_eq!(full(int::<i32>(10), "12345").unwrap(), 12345);
+    assert_eq!(full(int::<i32>(10), "+7").unwrap(), 7);
+    assert_eq!(full(int::<i32>(10), "-2147483648").unwrap(), i32::MIN);
+    assert_eq!(full(int::<u8>(16), "fF").unwrap(), 0xff);
+    assert_eq!(full(int::<u64>(2), "1011").unwrap(), 0b1011);
+    assert_eq!(full(int::<u32>(8), "777").unwrap(), 0o777);
+    assert_eq!(full(int::<u32>(36), "zz").unwrap(), 36 * 36 - 1);
+
+    // Stops at the first byte which is not a digit.
+    assert_eq!(prefix(int::<u16>(10), "80/tcp").unwrap(), (80, 2));
+    assert_eq!(prefix(int::<u16>(16), "1fg").unwrap(), (0x1f, 2));
+
+    assert_eq!(
+        fails(int::<u8>(10), "256"),
+        (0..3, "number too large to fit in target type".into())
+    );
+    assert_eq!(
+        fails(int::<i8>(10), "-129"),
+        (0..4, "number too large to fit in target type".into())
+    );
+    assert_eq!(fails(int::<u8>(10), "-1"), (0..0, "expected a decimal integer".into()));
+    assert_eq!(
+        fails(int::<u8>(16), "g"),
+        (0..0, "expected a hexadecimal integer".into())
+    );
+    assert_eq!(fails(int::<u8>(2), "+"), (0..0, "expected a binary integer".into()));
+    assert_eq!(fails(int::<u8>(7), ""), (0..0, "expected an integer in radix 7".into()));
+    assert_eq!(fails(int::<u8>(10), "1 "), (1..1, "expected end of input".into()));
+}
+
+#[test]
+fn unsigned_and_separators() {
+    assert_eq!(fails(int::<i32>(10).unsigned(), "+1").0, 0..0);
+    assert_eq!(fails(int::<i32>(10).unsigned(), "-1").0, 0..0);
+    assert_eq!(full(int::<i32>(10).unsigned(), "42").unwrap(), 42);
+
+    let hex = || int::<u32>(16).separator(b'_');
+    assert_eq!(full(hex(), "dead_beef").unwrap(), 0xdead_beef);
+    assert_eq!(full(hex(), "1_2_3").unwrap(), 0x123);
+
+    // Separators are only allowed between two digits.
+    assert_eq!(prefix(hex(), "1__2").unwrap(), (1, 1));
+    assert_eq!(prefix(hex(), "12_").unwrap(), (0x12, 2));
+    assert_eq!(fails(hex(), "_1").0, 0..0);
+    assert_eq!(prefix(int::<u32>(10), "1_000").unwrap(), (1, 1));
+
+    // Overflow spans the whole literal, separators included.
+    assert_eq!(fails(int::<u8>(10).separator(b'_'), "1_000").0, 0..5);
+}
+
+#[test]
+fn floats() {
+    assert_eq!(full(float::<f64>(), "3.25").unwrap(), 3.25);
+    assert_eq!(full(float::<f64>(), "-12.5e-3").unwrap(), -0.0125);
+    assert_eq!(full(float::<f64>(), "+1E2").unwrap(), 100.0);
+    assert_eq!(full(float::<f32>(), "7").unwrap(), 7.0);
+
+    // Incomplete fractions and exponents are left over.
+    assert_eq!(prefix(float::<f64>(), "1.").unwrap(), (1.0, 1));
+    assert_eq!(prefix(float::<f64>(), "1.5e").unwrap(), (1.5, 3));
+    assert_eq!(prefix(float::<f64>(), "2e+x").unwrap(), (2.0, 1));
+    assert_eq!(
+        fails(float::<f64>(), ".5"),
+        (0..0, "expected a floating point number".into())
+    );
+    assert_eq!(fails(float::<f64>().unsigned(), "-1.0").0, 0..0);
+
+    assert_eq!(
+        fails(float::<f32>(), "1e39"),
+        (0..4, "number too large to fit in target type".into())
+    );
+    assert_eq!(full(float::<f64>(), "1e-400").unwrap(), 0.0);
+
+    assert_eq!(full(float::<f64>().separator(b'_'), "1_000.000_5").unwrap(), 1000.0005);
+    assert_eq!(prefix(float::<f64>().separator(b'_'), "1_.5").unwrap(), (1.0, 1));
+}
+
+#[test]
+fn inf_nan() {
+    let special = || float::<f64>().inf_nan();
+    assert_eq!(full(special(), "inf").unwrap(), f64::INFINITY);
+    assert_eq!(full(special(), "-Infinity").unwrap(), f64::NEG_INFINITY);
+    assert!(full(special(), "NaN").unwrap().is_nan());
+    assert_eq!(prefix(special(), "infinit").unwrap(), (f64::INFINITY, 3));
+    assert_eq!(fails(float::<f64>(), "inf").0, 0..0);
+}
+
+#[test]
+fn bytes() {
+    fn full_bytes<'a, P, O>(pattern: P, bytes: &'a [u8]) -> Result<O, Failed>
+    where
+        P: Pattern<'a, &'a [u8], Ext, View<'a> = O>,
+    {
+        pattern.captured().fullmatch(bytes).into_result()
+    }
+
+    assert_eq!(full_bytes(int::<i64>(10), b"-9000").unwrap(), -9000);
+    assert_eq!(full_bytes(float::<f64>(), b"0.5").unwrap(), 0.5);
+    assert_eq!(full_bytes(int::<u8>(10), b"300").unwrap_err().0, 0..3);
+}
+
+#[test]
+fn long_literals_with_separators() {
+    let digits = "1_0".repeat(100);
+    let res = full(float::<f64>().separator(b'_'), &digits);
+
+    #[cfg(feature = "alloc")]
+    assert_eq!(res.unwrap(), digits.replace('_', "").parse::<f64>().unwrap());
+
+    #[cfg(not(feature = "alloc"))]
+    assert_eq!(
+        res.map_err(|e| (e.0, e.1)).unwrap_err(),
+        (
+            0..digits.len(),
+            "error: literal with separators too long to convert without `alloc`".into()
+        )
+    );
+}
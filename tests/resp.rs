@@ -0,0 +1,263 @@
+//! Examples from the RESP protocol specification.
+
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+use kaparser::{grammars::resp::*, input::partial::PartialInput, parser::*};
+use std::ops::Range;
+
+fn decode(bytes: &[u8]) -> Result<Frame<'_>, Failed> {
+    Parser::<&[u8], Frame, Ext>::fullmatch(&frame(), bytes).into_result()
+}
+
+fn fails(parser: Resp, cases: &[(&[u8], Range<usize>, &str)]) {
+    for (bytes, span, message) in cases {
+        let err = Parser::<&[u8], Frame, Ext>::fullmatch(&parser, *bytes)
+            .into_result()
+            .unwrap_err();
+        assert_eq!((err.0, &*err.1), (span.clone(), *message), "{}", bytes.escape_ascii());
+    }
+}
+
+/// Decodes what has been received so far, `None` if incomplete.
+fn partial(bytes: &[u8], start: usize) -> Option<Result<(Frame<'_>, usize), Failed>> {
+    let mut input = PartialInput::new(bytes);
+    let res = Parser::<PartialInput, Frame, Ext>::parse(&frame(), &mut input, start).into_result();
+    (!input.is_incomplete()).then_some(res)
+}
+
+#[test]
+fn resp2() {
+    assert_eq!(decode(b"+OK\r\n").unwrap(), Frame::Simple(b"OK"));
+    let err = decode(b"-ERR unknown command 'asdf'\r\n").unwrap();
+    assert!(err.is_error());
+    assert_eq!(err.as_bytes(), Some(&b"ERR unknown command 'asdf'"[..]));
+    assert_eq!(decode(b":1000\r\n").unwrap(), Frame::Integer(1000));
+    assert_eq!(decode(b":-42\r\n").unwrap().as_integer(), Some(-42));
+    assert_eq!(decode(b":+7\r\n").unwrap().as_integer(), Some(7));
+    assert_eq!(
+        decode(b":-9223372036854775808\r\n").unwrap().as_integer(),
+        Some(i64::MIN)
+    );
+    assert_eq!(decode(b"$5\r\nhello\r\n").unwrap(), Frame::Bulk(b"hello"));
+    assert_eq!(decode(b"$0\r\n\r\n").unwrap(), Frame::Bulk(b""));
+    assert_eq!(decode(b"$4\r\na\r\nb\r\n").unwrap(), Frame::Bulk(b"a\r\nb"));
+    assert!(decode(b"$-1\r\n").unwrap().is_null());
+    assert!(decode(b"*-1\r\n").unwrap().is_null());
+    assert_eq!(decode(b"*0\r\n").unwrap(), Frame::Array(vec![]));
+
+    let array = decode(b"*2\r\n*3\r\n:1\r\n:2\r\n:3\r\n*2\r\n+Hello\r\n-World\r\n").unwrap();
+    assert_eq!(
+        array,
+        Frame::Array(vec![
+            Frame::Array(vec![Frame::Integer(1), Frame::Integer(2), Frame::Integer(3)]),
+            Frame::Array(vec![Frame::Simple(b"Hello"), Frame::Error(b"World")]),
+        ])
+    );
+    let array = decode(b"*3\r\n$5\r\nhello\r\n$-1\r\n$5\r\nworld\r\n").unwrap();
+    assert_eq!(
+        array.as_slice(),
+        Some(&[Frame::Bulk(b"hello"), Frame::Null, Frame::Bulk(b"world")][..])
+    );
+
+    fails(
+        frame(),
+        &[
+            (b"+OK\n", 3..4, "error: LF without CR"),
+            (b"+OK\r", 4..4, "expected `\\r\\n`"),
+            (b":12a\r\n", 1..1, "expected an integer"),
+            (
+                b":9223372036854775808\r\n",
+                1..20,
+                "number too large to fit in target type",
+            ),
+            (b"$-2\r\n", 1..3, "error: negative length other than -1"),
+            (b"$3\r\nhello\r\n", 7..7, "expected `\\r\\n`"),
+            (b"$5\r\nhi\r\n", 8..8, "expected bulk data"),
+            (b"?\r\n", 0..0, "expected a frame"),
+            (b"$?\r\n", 1..1, "expected an integer"),
+            (b"+OK\r\n+OK\r\n", 5..5, "expected end of input"),
+        ],
+    );
+    fails(frame().resp2(), &[(b"_\r\n", 0..0, "expected a RESP2 frame")]);
+}
+
+#[test]
+fn resp3() {
+    assert!(decode(b"_\r\n").unwrap().is_null());
+    assert_eq!(decode(b"#t\r\n").unwrap(), Frame::Boolean(true));
+    assert_eq!(decode(b"#f\r\n").unwrap(), Frame::Boolean(false));
+    assert_eq!(decode(b",1.23\r\n").unwrap(), Frame::Double(1.23));
+    assert_eq!(decode(b",10\r\n").unwrap(), Frame::Double(10.0));
+    assert_eq!(decode(b",-1.5e3\r\n").unwrap(), Frame::Double(-1500.0));
+    assert_eq!(decode(b",inf\r\n").unwrap(), Frame::Double(f64::INFINITY));
+    assert_eq!(decode(b",-inf\r\n").unwrap(), Frame::Double(f64::NEG_INFINITY));
+    assert!(matches!(decode(b",nan\r\n").unwrap(), Frame::Double(x) if x.is_nan()));
+    assert_eq!(
+        decode(b"(3492890328409238509324850943850943825024385\r\n").unwrap(),
+        Frame::BigNumber("3492890328409238509324850943850943825024385")
+    );
+    assert_eq!(decode(b"(-1\r\n").unwrap(), Frame::BigNumber("-1"));
+    assert_eq!(
+        decode(b"!21\r\nSYNTAX invalid syntax\r\n").unwrap(),
+        Frame::BulkError(b"SYNTAX invalid syntax")
+    );
+    assert_eq!(
+        decode(b"=15\r\ntxt:Some string\r\n").unwrap(),
+        Frame::Verbatim {
+            format: "txt",
+            text: b"Some string"
+        }
+    );
+
+    assert_eq!(
+        decode(b"%2\r\n+first\r\n:1\r\n+second\r\n:2\r\n").unwrap(),
+        Frame::Map(vec![
+            (Frame::Simple(b"first"), Frame::Integer(1)),
+            (Frame::Simple(b"second"), Frame::Integer(2)),
+        ])
+    );
+    assert_eq!(
+        decode(b"~2\r\n+orange\r\n+apple\r\n").unwrap(),
+        Frame::Set(vec![Frame::Simple(b"orange"), Frame::Simple(b"apple")])
+    );
+    let push = decode(b">3\r\n$7\r\nmessage\r\n$7\r\nchannel\r\n$5\r\nhello\r\n").unwrap();
+    assert_eq!(push.as_slice().unwrap()[2].as_bytes(), Some(&b"hello"[..]));
+    assert!(matches!(push, Frame::Push(_)));
+
+    let attributed = decode(
+        b"|1\r\n+key-popularity\r\n%2\r\n$1\r\na\r\n,0.1923\r\n$1\r\nb\r\n,0.0012\r\n*2\r\n:2039123\r\n:9543892\r\n",
+    )
+    .unwrap();
+    let Frame::Attribute { attrs, .. } = &attributed else {
+        panic!()
+    };
+    assert_eq!(attrs[0].0, Frame::Simple(b"key-popularity"));
+    assert_eq!(
+        attributed.without_attrs(),
+        &Frame::Array(vec![Frame::Integer(2039123), Frame::Integer(9543892)])
+    );
+
+    fails(
+        frame(),
+        &[
+            (b"#x\r\n", 1..1, "expected `t` or `f`"),
+            (b",1.2.3\r\n", 1..6, "invalid input"),
+            (b",1 \r\n", 1..3, "invalid input"),
+            (b"(12x\r\n", 1..1, "expected a big number"),
+            (
+                b"=3\r\ntxt\r\n",
+                1..1,
+                "expected a verbatim string with a format of three bytes",
+            ),
+            (b"_x\r\n", 1..1, "expected `\\r\\n`"),
+            (b"%1\r\n+key\r\n", 10..10, "expected a frame"),
+            // Only bulk strings and arrays may be null.
+            (
+                b"!-1\r\n",
+                1..3,
+                "error: length of -1 other than for a bulk string or an array",
+            ),
+            (
+                b"=-1\r\n",
+                1..3,
+                "error: length of -1 other than for a bulk string or an array",
+            ),
+            (
+                b"%-1\r\n",
+                1..3,
+                "error: length of -1 other than for a bulk string or an array",
+            ),
+            (
+                b"|-1\r\n+OK\r\n",
+                1..3,
+                "error: length of -1 other than for a bulk string or an array",
+            ),
+            (
+                b"~-1\r\n",
+                1..3,
+                "error: length of -1 other than for a bulk string or an array",
+            ),
+            (
+                b">-1\r\n",
+                1..3,
+                "error: length of -1 other than for a bulk string or an array",
+            ),
+            (
+                b"*1\r\n~-1\r\n",
+                5..7,
+                "error: length of -1 other than for a bulk string or an array",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn limits() {
+    fails(
+        frame().max_bulk_len(4),
+        &[
+            (b"$5\r\nhello\r\n", 1..2, "error: bulk length exceeds the limit"),
+            (b"+hello\r\n", 5..5, "error: bulk length exceeds the limit"),
+        ],
+    );
+    fails(
+        frame().max_elements(1),
+        &[(b"*2\r\n:1\r\n:2\r\n", 1..2, "error: aggregate length exceeds the limit")],
+    );
+    fails(
+        frame().max_depth(2),
+        &[(b"*1\r\n*1\r\n*1\r\n:1\r\n", 12..12, "error: aggregates nested too deep")],
+    );
+    let nested = b"*1\r\n".repeat(100_000);
+    assert!(decode(&nested).is_err());
+
+    // A huge declared length is not allocated upfront.
+    assert!(partial(b"*1048576\r\n:1\r\n", 0).is_none());
+
+    // Without a limit, the largest length is merely waited for.
+    let mut input = PartialInput::new(&b"$9223372036854775807\r\n"[..]);
+    let unlimited = frame().max_bulk_len(usize::MAX);
+    assert!(
+        Parser::<PartialInput, Frame, Ext>::parse(&unlimited, &mut input, 0)
+            .into_result()
+            .is_err()
+    );
+    assert!(input.is_incomplete());
+}
+
+#[test]
+fn incremental() {
+    let stream = b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n:1\r\n+PONG\r\n";
+
+    // A socket read loop, receiving one more byte at a time.
+    let mut frames = vec![];
+    let mut start = 0;
+    for received in 0..=stream.len() {
+        while let Some(res) = partial(&stream[..received], start) {
+            let (frame, end) = res.unwrap();
+            frames.push(frame);
+            start = end;
+        }
+    }
+    assert_eq!(start, stream.len());
+    assert_eq!(
+        frames,
+        [
+            Frame::Array(vec![Frame::Bulk(b"SET"), Frame::Bulk(b"key"), Frame::Bulk(b"value")]),
+            Frame::Integer(1),
+            Frame::Simple(b"PONG"),
+        ]
+    );
+
+    // Bulk payloads are borrowed from the buffer.
+    let (frame, _) = partial(stream, 0).unwrap().unwrap();
+    let value = frame.as_slice().unwrap()[2].as_bytes().unwrap();
+    assert_eq!(value.as_ptr(), stream[26..].as_ptr());
+
+    // Errors are reported without waiting for more.
+    let err = partial(b"*2\r\n:1\r\n?", 0).unwrap().unwrap_err();
+    assert_eq!((err.0, &*err.1), (8..8, "expected a frame"));
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+use kaparser::{
+    input::{Input, InputOwnableToken, InputSlice, rope::*},
+    literal::*,
+    number::*,
+    parser::*,
+    pattern::*,
+    predicate::*,
+};
+
+fn parse_at<'a, P, O>(parser: P, chunks: &'a [&'a str], start: usize) -> Result<(O, usize), Failed>
+where
+    P: Parser<'a, Rope<'a>, O, Ext>,
+{
+    let mut rope = Rope::new(chunks);
+    let start = Rope::bump_cursor(rope.begin(), start);
+    let res = parser.parse(&mut rope, start).into_result();
+    res.map(|(out, cur)| (out, Rope::offset(cur)))
+}
+
+#[test]
+fn cursors() {
+    let chunks = ["", "ab", "", "", "cd", ""];
+    let rope = Rope::new(&chunks);
+
+    // Cursors at the end of a chunk move on to the next non-empty one.
+    let begin = rope.begin();
+    assert_eq!((begin.chunk(), begin.offset()), (1, 0));
+    let cur = Rope::bump_cursor(begin.clone(), 2);
+    assert_eq!((cur.chunk(), cur.offset(), Rope::offset(cur.clone())), (4, 0, 2));
+    let end = Rope::bump_cursor(cur, 2);
+    assert_eq!((end.chunk(), end.offset(), Rope::offset(end.clone())), (5, 0, 4));
+    assert!(Rope::new(&chunks).has_reached_end(end.clone()));
+    assert!(!Rope::new(&chunks).has_reached_end(begin.clone()));
+    assert!(Rope::new(&[]).has_reached_end(Rope::new(&[]).begin()));
+
+    let tokens = Rope::new(&chunks).iter_owned(begin..end).collect::<String>();
+    assert_eq!(tokens, "abcd");
+}
+
+#[test]
+fn slices() {
+    let chunks = ["hel", "lo ", "wor", "ld"];
+    let mut rope = Rope::new(&chunks);
+    let at = |n| Rope::bump_cursor(Rope::new(&chunks).begin(), n);
+
+    // Within a chunk, slices are borrowed.
+    assert!(std::ptr::eq(rope.release_slice(at(3)..at(5)), &chunks[1][..2]));
+    assert_eq!(rope.get_slice(at(0)..at(2)), Some("he"));
+
+    // Straddling ones are stitched, including the one ending at a chunk boundary.
+    assert_eq!(rope.release_slice(at(1)..at(8)), "ello wo");
+    assert_eq!(rope.get_slice(at(1)..at(8)), Some("ello wo"));
+    assert_eq!(rope.get_slice(at(2)..at(8)), None);
+    assert_eq!(rope.release_slice(at(4)..at(9)), "o wor");
+
+    // More chunks are fetched each time from the same start.
+    let mut rope = Rope::new(&chunks);
+    let (slice, eof) = rope.fetch_slice::<Failed>(at(4)).unwrap();
+    assert_eq!((slice, eof), ("o ", false));
+    let (slice, eof) = rope.fetch_slice::<Failed>(at(4)).unwrap();
+    assert_eq!((slice, eof), ("o wor", false));
+    let (slice, eof) = rope.fetch_slice::<Failed>(at(4)).unwrap();
+    assert_eq!((slice, eof), ("o world", true));
+    let (slice, eof) = rope.fetch_slice::<Failed>(at(4)).unwrap();
+    assert_eq!((slice, eof), ("o world", true));
+    let (slice, eof) = rope.fetch_slice::<Failed>(at(7)).unwrap();
+    assert_eq!((slice, eof), ("or", false));
+}
+
+#[test]
+fn patterns_across_chunks() {
+    // `lift` needs `'static` chunks.
+    let chunks = &["12", "3", "4.5", "e1 +", "= x"];
+    let (n, end) = parse_at(int::<u32>(10).lift(|n| n), chunks, 0).unwrap();
+    assert_eq!((n, end), (1234, 4));
+    let (x, end) = parse_at(float::<f64>().lift(|x| x), chunks, 0).unwrap();
+    assert_eq!((x, end), (12345.0, 8));
+
+    // Literals are decided only once the next chunk tells `+` from `+=`.
+    let ops = one_of_literals([("+", 1), ("+=", 2)]);
+    assert_eq!(parse_at(ops.lift(|op| op), chunks, 9).unwrap(), (2, 11));
+
+    let word = (' ', 'x', '=').take1more().lift(|_| ());
+    assert_eq!(parse_at(word, chunks, 10).unwrap().1, 13);
+    let digits = ('0'..='9').take1more().lift(|_| ());
+    assert_eq!(parse_at(digits, chunks, 0).unwrap().1, 4);
+
+    let chars = &["aé", "€x"];
+    assert_eq!(parse_at(['€'].lift(|c| c), chars, 3).unwrap(), ('€', 6));
+    let err = parse_at(['€'].lift(|c| c), chars, 6).unwrap_err();
+    assert_eq!((err.0, &*err.1), (6..7, "expected a single token matches €"));
+
+    let err = parse_at(int::<u8>(10).lift(|n| n), &["25", "6"], 0).unwrap_err();
+    assert_eq!((err.0, &*err.1), (0..3, "number too large to fit in target type"));
+}
@@ -0,0 +1,161 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+use kaparser::{grammars::semver::*, parser::*};
+use std::cmp::Ordering;
+
+fn version(text: &'static str) -> Version<'static> {
+    Parser::<&str, Version, Ext>::fullmatch(&semver(), text)
+        .into_result()
+        .unwrap()
+}
+
+fn req(text: &'static str) -> VersionReq<'static> {
+    Parser::<&str, VersionReq, Ext>::fullmatch(&semver_req(), text)
+        .into_result()
+        .unwrap()
+}
+
+/// Checks the requirement against versions that shall match and those that shall not.
+fn matches(text: &'static str, yes: &[&'static str], no: &[&'static str]) {
+    let req = req(text);
+    for v in yes {
+        assert!(req.matches(&version(v)), "{text} shall match {v}");
+    }
+    for v in no {
+        assert!(!req.matches(&version(v)), "{text} shall not match {v}");
+    }
+}
+
+#[test]
+fn versions() {
+    let v = version("1.0.0-alpha.1+001.sha-5114f85");
+    assert_eq!((v.major, v.minor, v.patch), (1, 0, 0));
+    assert_eq!(v.pre.identifiers().collect::<Vec<_>>(), ["alpha", "1"]);
+    assert_eq!(v.build.as_str(), "001.sha-5114f85");
+    assert_eq!(v.to_string(), "1.0.0-alpha.1+001.sha-5114f85");
+    assert_eq!(version("10.20.30"), Version::new(10, 20, 30));
+
+    for (text, span, message) in [
+        ("01.0.0", 0..2, "invalid input"),
+        ("1.0", 3..3, "expected `.`"),
+        ("1..0", 2..2, "expected a version number"),
+        ("1.0.0-", 6..6, "expected an identifier"),
+        ("1.0.0-alpha..1", 12..12, "expected an identifier"),
+        ("1.0.0-01", 6..8, "invalid input"),
+        (
+            "18446744073709551616.0.0",
+            0..20,
+            "number too large to fit in target type",
+        ),
+    ] {
+        let err = Parser::<&str, Version, Ext>::fullmatch(&semver(), text)
+            .into_result()
+            .unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{text}");
+    }
+
+    // Leading zeros are allowed in build metadata.
+    assert_eq!(version("1.0.0+01").build.as_str(), "01");
+}
+
+#[test]
+fn precedence() {
+    // In the order given by the specification.
+    let versions = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc.1",
+        "1.0.0",
+        "2.0.0",
+        "2.1.0",
+        "2.1.1",
+    ]
+    .map(version);
+    for pair in versions.windows(2) {
+        assert_eq!(
+            pair[0].cmp_precedence(&pair[1]),
+            Ordering::Less,
+            "{} < {}",
+            pair[0],
+            pair[1]
+        );
+        assert!(pair[0] < pair[1]);
+    }
+
+    // Build metadata is ignored by precedence, but not by equality.
+    let (a, b) = (version("1.0.0+a"), version("1.0.0+b"));
+    assert_eq!(a.cmp_precedence(&b), Ordering::Equal);
+    assert_ne!(a, b);
+}
+
+#[test]
+fn caret() {
+    matches("^1.2.3", &["1.2.3", "1.9.0"], &["1.2.2", "2.0.0"]);
+    matches("^1.2", &["1.2.0", "1.9.9"], &["1.1.9", "2.0.0"]);
+    matches("^1", &["1.0.0", "1.9.9"], &["0.9.9", "2.0.0"]);
+    matches("^0.2.3", &["0.2.3", "0.2.9"], &["0.2.2", "0.3.0"]);
+    matches("^0.2", &["0.2.0", "0.2.9"], &["0.1.9", "0.3.0"]);
+    matches("^0.0.3", &["0.0.3"], &["0.0.2", "0.0.4"]);
+    matches("^0.0", &["0.0.0", "0.0.9"], &["0.1.0"]);
+    matches("^0", &["0.0.0", "0.9.9"], &["1.0.0"]);
+    // Without an operator, the same as caret.
+    matches("1.2.3", &["1.2.3", "1.9.0"], &["1.2.2", "2.0.0"]);
+}
+
+#[test]
+fn tilde() {
+    matches("~1.2.3", &["1.2.3", "1.2.9"], &["1.2.2", "1.3.0"]);
+    matches("~1.2", &["1.2.0", "1.2.9"], &["1.1.9", "1.3.0"]);
+    matches("~1", &["1.0.0", "1.9.9"], &["0.9.9", "2.0.0"]);
+}
+
+#[test]
+fn wildcard() {
+    matches("*", &["0.0.0", "1.2.3"], &["1.0.0-alpha"]);
+    matches("1.*", &["1.0.0", "1.9.9"], &["0.9.9", "2.0.0"]);
+    matches("1.2.*", &["1.2.0", "1.2.9"], &["1.1.9", "1.3.0"]);
+    matches("1.2.x", &["1.2.0"], &["1.3.0"]);
+    matches("=1.X", &["1.0.0"], &["2.0.0"]);
+}
+
+#[test]
+fn comparisons() {
+    matches(">= 1.2.0", &["1.2.0", "5.0.0"], &["1.1.9"]);
+    matches("> 1", &["2.0.0"], &["1.9.9"]);
+    matches("< 2", &["1.9.9"], &["2.0.0"]);
+    matches("<=1.2", &["1.2.9"], &["1.3.0"]);
+    matches("= 1.2.3", &["1.2.3"], &["1.2.4"]);
+    matches(">= 1.2, < 1.5", &["1.2.0", "1.4.9"], &["1.1.9", "1.5.0"]);
+    assert_eq!(req(">= 1.2 ,< 1.5").to_string(), ">=1.2, <1.5");
+    assert_eq!(req("1.2.x").to_string(), "1.2.*");
+    assert_eq!(req("*").to_string(), "*");
+}
+
+#[test]
+fn prereleases() {
+    matches(
+        ">=1.2.3-beta",
+        &["1.2.3-rc", "1.2.3", "1.3.0"],
+        &["1.2.3-alpha", "1.3.0-rc"],
+    );
+    matches("^1.2.3", &["1.2.3"], &["1.2.4-alpha"]);
+    matches("~1.2.3-beta.2", &["1.2.3-beta.11", "1.2.3"], &["1.2.3-beta.1"]);
+
+    for (text, span, message) in [
+        (">1.*", 0..4, "invalid input"),
+        ("^", 1..1, "expected a version number"),
+        ("1.2.3,", 6..6, "expected a version number"),
+    ] {
+        let err = Parser::<&str, VersionReq, Ext>::fullmatch(&semver_req(), text)
+            .into_result()
+            .unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{text}");
+    }
+}
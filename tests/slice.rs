@@ -0,0 +1,96 @@
+mod common;
+
+use common::*;
+use kaparser::{
+    input::*,
+    literal::*,
+    number::*,
+    parser::*,
+    pattern::*,
+    predicate::*,
+    slice::{Slice, latin1::*, utf16::*},
+};
+
+fn parse_at<'a, S, P, O>(parser: P, text: &'a S, start: usize) -> Result<(O, usize), Failed>
+where
+    S: ?Sized + Slice<'a>,
+    P: Parser<'a, &'a S, O, Ext>,
+{
+    let mut input = text;
+    parser.parse(&mut input, start).into_result()
+}
+
+fn utf16le(text: &str) -> &'static Utf16Le {
+    Utf16Le::new(text.encode_utf16().map(u16::to_le).collect::<Vec<_>>().leak())
+}
+
+#[test]
+fn utf16() {
+    let text = utf16le("hé😀!");
+    assert_eq!(text.len(), 5);
+    assert_eq!(format!("{text:?}"), "\"hé😀!\"");
+
+    // Offsets are in code units, and a surrogate pair is a single token.
+    assert_eq!(parse_at(['h'].captured(), text, 0).unwrap(), ('h', 1));
+    assert_eq!(parse_at(['😀'].captured(), text, 2).unwrap(), ('😀', 4));
+    let letters = ('a'..='z', 'é').take1more().lift(|_| ());
+    assert_eq!(parse_at(letters, text, 0).unwrap().1, 2);
+    let (_, end) = parse_at(Predicate::<char>::take0more(ANY).captured(), text, 0).unwrap();
+    assert_eq!(end, 5);
+
+    let err = parse_at(['!'].captured(), text, 2).unwrap_err();
+    assert_eq!((err.0, &*err.1), (2..4, "expected a single token matches !"));
+
+    // Slicing between the surrogates of a pair is refused.
+    assert!(text.is_item_boundary(2));
+    assert!(!text.is_item_boundary(3));
+    assert!(text.is_item_boundary(5));
+    assert_eq!((&text).get_slice(2..4), Some(utf16le("😀")));
+    assert_eq!((&text).truncate(3), None);
+    assert_eq!((&text).truncate(4).map(|t| t.len()), Some(4));
+}
+
+#[test]
+fn utf16_units() {
+    // Lone surrogates are decoded as U+FFFD.
+    let lone = Utf16Le::new(vec![u16::to_le(0xd800), u16::to_le(0x61)].leak());
+    assert_eq!(format!("{lone:?}"), "\"\u{fffd}a\"");
+    assert_eq!(parse_at(['\u{fffd}'].captured(), lone, 0).unwrap(), ('\u{fffd}', 1));
+    assert!(lone.is_item_boundary(1));
+
+    let units = [0x68, 0xd83d, 0xde00].map(u16::to_be);
+    // SAFETY: `u16` has no padding bytes.
+    let bytes: &[u8] = unsafe { std::slice::from_raw_parts(units.as_ptr().cast(), 6) };
+    let text = Utf16Be::from_bytes(bytes).unwrap();
+    assert_eq!(format!("{text:?}"), "\"h😀\"");
+    assert_eq!(text.as_units().len(), 3);
+    assert_eq!(parse_at(['😀'].captured(), text, 1).unwrap(), ('😀', 3));
+
+    // Misaligned or odd lengths are refused.
+    assert!(Utf16Be::from_bytes(&bytes[..3]).is_none());
+    assert!(Utf16Le::from_bytes(&bytes[1..5]).is_none());
+    assert_eq!(Utf16Le::from_bytes(&bytes[2..]).unwrap().as_units().len(), 2);
+    assert!(Utf16Le::from_bytes(&bytes[2..2]).unwrap().as_units().is_empty());
+}
+
+#[test]
+fn latin1() {
+    let text = Latin1::new(b"caf\xe9 = 42\xb0");
+    assert_eq!(format!("{text:?}"), "\"café = 42°\"");
+    assert_eq!(text.len(), 10);
+
+    assert_eq!(parse_at(['é'].captured(), text, 3).unwrap(), ('é', 4));
+    let word = ('a'..='z', 'é').take1more().lift(|_| ());
+    assert_eq!(parse_at(word, text, 0).unwrap().1, 4);
+    let err = parse_at(['€'].captured(), text, 9).unwrap_err();
+    assert_eq!((err.0, &*err.1), (9..10, "expected a single token matches €"));
+
+    // Byte-oriented patterns work on the code units.
+    let ops = one_of_literals([("=", 1), ("==", 2)]);
+    assert_eq!(parse_at(ops.captured(), text, 5).unwrap(), (1, 6));
+    assert_eq!(parse_at(int::<u8>(10).captured(), text, 7).unwrap(), (42, 9));
+    assert_eq!(
+        parse_at([ByteSet::new(b"\xb0")].captured(), text, 9).unwrap(),
+        ('°', 10)
+    );
+}
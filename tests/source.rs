@@ -0,0 +1,118 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+use kaparser::{
+    input::{Input, InputSlice, InputTruncatable, source::SourceInput},
+    location::ColumnUnit,
+    number::*,
+    parser::*,
+    pattern::*,
+    source::*,
+};
+
+/// A grammar written against plain `&str`.
+fn number<'a>() -> impl Parser<'a, &'a str, u32, Ext> {
+    int::<u32>(10).captured()
+}
+
+fn sources() -> (SourceMap<'static>, [SourceId; 3]) {
+    let mut map = SourceMap::new();
+    let ids = [
+        map.add("a.txt", "12"),
+        map.add("empty.txt", ""),
+        map.add(String::from("dir/b.txt"), "345\r\nx"),
+    ];
+    (map, ids)
+}
+
+#[test]
+fn layout() {
+    let (map, [a, empty, b]) = sources();
+    assert_eq!((map.len(), map.ids().collect::<Vec<_>>()), (3, vec![a, empty, b]));
+    assert_eq!(
+        (map.name(b), map.text(b), map.text(empty)),
+        ("dir/b.txt", "345\r\nx", "")
+    );
+    assert_eq!(b.to_string(), "#2");
+
+    // Each source is parsed on its own, without seeing the others.
+    assert_eq!(map.input(b), "345\r\nx");
+    assert_eq!(map.input(empty), "");
+
+    // Borrowed text is not copied.
+    let text = String::from("abc");
+    let mut map = SourceMap::new();
+    let id = map.add("c.txt", text.as_str());
+    assert!(std::ptr::eq(map.text(id), text.as_str()));
+}
+
+#[test]
+fn local_spans() {
+    let (map, [a, empty, b]) = sources();
+
+    // The same grammar parses each source with local spans, and errors are tagged with the source.
+    let parser = number();
+    assert_eq!(map.fullmatch(a, &parser).into_result().unwrap(), 12);
+    let err = map.fullmatch(empty, &parser).into_result().unwrap_err();
+    assert_eq!(err.source, empty);
+    assert_eq!((err.error.0, &*err.error.1), (0..0, "expected a decimal integer"));
+
+    // Parsing stops at the end of the source.
+    let err = map.fullmatch(b, &parser).into_result().unwrap_err();
+    assert_eq!((err.error.0.clone(), &*err.error.1), (3..3, "expected end of input"));
+    let span = SourceSpan::new(err.source, err.error.0);
+    assert_eq!(span, SourceSpan::new(b, 3..3));
+    assert_eq!(map.locate(&span, ColumnUnit::Chars).unwrap().to_string(), "1:4-1:4");
+
+    let mut input = map.input(b);
+    let (n, end) = parser.parse(&mut input, 0).into_result().unwrap();
+    assert_eq!((n, end), (345, 3));
+}
+
+#[test]
+fn reports() {
+    let (map, [_, _, b]) = sources();
+    let report = map.report(&SourceSpan::new(b, 0..3), "unexpected number");
+    assert_eq!(
+        report.to_string(),
+        "error: unexpected number\n --> dir/b.txt:1:1\n  |\n1 | 345\n  | ^^^\n"
+    );
+
+    // Spans beyond the first line are underlined until its end, and empty ones by one column.
+    let report = map.report(&SourceSpan::new(b, 1..6), "bad");
+    assert!(report.to_string().ends_with("1 | 345\n  |  ^^\n"), "{report}");
+    let report = map.report(&SourceSpan::new(b, 5..5), "bad");
+    assert!(
+        report.to_string().ends_with(" --> dir/b.txt:2:1\n  |\n2 | x\n  | ^\n"),
+        "{report}"
+    );
+}
+
+#[test]
+fn shifted_input() {
+    // A part of a larger text, from offset 10.
+    let mut input = SourceInput::new("x = 12;", 10);
+    assert_eq!((input.slice(), input.base()), ("x = 12;", 10));
+    let begin = input.begin();
+    let start = SourceInput::<str>::bump_cursor(begin, 4);
+    let (n, end) = Parser::<_, _, Ext>::parse(&int::<u32>(10).captured(), &mut input, start)
+        .into_result()
+        .unwrap();
+    assert_eq!((n, end.local(), SourceInput::<str>::offset(end)), (12, 6, 16));
+    assert_eq!(input.get_slice(start..end), Some("12"));
+
+    let err = Parser::<_, _, Ext>::parse(&int::<u32>(10).captured(), &mut input, begin)
+        .into_result()
+        .unwrap_err();
+    assert_eq!((err.0, &*err.1), (10..10, "expected a decimal integer"));
+
+    // Truncation keeps the base.
+    let short = input.truncate(end).unwrap();
+    assert_eq!((short.slice(), short.base()), ("x = 12", 10));
+    let err = Parser::<_, _, Ext>::fullmatch(&int::<u32>(10).captured(), SourceInput::new("7x", 3))
+        .into_result()
+        .unwrap_err();
+    assert_eq!((err.0, &*err.1), (4..4, "expected end of input"));
+}
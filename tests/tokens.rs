@@ -0,0 +1,145 @@
+mod common;
+
+use common::*;
+use kaparser::{
+    combinator::*,
+    input::{Input, InputSlice, tokens::*},
+    kind,
+    parser::*,
+    pattern::*,
+    payload,
+    predicate::*,
+    primitive::*,
+};
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Ident(&'static str),
+    Num(usize),
+    Plus,
+    LParen,
+    RParen,
+}
+
+/// Tokens of `foo + (42)  bar`.
+fn lexed() -> (Vec<Tok>, Vec<Range<usize>>) {
+    let tokens = vec![
+        Tok::Ident("foo"),
+        Tok::Plus,
+        Tok::LParen,
+        Tok::Num(42),
+        Tok::RParen,
+        Tok::Ident("bar"),
+    ];
+    let spans = vec![0..3, 4..5, 6..7, 7..9, 9..10, 12..15];
+    (tokens, spans)
+}
+
+/// Tokens of `2 a b c`, `lift` needs them to be `'static`.
+static TOKENS: [Tok; 4] = [Tok::Num(2), Tok::Ident("a"), Tok::Ident("b"), Tok::Ident("c")];
+static SPANS: [Range<usize>; 4] = [0..1, 2..3, 4..5, 6..7];
+
+fn parse_at<'a, P, O>(parser: P, input: SpannedTokens<'a, Tok>, index: usize) -> Result<(O, usize), Failed>
+where
+    P: Parser<'a, SpannedTokens<'a, Tok>, O, Ext>,
+{
+    let mut input = input;
+    let start = SpannedTokens::<Tok>::bump_cursor(input.begin(), index);
+    let res = parser.parse(&mut input, start).into_result();
+    res.map(|(out, cur)| (out, cur.index()))
+}
+
+#[test]
+fn kinds_and_payloads() {
+    let (tokens, spans) = lexed();
+    let input = SpannedTokens::new(&tokens, &spans);
+
+    let ident = payload!(Tok::Ident(name) => *name);
+    let (name, end) = parse_at(ident.captured(), input, 0).unwrap();
+    assert_eq!((name, end), ("foo", 1));
+
+    let op = Ref(kind!(Tok::Plus | Tok::LParen));
+    assert_eq!(parse_at(op.captured(), input, 1).unwrap(), (&Tok::Plus, 2));
+    assert_eq!(
+        parse_at([kind!(Tok::Num(_))].captured(), input, 3).unwrap(),
+        (Tok::Num(42), 4)
+    );
+
+    let err = parse_at(Ref(kind!(Tok::Num(_))).captured(), input, 1).unwrap_err();
+    assert_eq!((err.0, &*err.1), (4..5, "expected a single token matches Tok::Num(_)"));
+    let err = parse_at(payload!(Tok::Num(n) => n).captured(), input, 5).unwrap_err();
+    assert_eq!(
+        (err.0, &*err.1),
+        (12..15, "expected a single token matches Tok::Num(n)")
+    );
+}
+
+#[test]
+fn source_spans() {
+    let (tokens, spans) = lexed();
+    let input = SpannedTokens::new(&tokens, &spans);
+
+    // Spans of several tokens cover the text between them.
+    let group = kind!(Tok::LParen | Tok::Num(_) | Tok::RParen).take1more();
+    let (_, end) = parse_at(group.captured(), input, 2).unwrap();
+    assert_eq!(end, 5);
+    assert_eq!(SpannedTokens::<Tok>::span(input.begin()..input.begin()), 0..0);
+    let cursor = |index| SpannedTokens::<Tok>::bump_cursor(input.begin(), index);
+    assert_eq!(SpannedTokens::<Tok>::span(cursor(2)..cursor(5)), 6..10);
+    assert_eq!(SpannedTokens::<Tok>::offset(cursor(5)), 12);
+
+    // The end of input is at the end of the last token, unless told otherwise.
+    assert_eq!(SpannedTokens::<Tok>::offset(cursor(6)), 15);
+    let err = Parser::<_, _, Ext>::fullcheck(&kind!(Tok::Ident(_)).take0more().captured(), input).unwrap_err();
+    assert_eq!((err.0, &*err.1), (4..4, "expected end of input"));
+
+    let input = input.with_end(16);
+    let err = parse_at(kind!(Tok::Ident(_)).take1more().captured(), input, 6)
+        .map(|_| ())
+        .unwrap_err();
+    assert_eq!(err.0, 16..16);
+
+    // Cursors past the end, as for lengths declared too long.
+    let past = SpannedTokens::<Tok>::bump_cursor(input.begin(), 8);
+    assert_eq!(SpannedTokens::<Tok>::span(cursor(5)..past), 12..16);
+}
+
+#[test]
+fn truncation() {
+    // A count followed by that many identifiers.
+    let input = SpannedTokens::new(&TOKENS, &SPANS);
+
+    let count = |extra| payload!(Tok::Num(n) => n).lift(move |n: &usize| *n + extra);
+    let list = length_prefixed(count(0), kind!(Tok::Ident(_)).take0more().captured());
+    assert_eq!(parse_at(list, input, 0).unwrap().1, 3);
+
+    // Errors inside the truncated input point into the source.
+    let list = length_prefixed(count(1), Ref(ANY).captured());
+    let err = parse_at(list, input, 0).unwrap_err();
+    assert_eq!((err.0, &*err.1), (4..4, "expected end of input"));
+
+    let list = length_prefixed(count(2), kind!(Tok::Ident(_)).take0more().captured());
+    let err = parse_at(list, input, 0).map(|_| ()).unwrap_err();
+    assert_eq!((err.0, &*err.1), (2..7, "expected 4 more tokens"));
+}
+
+#[test]
+fn inner_parsers() {
+    let input = SpannedTokens::new(&TOKENS, &SPANS);
+    let group = || kind!(Tok::Num(_) | Tok::Ident(_)).take1more();
+
+    let inner = group().and_parse_inner(kind!(Tok::Ident(_)).take0more().lift(|_| ()));
+    assert_eq!(parse_at(inner, input, 1).unwrap().1, 4);
+
+    // Spans of inner errors are in the units of the source, not of tokens.
+    let inner = group().and_parse_inner(Ref(kind!(Tok::Ident(_))).captured());
+    let err = parse_at(inner, input, 0).unwrap_err();
+    assert_eq!(
+        (err.0, &*err.1),
+        (0..1, "expected a single token matches Tok::Ident(_)")
+    );
+    let inner = group().and_parse_inner(Ref(kind!(Tok::Ident(_))).captured());
+    let err = parse_at(inner, input, 1).unwrap_err();
+    assert_eq!((err.0, &*err.1), (4..4, "expected end of input"));
+}
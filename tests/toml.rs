@@ -0,0 +1,209 @@
+//! Runs the samples under `tests/toml`, see the README there.
+
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::*;
+use kaparser::{
+    grammars::{datetime::*, json, toml::*},
+    parser::*,
+    pattern::*,
+};
+use std::{
+    borrow::Cow,
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn parse(text: &'static str) -> Result<Table<'static>, Failed> {
+    Parser::<&str, Table, Ext>::fullmatch(&toml(), text).into_result()
+}
+
+fn tagged(item: &Item<'_>) -> json::Value<'static> {
+    let tag = |ty: &str, value: String| {
+        json::Value::Object(vec![
+            ("type".into(), json::Value::String(ty.to_owned().into())),
+            ("value".into(), json::Value::String(value.into())),
+        ])
+    };
+    match &item.value {
+        Value::String(s) => tag("string", s.to_string()),
+        Value::Integer(i) => tag("integer", i.to_string()),
+        Value::Float(f) => tag("float", f.to_string()),
+        Value::Boolean(b) => tag("bool", b.to_string()),
+        Value::Datetime(dt) => {
+            let ty = match dt.kind {
+                DatetimeKind::OffsetDateTime => "datetime",
+                DatetimeKind::LocalDateTime => "datetime-local",
+                DatetimeKind::LocalDate => "date-local",
+                DatetimeKind::LocalTime => "time-local",
+            };
+            tag(ty, dt.text.to_owned())
+        }
+        Value::Array(array) => json::Value::Array(array.items.iter().map(tagged).collect()),
+        Value::Table(table) => table_tagged(table),
+    }
+}
+
+fn table_tagged(table: &Table<'_>) -> json::Value<'static> {
+    json::Value::Object(
+        table
+            .iter()
+            .map(|(name, item)| (Cow::Owned(name.to_owned()), tagged(item)))
+            .collect(),
+    )
+}
+
+fn full<'a, P, O>(pattern: P, text: &'a str) -> O
+where
+    P: Pattern<'a, &'a str, Ext, View<'a> = O>,
+{
+    pattern.captured().fullmatch(text).into_result().unwrap()
+}
+
+/// Writes the scalar in the same way whichever side it comes from, as toml-test compares them:
+/// numbers by value, offset date-times as instants, and local date-times in the `T` and `Z` form
+/// without trailing zeros of the fraction.
+fn canonical(ty: &str, value: &str) -> Option<String> {
+    let value = &*value.replace([' ', 't'], "T").replace('z', "Z");
+    Some(match ty {
+        "integer" => value.parse::<i64>().unwrap().to_string(),
+        // `-0.0` equals `0.0` as well.
+        "float" => (value.parse::<f64>().unwrap() + 0.0).to_string(),
+        "datetime" => {
+            let dt = full(rfc3339(), value);
+            let secs = dt.unix_timestamp().unwrap();
+            let utc = DateTime {
+                date: Date::from_days_since_epoch(secs.div_euclid(86400)),
+                time: Time {
+                    hour: (secs.rem_euclid(86400) / 3600) as u8,
+                    minute: (secs.rem_euclid(3600) / 60) as u8,
+                    second: secs.rem_euclid(60) as u8,
+                    nanosecond: dt.time.nanosecond,
+                },
+                offset: Some(Offset::UTC),
+            };
+            utc.to_string()
+        }
+        "datetime-local" => full(rfc3339().local(), value).to_string(),
+        "date-local" => full(date(), value).to_string(),
+        "time-local" => full(time(), value).to_string(),
+        _ => return None,
+    })
+}
+
+/// Members of objects are compared regardless of the order.
+fn normalize(value: &mut json::Value<'_>) {
+    match value {
+        json::Value::Array(items) => items.iter_mut().for_each(normalize),
+        json::Value::Object(members) => {
+            members.sort_by(|a, b| a.0.cmp(&b.0));
+            if let [(ty, json::Value::String(ty_name)), (val, json::Value::String(s))] = &mut members[..]
+                && ty == "type"
+                && val == "value"
+            {
+                if let Some(canonical) = canonical(ty_name, s) {
+                    *s = canonical.into();
+                }
+                return;
+            }
+            members.iter_mut().for_each(|(_, value)| normalize(value));
+        }
+        _ => (),
+    }
+}
+
+/// Samples with the extension under the directory and its subdirectories, by their relative paths.
+fn samples(dir: &str, ext: &str) -> Vec<(String, &'static [u8])> {
+    fn walk(dir: &Path, ext: &str, samples: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                walk(&path, ext, samples);
+            } else if path.extension().is_some_and(|e| e == ext) {
+                samples.push(path);
+            }
+        }
+    }
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/toml").join(dir);
+    let mut paths = Vec::new();
+    walk(&root, ext, &mut paths);
+    let mut samples = paths
+        .into_iter()
+        .map(|path| {
+            let bytes = fs::read(&path).unwrap().leak() as &'static [u8];
+            let name = path.strip_prefix(&root).unwrap().with_extension("");
+            (name.to_string_lossy().into_owned(), bytes)
+        })
+        .collect::<Vec<_>>();
+    samples.sort();
+    assert!(!samples.is_empty());
+    samples
+}
+
+#[test]
+fn valid() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/toml/valid");
+    for (name, bytes) in samples("valid", "toml") {
+        let text = std::str::from_utf8(bytes).unwrap();
+        let table = parse(text).unwrap_or_else(|e| panic!("{name}: {e:?}"));
+
+        let expected = fs::read_to_string(dir.join(format!("{name}.json"))).unwrap().leak();
+        let mut expected = Parser::<&str, json::Value, Ext>::fullmatch(&json::json(), expected)
+            .into_result()
+            .unwrap_or_else(|e| panic!("{name}.json: {e:?}"));
+        let mut actual = table_tagged(&table);
+        normalize(&mut expected);
+        normalize(&mut actual);
+        assert_eq!(actual, expected, "{name}");
+    }
+}
+
+#[test]
+fn invalid() {
+    for (name, bytes) in samples("invalid", "toml") {
+        let res = Parser::<&[u8], Table, Ext>::fullmatch(&toml(), bytes).into_result();
+        assert!(res.is_err(), "{name}: {res:?}");
+    }
+}
+
+#[test]
+fn spans() {
+    let text = "[server]\nhost = \"example.com\"\nports = [80, 443]\n";
+    let table = parse(text).unwrap();
+    let server = table.get("server").unwrap();
+    assert_eq!(&text[server.span.clone()], "[server]");
+    let host = server.get("host").unwrap();
+    assert_eq!(&text[host.span.clone()], "\"example.com\"");
+    let ports = server.get("ports").unwrap().as_array().unwrap();
+    assert_eq!(&text[ports[1].span.clone()], "443");
+    let names = server
+        .as_table()
+        .unwrap()
+        .entries()
+        .iter()
+        .map(|(key, _)| &text[key.span.clone()]);
+    assert_eq!(names.collect::<Vec<_>>(), ["host", "ports"]);
+
+    let err = parse("a = 1\nb = 2\na = 3\n").unwrap_err();
+    assert_eq!((err.0, &*err.1), (12..13, "error: duplicate key"));
+    let err = parse("a = \"\\q\"\n").unwrap_err();
+    assert_eq!((err.0, &*err.1), (5..7, "invalid input"));
+
+    for (text, span, message) in [
+        ("n = -01\n", 5..7, "invalid input"),
+        (
+            "n = 0x1_0000_0000_0000_0000\n",
+            6..27,
+            "number too large to fit in target type",
+        ),
+        ("t = 07:32\n", 9..9, "expected `:`"),
+        ("t = 07:32:00,5\n", 12..14, "invalid input"),
+        ("d = 2024-02-30\n", 12..14, "error: day exceeds the days in the month"),
+    ] {
+        let err = parse(text).unwrap_err();
+        assert_eq!((err.0, &*err.1), (span, message), "{text}");
+    }
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::*;
+use kaparser::{
+    input::{Input, InputSlice, utf8::*},
+    parser::*,
+    pattern::*,
+    predicate::*,
+};
+
+/// The size of chunks validated at once.
+const CHUNK: usize = 4096;
+
+/// `lift` needs `'static` bytes.
+fn leak(parts: &[&[u8]]) -> &'static [u8] {
+    parts.concat().leak()
+}
+
+fn parse<P, O>(parser: P, mut input: Utf8Input<'static>) -> Result<(O, usize), Failed>
+where
+    P: Parser<'static, Utf8Input<'static>, O, Ext>,
+{
+    let start = input.begin();
+    let res = parser.parse(&mut input, start).into_result();
+    res.map(|(out, cur)| (out, cur.pos()))
+}
+
+fn fetch(input: &mut Utf8Input, pos: usize) -> Result<(String, bool), Failed> {
+    let start = Utf8Input::bump_cursor(input.begin(), pos);
+    input.fetch_slice(start).map(|(slice, eof)| (slice.to_owned(), eof))
+}
+
+#[test]
+fn window_growth() {
+    let bytes = leak(&[&[b'a'; 3 * CHUNK + 10]]);
+    let mut input = Utf8Input::strict(bytes);
+
+    // Each fetch from the same start validates one more chunk.
+    for (len, eof) in [
+        (CHUNK, false),
+        (2 * CHUNK, false),
+        (3 * CHUNK, false),
+        (3 * CHUNK + 10, true),
+    ] {
+        let (slice, end) = fetch(&mut input, 0).unwrap();
+        assert_eq!((slice.len(), end), (len, eof));
+    }
+    let (slice, eof) = fetch(&mut input, 100).unwrap();
+    assert_eq!((slice.len(), eof), (CHUNK, false));
+
+    let run = 'a'.take0more().lift(|_| ());
+    assert_eq!(parse(run, Utf8Input::strict(bytes)).unwrap().1, bytes.len());
+
+    // A character straddling the window is left to the next one.
+    let bytes = leak(&[&[b'a'; CHUNK - 1], "é".as_bytes(), b"z"]);
+    let mut input = Utf8Input::strict(bytes);
+    assert_eq!(fetch(&mut input, 0).unwrap().0.len(), CHUNK - 1);
+    assert_eq!(
+        fetch(&mut input, 0).unwrap(),
+        (std::str::from_utf8(bytes).unwrap().into(), true)
+    );
+    let run = ('a', 'é').take0more().lift(|_| ());
+    assert_eq!(parse(run, Utf8Input::strict(bytes)).unwrap().1, CHUNK + 1);
+}
+
+#[test]
+fn strict() {
+    let input = |bytes| Utf8Input::strict(leak(&[bytes]));
+    let err = parse(Predicate::<char>::take0more(ANY).lift(|_| ()), input(b"ok\xff\xfe")).unwrap_err();
+    assert_eq!((err.0, &*err.1), (2..3, "invalid input"));
+    let err = parse(['a'].lift(|c| c), input(b"\xe2\x28\xa1")).unwrap_err();
+    assert_eq!((err.0, &*err.1), (0..1, "invalid input"));
+
+    // Truncated at the end of input, or by an unexpected byte.
+    let err = parse(Predicate::<char>::take0more(ANY).lift(|_| ()), input(b"ab\xe2\x82")).unwrap_err();
+    assert_eq!(err.0, 2..4);
+    let err = parse(Predicate::<char>::take0more(ANY).lift(|_| ()), input(b"\xf0\x9f\x98z")).unwrap_err();
+    assert_eq!(err.0, 0..3);
+
+    // Valid text before an invalid sequence is matched without looking at it.
+    assert_eq!(parse('o'.take1more().lift(|_| ()), input(b"oo!\xff")).unwrap().1, 2);
+    assert_eq!(parse(['€'].lift(|c| c), input("€".as_bytes())).unwrap(), ('€', 3));
+}
+
+#[test]
+fn strict_across_chunks() {
+    // The invalid sequence `e2 82 28` starts in the first window and ends in the second.
+    let bytes = leak(&[&[b'a'; CHUNK - 1], b"\xe2\x82\x28", b"rest"]);
+    let mut input = Utf8Input::strict(bytes);
+    assert_eq!(fetch(&mut input, 0).unwrap(), ("a".repeat(CHUNK - 1), false));
+    assert_eq!(fetch(&mut input, 0).unwrap(), ("a".repeat(CHUNK - 1), false));
+    assert_eq!(fetch(&mut input, 0).unwrap_err().0, CHUNK - 1..CHUNK + 1);
+
+    let err = parse('a'.take0more().lift(|_| ()), Utf8Input::strict(bytes)).unwrap_err();
+    assert_eq!((err.0, &*err.1), (CHUNK - 1..CHUNK + 1, "invalid input"));
+    let err = parse(Predicate::<char>::take0more(ANY).lift(|_| ()), Utf8Input::strict(bytes)).unwrap_err();
+    assert_eq!(err.0, CHUNK - 1..CHUNK + 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn lossy() {
+    let bytes = leak(&[b"a\xff\xfeb\xe2\x82"]);
+    let mut input = Utf8Input::lossy(bytes);
+    assert_eq!(
+        fetch(&mut input, 0).unwrap(),
+        ("a\u{fffd}\u{fffd}b\u{fffd}".into(), true)
+    );
+
+    // Tokens and cursors step over whole invalid sequences.
+    let mut cursor = input.begin();
+    let mut tokens = vec![];
+    while let Some(ch) = input.next_maybe_ref::<Failed>(&mut cursor).unwrap() {
+        tokens.push((ch, cursor.pos()));
+    }
+    assert_eq!(
+        tokens,
+        [('a', 1), ('\u{fffd}', 2), ('\u{fffd}', 3), ('b', 4), ('\u{fffd}', 6)]
+    );
+
+    let run = except('b').take0more().lift(|_| ());
+    assert_eq!(parse(run, Utf8Input::lossy(bytes)).unwrap().1, 3);
+    let run = Predicate::<char>::take0more(ANY).lift(|_| ());
+    assert_eq!(parse(run, Utf8Input::lossy(bytes)).unwrap().1, 6);
+    assert_eq!(
+        parse(['\u{fffd}'].lift(|c| c), Utf8Input::lossy(&bytes[1..])).unwrap(),
+        ('\u{fffd}', 1)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn lossy_cursor_mapping() {
+    let bytes = leak(&[b"a\xff\xfeb\xf0\x9f\x98\x80c"]);
+    let begin = Utf8Input::lossy(bytes).begin();
+
+    // Each U+FFFD takes 3 bytes in the decoded slice, but 1 byte here in the input.
+    for (length, pos) in [(0, 0), (1, 1), (4, 2), (7, 3), (8, 4), (12, 8), (13, 9), (100, 9)] {
+        assert_eq!(Utf8Input::bump_cursor(begin.clone(), length).pos(), pos, "{length}");
+    }
+
+    // Valid slices are mapped byte for byte.
+    let after = Utf8Input::bump_cursor(begin.clone(), 8);
+    assert_eq!(Utf8Input::bump_cursor(after, 4).pos(), 8);
+
+    let mut input = Utf8Input::lossy(bytes);
+    let end = Utf8Input::bump_cursor(begin.clone(), 13);
+    assert_eq!(input.release_slice(begin..end), "a\u{fffd}\u{fffd}b😀c");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn lossy_across_chunks() {
+    // A truncated sequence `e2 82` at the boundary of the first window.
+    let bytes = leak(&[&[b'a'; CHUNK - 1], b"\xe2\x82", b"zz"]);
+    let mut input = Utf8Input::lossy(bytes);
+    assert_eq!(fetch(&mut input, 0).unwrap(), ("a".repeat(CHUNK - 1), false));
+    let expected = format!("{}\u{fffd}zz", "a".repeat(CHUNK - 1));
+    assert_eq!(fetch(&mut input, 0).unwrap(), (expected, true));
+
+    let run = 'a'.take0more().lift(|_| ());
+    assert_eq!(parse(run, Utf8Input::lossy(bytes)).unwrap().1, CHUNK - 1);
+    let run = except('z').take0more().lift(|_| ());
+    assert_eq!(parse(run, Utf8Input::lossy(bytes)).unwrap().1, CHUNK + 1);
+    let run = Predicate::<char>::take0more(ANY).lift(|_| ());
+    assert_eq!(parse(run, Utf8Input::lossy(bytes)).unwrap().1, CHUNK + 3);
+
+    // Invalid bytes in every window.
+    let bytes = leak(&[&b"\xffa".repeat(CHUNK + 1)]);
+    let run = ('a', '\u{fffd}').take0more().lift(|_| ());
+    assert_eq!(parse(run, Utf8Input::lossy(bytes)).unwrap().1, bytes.len());
+}